@@ -3,7 +3,7 @@ extern crate kdtree;
 extern crate rand;
 extern crate test;
 
-use kdtree::distance::squared_euclidean;
+use kdtree::distance::{squared_euclidean, squared_euclidean_simd};
 use kdtree::KdTree;
 use test::Bencher;
 
@@ -40,3 +40,25 @@ fn bench_nearest_from_kdtree_with_1k_3d_points(b: &mut Bencher) {
     }
     b.iter(|| kdtree.nearest(&point.0, 8, &squared_euclidean).unwrap());
 }
+
+#[bench]
+fn bench_nearest_from_kdtree_with_1k_32d_points(b: &mut Bencher) {
+    let len = 1000usize;
+    let point: [f64; 32] = rand::random();
+    let mut kdtree = KdTree::with_capacity(32, 64);
+    for i in 0..len {
+        kdtree.add(rand::random::<[f64; 32]>(), i).unwrap();
+    }
+    b.iter(|| kdtree.nearest(&point, 8, &squared_euclidean).unwrap());
+}
+
+#[bench]
+fn bench_nearest_simd_from_kdtree_with_1k_32d_points(b: &mut Bencher) {
+    let len = 1000usize;
+    let point: [f64; 32] = rand::random();
+    let mut kdtree = KdTree::with_capacity(32, 64);
+    for i in 0..len {
+        kdtree.add(rand::random::<[f64; 32]>(), i).unwrap();
+    }
+    b.iter(|| kdtree.nearest(&point, 8, &squared_euclidean_simd).unwrap());
+}
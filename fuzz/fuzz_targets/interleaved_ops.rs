@@ -0,0 +1,102 @@
+//! Applies random interleavings of mutations and queries to a tree and cross-checks every result
+//! against a naive shadow implementation.
+//!
+//! Coordinates are small integers so that duplicates and ties are frequent.
+#![no_main]
+
+use arbitrary::Arbitrary;
+use kdtree::distance::squared_euclidean;
+use kdtree::KdTree;
+use libfuzzer_sys::fuzz_target;
+
+type Point = [f64; 2];
+
+#[derive(Arbitrary, Debug)]
+enum Op {
+    Add { x: i8, y: i8, data: u8 },
+    Remove { x: i8, y: i8, data: u8 },
+    Update { x: i8, y: i8, data: u8, new_x: i8, new_y: i8 },
+    Nearest { x: i8, y: i8, k: u8 },
+    Within { x: i8, y: i8, radius: u8 },
+}
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+    capacity: u8,
+    ops: Vec<Op>,
+}
+
+fn point(x: i8, y: i8) -> Point {
+    [f64::from(x), f64::from(y)]
+}
+
+fn remove(tree: &mut KdTree<f64, u8, Point>, shadow: &mut Vec<(Point, u8)>, p: Point, data: u8) {
+    let removed = tree.remove(&p, &data).unwrap();
+    let before = shadow.len();
+    shadow.retain(|(q, d)| !(*q == p && *d == data));
+    assert_eq!(removed, before - shadow.len());
+}
+
+fn sorted_distances(p: &Point, shadow: &[(Point, u8)]) -> Vec<f64> {
+    let mut distances: Vec<f64> = shadow.iter().map(|(q, _)| squared_euclidean(p, q)).collect();
+    distances.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    distances
+}
+
+fuzz_target!(|input: Input| {
+    let capacity = usize::from(input.capacity % 8) + 1;
+    let mut tree: KdTree<f64, u8, Point> = KdTree::with_capacity(2, capacity);
+    let mut shadow: Vec<(Point, u8)> = vec![];
+
+    for op in input.ops {
+        match op {
+            Op::Add { x, y, data } => {
+                tree.add(point(x, y), data).unwrap();
+                shadow.push((point(x, y), data));
+            }
+            Op::Remove { x, y, data } => remove(&mut tree, &mut shadow, point(x, y), data),
+            Op::Update {
+                x,
+                y,
+                data,
+                new_x,
+                new_y,
+            } => {
+                let before = shadow.len();
+                remove(&mut tree, &mut shadow, point(x, y), data);
+                for _ in shadow.len()..before {
+                    tree.add(point(new_x, new_y), data).unwrap();
+                    shadow.push((point(new_x, new_y), data));
+                }
+            }
+            Op::Nearest { x, y, k } => {
+                let p = point(x, y);
+                let k = usize::from(k);
+                let found: Vec<f64> = tree
+                    .nearest(&p, k, &squared_euclidean)
+                    .unwrap()
+                    .into_iter()
+                    .map(|(d, _)| d)
+                    .collect();
+                let expected: Vec<f64> = sorted_distances(&p, &shadow).into_iter().take(k).collect();
+                assert_eq!(found, expected);
+            }
+            Op::Within { x, y, radius } => {
+                let p = point(x, y);
+                let radius = f64::from(radius);
+                let found: Vec<f64> = tree
+                    .within(&p, radius, &squared_euclidean)
+                    .unwrap()
+                    .into_iter()
+                    .map(|(d, _)| d)
+                    .collect();
+                let expected: Vec<f64> = sorted_distances(&p, &shadow)
+                    .into_iter()
+                    .filter(|d| *d <= radius)
+                    .collect();
+                assert_eq!(found, expected);
+            }
+        }
+        assert_eq!(tree.size(), shadow.len());
+    }
+});
@@ -0,0 +1,251 @@
+//! A small HTTP server answering queries against a tree loaded from a file.
+//!
+//! The file is one of
+//!
+//! - a snapshot, as written by `KdTree::to_snapshot_string`,
+//! - a serialized tree, as written by `serde_json` for a `KdTree<f64, Value, Vec<f64>>`,
+//! - a JSON array of `[[x, y, ...], payload]` pairs, bulk loaded into a new tree.
+//!
+//! Queries are answered by a `KdTreeService`, so concurrent requests get batched onto its worker
+//! threads, and reloading the file swaps in a new snapshot without interrupting them.
+//!
+//! ```text
+//! GET  /stats
+//! GET  /nearest?k=<k>&point=<x,y,...>
+//! GET  /within?radius=<radius>&point=<x,y,...>
+//! POST /reload
+//! ```
+//!
+//! Responses are JSON. Distances are squared euclidean.
+#[macro_use]
+extern crate serde_json;
+extern crate kdtree;
+
+use std::fs;
+use std::future::Future;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake};
+use std::thread::{self, Thread};
+
+use kdtree::distance::squared_euclidean;
+use kdtree::service::KdTreeService;
+use kdtree::{KdTree, OwnedNeighbor};
+use serde_json::Value;
+
+pub type Tree = KdTree<f64, Value, Vec<f64>>;
+type Distance = fn(&[f64], &[f64]) -> f64;
+type Service = KdTreeService<f64, Value, Vec<f64>, Distance>;
+
+/// Capacity of leaves when building a tree from a list of points.
+const CAPACITY: usize = 16;
+
+/// Load a tree from a snapshot, a serialized tree or a list of points, see the crate docs.
+pub fn load(path: &Path) -> Result<Tree, String> {
+    let contents = fs::read_to_string(path).map_err(|e| format!("cannot read `{}`: {}", path.display(), e))?;
+    if contents.starts_with("kdtree snapshot") {
+        return Tree::from_snapshot_str(&contents).map_err(|e| e.to_string());
+    }
+    let value: Value =
+        serde_json::from_str(&contents).map_err(|e| format!("cannot parse `{}`: {}", path.display(), e))?;
+    match value {
+        Value::Array(entries) => {
+            let mut points = Vec::with_capacity(entries.len());
+            for (i, entry) in entries.into_iter().enumerate() {
+                points.push(serde_json::from_value(entry).map_err(|e| format!("invalid point {}: {}", i, e))?);
+            }
+            Tree::bulk_load(0, CAPACITY, points).map_err(|e| e.to_string())
+        }
+        value => serde_json::from_value(value).map_err(|e| format!("cannot parse `{}`: {}", path.display(), e)),
+    }
+}
+
+/// A tree loaded from a file, served over HTTP.
+pub struct Server {
+    path: PathBuf,
+    service: Service,
+}
+
+impl Server {
+    /// Load the tree at `path` and start `workers` threads answering queries.
+    pub fn open<P: Into<PathBuf>>(path: P, workers: usize) -> Result<Self, String> {
+        let path = path.into();
+        let tree = load(&path)?;
+        Ok(Server {
+            path,
+            service: KdTreeService::new(tree, squared_euclidean as Distance, workers, 64),
+        })
+    }
+
+    /// Load the file again and answer all further queries with it, returning its size.
+    ///
+    /// If the file cannot be loaded, the previous tree stays in place.
+    pub fn reload(&self) -> Result<usize, String> {
+        let tree = load(&self.path)?;
+        let size = tree.size();
+        self.service.swap(tree);
+        Ok(size)
+    }
+
+    /// Answer a request, returning the status code and the JSON body.
+    pub fn respond(&self, method: &str, target: &str) -> (u16, Value) {
+        let (path, query) = match target.find('?') {
+            Some(i) => (&target[..i], &target[i + 1..]),
+            None => (target, ""),
+        };
+        let result = match (method, path) {
+            ("GET", "/stats") => Ok(self.stats()),
+            ("GET", "/nearest") => self.nearest(query),
+            ("GET", "/within") => self.within(query),
+            ("POST", "/reload") => self.reload().map(|size| json!({ "size": size })),
+            _ => return (404, json!({ "error": "not found" })),
+        };
+        match result {
+            Ok(body) => (200, body),
+            Err(message) => (400, json!({ "error": message })),
+        }
+    }
+
+    fn stats(&self) -> Value {
+        let stats = self.service.tree().stats();
+        json!({
+            "size": stats.size,
+            "dimensions": stats.dimensions,
+            "capacity": stats.capacity,
+            "depth": stats.depth,
+            "leaves": stats.leaves,
+            "unsplittable_leaves": stats.unsplittable_leaves,
+        })
+    }
+
+    fn nearest(&self, query: &str) -> Result<Value, String> {
+        let k = parameter(query, "k")?;
+        let k = k.parse::<usize>().map_err(|e| format!("invalid k `{}`: {}", k, e))?;
+        let point = parse_point(parameter(query, "point")?)?;
+        neighbors(block_on(self.service.nearest(point, k)))
+    }
+
+    fn within(&self, query: &str) -> Result<Value, String> {
+        let radius = parameter(query, "radius")?;
+        let radius = radius
+            .parse::<f64>()
+            .map_err(|e| format!("invalid radius `{}`: {}", radius, e))?;
+        let point = parse_point(parameter(query, "point")?)?;
+        neighbors(block_on(self.service.within(point, radius)))
+    }
+
+    /// Answer a single HTTP request on `stream`.
+    pub fn handle(&self, stream: TcpStream) -> io::Result<()> {
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line)?;
+        // skip the headers, requests have no body
+        let mut header = String::new();
+        while reader.read_line(&mut header)? > 2 {
+            header.clear();
+        }
+        let mut parts = request_line.split_whitespace();
+        let (status, body) = match (parts.next(), parts.next()) {
+            (Some(method), Some(target)) => self.respond(method, target),
+            _ => (400, json!({ "error": "malformed request" })),
+        };
+        let body = body.to_string();
+        let reason = match status {
+            200 => "OK",
+            404 => "Not Found",
+            _ => "Bad Request",
+        };
+        let mut stream = stream;
+        write!(
+            stream,
+            "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            reason,
+            body.len(),
+            body
+        )?;
+        stream.flush()
+    }
+
+    /// Accept connections on `listener` forever, handling each on its own thread.
+    ///
+    /// Failing to accept a connection, e.g. when running out of file descriptors, is logged and
+    /// does not stop the server.
+    pub fn serve(self: Arc<Self>, listener: TcpListener) -> io::Result<()> {
+        for stream in listener.incoming() {
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(e) => {
+                    eprintln!("error accepting connection: {}", e);
+                    continue;
+                }
+            };
+            let server = Arc::clone(&self);
+            thread::spawn(move || {
+                if let Err(e) = server.handle(stream) {
+                    eprintln!("error: {}", e);
+                }
+            });
+        }
+        Ok(())
+    }
+}
+
+fn parameter<'a>(query: &'a str, name: &str) -> Result<&'a str, String> {
+    query
+        .split('&')
+        .filter_map(|pair| {
+            let mut pair = pair.splitn(2, '=');
+            Some((pair.next()?, pair.next()?))
+        })
+        .find(|&(key, _)| key == name)
+        .map(|(_, value)| value)
+        .ok_or_else(|| format!("missing parameter `{}`", name))
+}
+
+fn parse_point(point: &str) -> Result<Vec<f64>, String> {
+    point
+        .replace("%2C", ",")
+        .replace("%2c", ",")
+        .split(',')
+        .map(|x| {
+            x.trim()
+                .parse::<f64>()
+                .map_err(|e| format!("invalid coordinate `{}`: {}", x, e))
+        })
+        .collect()
+}
+
+fn neighbors(found: Result<Vec<OwnedNeighbor<f64, Value, Vec<f64>>>, kdtree::ErrorKind>) -> Result<Value, String> {
+    let found = found.map_err(|e| e.to_string())?;
+    Ok(Value::Array(
+        found
+            .iter()
+            .map(|n| json!({ "distance": n.distance(), "point": n.point(), "data": n.data() }))
+            .collect(),
+    ))
+}
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Wait for `future` on the current thread; connection threads have nothing else to do.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let waker = Arc::new(ThreadWaker(thread::current())).into();
+    let mut cx = Context::from_waker(&waker);
+    let mut future: Pin<Box<F>> = Box::pin(future);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
@@ -0,0 +1,62 @@
+//! Serve a tree over HTTP, see the library docs for the endpoints.
+//!
+//! ```text
+//! kdtree-server <tree-file> [address] [workers]
+//! ```
+//!
+//! On unix, `SIGHUP` reloads the tree file, like `POST /reload`.
+extern crate kdtree_server;
+#[cfg(unix)]
+extern crate signal_hook;
+
+use std::env;
+use std::net::TcpListener;
+use std::process;
+use std::sync::Arc;
+
+use kdtree_server::Server;
+
+const USAGE: &str = "usage: kdtree-server <tree-file> [address] [workers]";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if let Err(message) = run(&args) {
+        eprintln!("error: {}", message);
+        eprintln!("{}", USAGE);
+        process::exit(1);
+    }
+}
+
+fn run(args: &[String]) -> Result<(), String> {
+    let path = args.first().ok_or("missing tree file")?;
+    let address = args.get(1).map_or("127.0.0.1:7878", String::as_str);
+    let workers = match args.get(2) {
+        Some(workers) => workers
+            .parse::<usize>()
+            .map_err(|e| format!("invalid workers `{}`: {}", workers, e))?,
+        None => 4,
+    };
+    let server = Arc::new(Server::open(path, workers)?);
+    #[cfg(unix)]
+    reload_on_sighup(Arc::clone(&server))?;
+    let listener = TcpListener::bind(address).map_err(|e| format!("cannot bind `{}`: {}", address, e))?;
+    eprintln!("serving `{}` on {}", path, address);
+    server.serve(listener).map_err(|e| e.to_string())
+}
+
+#[cfg(unix)]
+fn reload_on_sighup(server: Arc<Server>) -> Result<(), String> {
+    use signal_hook::consts::SIGHUP;
+    use signal_hook::iterator::Signals;
+
+    let mut signals = Signals::new([SIGHUP]).map_err(|e| e.to_string())?;
+    std::thread::spawn(move || {
+        for _ in signals.forever() {
+            match server.reload() {
+                Ok(size) => eprintln!("reloaded {} points", size),
+                Err(message) => eprintln!("error: reload failed, keeping the previous tree: {}", message),
+            }
+        }
+    });
+    Ok(())
+}
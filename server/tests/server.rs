@@ -0,0 +1,89 @@
+#[macro_use]
+extern crate serde_json;
+extern crate kdtree;
+extern crate kdtree_server;
+
+use std::env;
+use std::fs;
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::thread;
+
+use kdtree_server::{load, Server};
+
+fn temp_file(name: &str, contents: &str) -> PathBuf {
+    let path = env::temp_dir().join(format!("kdtree-server-{}-{}", std::process::id(), name));
+    fs::write(&path, contents).unwrap();
+    path
+}
+
+#[test]
+fn it_answers_queries_and_reloads() {
+    let path = temp_file("points.json", r#"[[[0, 0], "a"], [[1, 1], "b"], [[3, 3], "c"]]"#);
+    let server = Server::open(&path, 2).unwrap();
+
+    let (status, body) = server.respond("GET", "/nearest?k=2&point=0.5,0");
+    assert_eq!(status, 200);
+    assert_eq!(
+        body,
+        json!([
+            { "distance": 0.25, "point": [0.0, 0.0], "data": "a" },
+            { "distance": 1.25, "point": [1.0, 1.0], "data": "b" },
+        ])
+    );
+    let (status, body) = server.respond("GET", "/within?radius=8&point=1%2C1");
+    assert_eq!(status, 200);
+    assert_eq!(body.as_array().unwrap().len(), 3);
+    assert_eq!(server.respond("GET", "/stats").1["size"], json!(3));
+
+    assert_eq!(server.respond("GET", "/nearest?point=0,0").0, 400);
+    assert_eq!(server.respond("GET", "/nearest?k=1&point=0").0, 400);
+    assert_eq!(server.respond("GET", "/unknown").0, 404);
+
+    fs::write(&path, r#"[[[5, 5], "d"]]"#).unwrap();
+    assert_eq!(server.respond("POST", "/reload"), (200, json!({ "size": 1 })));
+    assert_eq!(server.respond("GET", "/nearest?k=5&point=0,0").1[0]["data"], json!("d"));
+
+    fs::write(&path, "not json").unwrap();
+    assert_eq!(server.respond("POST", "/reload").0, 400);
+    assert_eq!(server.respond("GET", "/stats").1["size"], json!(1));
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn it_loads_snapshots_and_serialized_trees() {
+    let points = temp_file("source.json", r#"[[[0, 0], {"id": 1}], [[2, 2], {"id": 2}]]"#);
+    let tree = load(&points).unwrap();
+
+    let snapshot = temp_file("tree.snapshot", &tree.to_snapshot_string());
+    let serialized = temp_file("tree.json", &serde_json::to_string(&tree).unwrap());
+    for path in &[&snapshot, &serialized] {
+        let server = Server::open(path, 1).unwrap();
+        assert_eq!(
+            server.respond("GET", "/nearest?k=1&point=2,1.5").1,
+            json!([{ "distance": 0.25, "point": [2.0, 2.0], "data": { "id": 2 } }])
+        );
+    }
+    for path in &[points, snapshot, serialized] {
+        fs::remove_file(path).unwrap();
+    }
+}
+
+#[test]
+fn it_serves_http() {
+    let path = temp_file("http.json", r#"[[[0], 1], [[10], 2]]"#);
+    let server = Arc::new(Server::open(&path, 1).unwrap());
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    thread::spawn(move || server.serve(listener));
+
+    let mut stream = TcpStream::connect(address).unwrap();
+    write!(stream, "GET /nearest?k=1&point=9 HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(response.ends_with(r#"[{"data":2,"distance":1.0,"point":[10.0]}]"#));
+    fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,96 @@
+//! Coordinates that are not floats, like integer grids and fixed-point numbers.
+//!
+//! [`KdTree`](crate::KdTree) is generic over any coordinate type implementing [`Axis`], which
+//! covers the floats, the signed integers and every other signed numeric type implementing the
+//! `num_traits` arithmetic and casts. Adding, removing, [`nearest`](crate::KdTree::nearest),
+//! [`within`](crate::KdTree::within), [`within_box`](crate::KdTree::within_box), iterating and
+//! the other queries of the core tree take these coordinates directly, and distances are computed
+//! in the coordinate type as well:
+//!
+//! ```
+//! use kdtree::distance::squared_euclidean;
+//! use kdtree::KdTree;
+//!
+//! let mut kdtree: KdTree<i32, &str, [i32; 3]> = KdTree::new(3);
+//! kdtree.add([1, -4, 7], "a").unwrap();
+//! kdtree.add([2, 2, 2], "b").unwrap();
+//! assert_eq!(kdtree.nearest(&[2, 1, 2], 1, &squared_euclidean).unwrap(), vec![(1, &"b")]);
+//! ```
+//!
+//! Methods that need fractions or infinities, like [`approx_nearest`](crate::KdTree::approx_nearest),
+//! [`coordinate_stats`](crate::KdTree::coordinate_stats) and the float distances, stay limited to
+//! [`Float`](num_traits::Float) coordinates. Unsigned integers are not coordinates, as the
+//! difference of two of them is not always representable. Integer distances overflow like any
+//! other integer arithmetic, so squared euclidean distances of `i32` coordinates are only safe
+//! for points less than about 32000 apart; store `i64` coordinates for larger grids.
+
+use std::ops::Neg;
+
+use num_traits::{Num, NumCast};
+
+/// A coordinate type of a [`KdTree`](crate::KdTree), implemented for every signed numeric type.
+pub trait Axis: Num + NumCast + Copy + PartialOrd + Neg<Output = Self> {
+    /// A value no coordinate is above, infinity for floats and the largest value otherwise. Empty
+    /// nodes have it as their lower bounds.
+    fn greatest() -> Self;
+
+    /// A value no coordinate is below, negative infinity for floats and the smallest value
+    /// otherwise.
+    fn least() -> Self;
+
+    /// Whether the value can be stored as a coordinate, that is, is neither NaN nor infinite.
+    fn is_regular(self) -> bool;
+
+    /// A value from `min` to `max`, both inclusive, halfway between them as far as the type
+    /// allows, without overflowing.
+    fn midpoint(min: Self, max: Self) -> Self;
+
+    /// How far apart `min` and `max` are, as a float that does not overflow.
+    fn spread(min: Self, max: Self) -> f64;
+}
+
+impl<A: Num + NumCast + Copy + PartialOrd + Neg<Output = Self>> Axis for A {
+    fn greatest() -> Self {
+        A::from(f64::INFINITY)
+            .or_else(|| A::from(i128::MAX))
+            .or_else(|| A::from(i64::MAX))
+            .or_else(|| A::from(i32::MAX))
+            .or_else(|| A::from(i16::MAX))
+            .or_else(|| A::from(i8::MAX))
+            .expect("coordinate type cannot represent i8::MAX")
+    }
+
+    fn least() -> Self {
+        A::from(f64::NEG_INFINITY)
+            .or_else(|| A::from(i128::MIN))
+            .or_else(|| A::from(i64::MIN))
+            .or_else(|| A::from(i32::MIN))
+            .or_else(|| A::from(i16::MIN))
+            .or_else(|| A::from(i8::MIN))
+            .expect("coordinate type cannot represent i8::MIN")
+    }
+
+    fn is_regular(self) -> bool {
+        self.to_f64().map_or(false, f64::is_finite)
+    }
+
+    fn midpoint(min: Self, max: Self) -> Self {
+        let two = A::one() + A::one();
+        if A::one() / two == A::zero() {
+            // integers, where `max - min` may overflow
+            min / two + max / two + (min % two + max % two) / two
+        } else {
+            min + (max - min) / two
+        }
+    }
+
+    fn spread(min: Self, max: Self) -> f64 {
+        let two = A::one() + A::one();
+        let spread = if A::one() / two == A::zero() {
+            min.to_f64().and_then(|min| max.to_f64().map(|max| max - min))
+        } else {
+            (max - min).to_f64()
+        };
+        spread.unwrap_or(f64::NAN)
+    }
+}
@@ -0,0 +1,150 @@
+//! Answering many queries at once.
+//!
+//! Batch queries hand out their results through a callback, along with the index of the query
+//! they answer. [`ResultOrder`] says whether results arrive in the order of the queries, or as
+//! soon as they are ready, which gets the first results out earlier when queries run in
+//! parallel.
+
+use num_traits::Float;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+#[cfg(feature = "rayon")]
+use std::sync::Mutex;
+
+use crate::kdtree::{ErrorKind, KdTree};
+
+/// In which order batch queries hand out their results.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResultOrder {
+    /// The results of query `i` are handed out before those of query `i + 1`.
+    Preserved,
+    /// Results are handed out as soon as their query is answered, in no particular order.
+    Unordered,
+}
+
+impl<A: Float, T: PartialEq, U: AsRef<[A]> + PartialEq> KdTree<A, T, U> {
+    /// The `num` nearest neighbours of each of `queries`, nearest first, in the order of the
+    /// queries.
+    ///
+    /// Every query is checked before any is answered, so nothing is returned if one of them is
+    /// invalid.
+    pub fn nearest_batch<Q, F>(&self, queries: &[Q], num: usize, distance: &F) -> Result<Vec<Vec<(A, &T)>>, ErrorKind>
+    where
+        Q: AsRef<[A]>,
+        F: Fn(&[A], &[A]) -> A,
+    {
+        self.check_queries(queries)?;
+        Ok(queries
+            .iter()
+            .map(|query| self.nearest_unchecked(query.as_ref(), num, distance))
+            .collect())
+    }
+
+    /// As [`nearest_batch`](Self::nearest_batch), answering queries in parallel and handing the
+    /// neighbours of each to `result` along with the index of its query, in the given `order`.
+    ///
+    /// With [`ResultOrder::Preserved`], results are handed out from the calling thread once all
+    /// queries are answered. With [`ResultOrder::Unordered`], each result is handed out from the
+    /// thread that answered it as soon as it is ready, one at a time.
+    #[cfg(feature = "rayon")]
+    pub fn par_nearest_batch<'a, Q, F, R>(
+        &'a self,
+        queries: &[Q],
+        num: usize,
+        distance: &F,
+        order: ResultOrder,
+        result: R,
+    ) -> Result<(), ErrorKind>
+    where
+        A: Send + Sync,
+        T: Sync,
+        U: Sync,
+        Q: AsRef<[A]> + Sync,
+        F: Fn(&[A], &[A]) -> A + Sync,
+        R: FnMut(usize, Vec<(A, &'a T)>) + Send,
+    {
+        self.check_queries(queries)?;
+        match order {
+            ResultOrder::Preserved => {
+                let mut result = result;
+                let found: Vec<_> = queries
+                    .par_iter()
+                    .map(|query| self.nearest_unchecked(query.as_ref(), num, distance))
+                    .collect();
+                for (i, nearest) in found.into_iter().enumerate() {
+                    result(i, nearest);
+                }
+            }
+            ResultOrder::Unordered => {
+                let result = Mutex::new(result);
+                queries.par_iter().enumerate().for_each(|(i, query)| {
+                    let nearest = self.nearest_unchecked(query.as_ref(), num, distance);
+                    (result.lock().unwrap())(i, nearest);
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// All points within `radius` of each of `queries`, nearest first, in the order of the
+    /// queries.
+    ///
+    /// Every query is checked before any is answered, so nothing is returned if one of them is
+    /// invalid.
+    pub fn within_batch<Q, F>(&self, queries: &[Q], radius: A, distance: &F) -> Result<Vec<Vec<(A, &T)>>, ErrorKind>
+    where
+        Q: AsRef<[A]>,
+        F: Fn(&[A], &[A]) -> A,
+    {
+        self.check_queries(queries)?;
+        queries
+            .iter()
+            .map(|query| self.within(query.as_ref(), radius, distance))
+            .collect()
+    }
+
+    /// As [`within_batch`](Self::within_batch), answering queries in parallel and handing the
+    /// points found for each to `result` along with the index of its query, in the given
+    /// `order`, like [`par_nearest_batch`](Self::par_nearest_batch).
+    #[cfg(feature = "rayon")]
+    pub fn par_within_batch<'a, Q, F, R>(
+        &'a self,
+        queries: &[Q],
+        radius: A,
+        distance: &F,
+        order: ResultOrder,
+        result: R,
+    ) -> Result<(), ErrorKind>
+    where
+        A: Send + Sync,
+        T: Sync,
+        U: Sync,
+        Q: AsRef<[A]> + Sync,
+        F: Fn(&[A], &[A]) -> A + Sync,
+        R: FnMut(usize, Vec<(A, &'a T)>) + Send,
+    {
+        self.check_queries(queries)?;
+        let within = |query: &Q| self.within(query.as_ref(), radius, distance).unwrap_or_default();
+        match order {
+            ResultOrder::Preserved => {
+                let mut result = result;
+                let found: Vec<_> = queries.par_iter().map(within).collect();
+                for (i, points) in found.into_iter().enumerate() {
+                    result(i, points);
+                }
+            }
+            ResultOrder::Unordered => {
+                let result = Mutex::new(result);
+                queries.par_iter().enumerate().for_each(|(i, query)| {
+                    let points = within(query);
+                    (result.lock().unwrap())(i, points);
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn check_queries<Q: AsRef<[A]>>(&self, queries: &[Q]) -> Result<(), ErrorKind> {
+        queries.iter().try_for_each(|query| self.check_point(query.as_ref()))
+    }
+}
@@ -0,0 +1,119 @@
+//! Inspect a serialized tree from the command line.
+//!
+//! The tree is expected as JSON, as written by `serde_json` for a
+//! `KdTree<f64, T, U>` with any serializable payload `T` and coordinates `U`
+//! serialized as a sequence (`Vec<f64>`, `[f64; N]`, ...).
+//!
+//! ```text
+//! kdtree-cli <tree.json> stats
+//! kdtree-cli <tree.json> nearest <k> <x,y,...>
+//! kdtree-cli <tree.json> within <radius> <x,y,...>
+//! kdtree-cli <tree.json> tikz
+//! kdtree-cli <tree.json> svg
+//! kdtree-cli <tree.json> dot
+//! ```
+//!
+//! Distances are squared euclidean, like `kdtree::distance::squared_euclidean`.
+//! `tikz` and `svg` draw 2D trees, `dot` writes the node structure of trees of
+//! any dimension size as a Graphviz graph.
+extern crate kdtree;
+extern crate serde_json;
+
+use std::env;
+use std::fs::File;
+use std::io::BufReader;
+use std::process;
+
+use kdtree::distance::squared_euclidean;
+use kdtree::{KdTree, SvgOptions, TikzOptions};
+use serde_json::Value;
+
+type Tree = KdTree<f64, Value, Vec<f64>>;
+
+const USAGE: &str = "usage:
+    kdtree-cli <tree.json> stats
+    kdtree-cli <tree.json> nearest <k> <x,y,...>
+    kdtree-cli <tree.json> within <radius> <x,y,...>
+    kdtree-cli <tree.json> tikz
+    kdtree-cli <tree.json> svg
+    kdtree-cli <tree.json> dot";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if let Err(message) = run(&args) {
+        eprintln!("error: {}", message);
+        eprintln!("{}", USAGE);
+        process::exit(1);
+    }
+}
+
+fn run(args: &[String]) -> Result<(), String> {
+    let path = args.first().ok_or("missing tree file")?;
+    let command = args.get(1).ok_or("missing command")?;
+    let tree = load(path)?;
+    match (command.as_str(), &args[2..]) {
+        ("stats", []) => print_stats(&tree),
+        ("nearest", [k, point]) => {
+            let k = k.parse::<usize>().map_err(|e| format!("invalid k `{}`: {}", k, e))?;
+            let point = parse_point(point)?;
+            let found = tree.nearest(&point, k, &squared_euclidean).map_err(|e| e.to_string())?;
+            print_neighbors(&found);
+        }
+        ("within", [radius, point]) => {
+            let radius = radius
+                .parse::<f64>()
+                .map_err(|e| format!("invalid radius `{}`: {}", radius, e))?;
+            let point = parse_point(point)?;
+            let found = tree
+                .within(&point, radius, &squared_euclidean)
+                .map_err(|e| e.to_string())?;
+            print_neighbors(&found);
+        }
+        ("tikz", []) => print!(
+            "{}",
+            tree.to_tikz_string(&TikzOptions::default())
+                .map_err(|e| e.to_string())?
+        ),
+        ("svg", []) => print!(
+            "{}",
+            tree.to_svg_string(&SvgOptions::default()).map_err(|e| e.to_string())?
+        ),
+        ("dot", []) => print!("{}", tree.display_dot()),
+        _ => return Err(format!("unknown command or wrong arguments for `{}`", command)),
+    }
+    Ok(())
+}
+
+fn load(path: &str) -> Result<Tree, String> {
+    let file = File::open(path).map_err(|e| format!("cannot open `{}`: {}", path, e))?;
+    serde_json::from_reader(BufReader::new(file)).map_err(|e| format!("cannot parse `{}`: {}", path, e))
+}
+
+fn parse_point(point: &str) -> Result<Vec<f64>, String> {
+    point
+        .split(',')
+        .map(|x| {
+            x.trim()
+                .parse::<f64>()
+                .map_err(|e| format!("invalid coordinate `{}`: {}", x, e))
+        })
+        .collect()
+}
+
+fn print_stats(tree: &Tree) {
+    let stats = tree.stats();
+    println!("size:          {}", stats.size);
+    println!("dimensions:    {}", stats.dimensions);
+    println!("capacity:      {}", stats.capacity);
+    println!("depth:         {}", stats.depth);
+    println!("stems:         {}", stats.stems);
+    println!("leaves:        {}", stats.leaves);
+    println!("empty leaves:  {}", stats.empty_leaves);
+    println!("max leaf size: {}", stats.max_leaf_size);
+}
+
+fn print_neighbors(found: &[(f64, &Value)]) {
+    for (distance, data) in found {
+        println!("{}\t{}", distance, data);
+    }
+}
@@ -0,0 +1,37 @@
+//! Queries doing a bounded amount of work.
+
+use num_traits::Float;
+
+use crate::kdtree::{ErrorKind, KdTree};
+
+/// The neighbours found by [`KdTree::nearest_with_budget`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct BudgetedNearest<'a, A, T> {
+    /// The nearest neighbours found, nearest first.
+    pub neighbours: Vec<(A, &'a T)>,
+    /// Whether the search finished within the budget, so these are the exact neighbours.
+    pub complete: bool,
+}
+
+impl<A: Float, T: PartialEq, U: AsRef<[A]> + PartialEq> KdTree<A, T, U> {
+    /// Like [`nearest`](Self::nearest), but looking at the points of at most `max_leaves` leaves,
+    /// returning the nearest neighbours found by then.
+    ///
+    /// Leaves are visited nearest first, so the neighbours found are usually good even when the
+    /// budget runs out. Unlike a deadline, the budget gives the same results on every run, and
+    /// bounds the work a query can take on skewed or adversarial data.
+    pub fn nearest_with_budget<F>(
+        &self,
+        point: &[A],
+        num: usize,
+        distance: &F,
+        max_leaves: usize,
+    ) -> Result<BudgetedNearest<'_, A, T>, ErrorKind>
+    where
+        F: Fn(&[A], &[A]) -> A,
+    {
+        self.check_point(point)?;
+        let (neighbours, complete) = self.nearest_within_factor(point, num, A::one(), max_leaves, distance);
+        Ok(BudgetedNearest { neighbours, complete })
+    }
+}
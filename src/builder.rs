@@ -0,0 +1,169 @@
+//! A builder that only hands out trees once their dimension size is known.
+//!
+//! The dimension size is part of the builder's type: [`KdTreeBuilder::new`] starts out without
+//! one, and only [`KdTreeBuilder::dimensions`] leads to a builder that can
+//! [`build`](KdTreeBuilder::build). Trees built this way store `[A; K]` points, so adding a point
+//! of the wrong dimension size does not compile. Their queries still take slices; to have those
+//! checked at compile time as well, [`build_fixed`](KdTreeBuilder::build_fixed) a
+//! [`FixedKdTree`] instead.
+//!
+//! ```
+//! use kdtree::builder::KdTreeBuilder;
+//! use kdtree::distance::squared_euclidean;
+//!
+//! let mut kdtree = KdTreeBuilder::new().dimensions::<2>().capacity(8).build().unwrap();
+//! kdtree.add([1.0, 2.0], "a").unwrap();
+//! assert_eq!(kdtree.nearest(&[1.0, 2.0], 1, &squared_euclidean).unwrap(), vec![(0.0, &"a")]);
+//! ```
+//!
+//! ```compile_fail
+//! use kdtree::builder::KdTreeBuilder;
+//! use kdtree::KdTree;
+//!
+//! // no dimension size given yet
+//! let kdtree: KdTree<f64, &str, [f64; 2]> = KdTreeBuilder::new().build().unwrap();
+//! ```
+//!
+//! ```compile_fail
+//! use kdtree::builder::KdTreeBuilder;
+//!
+//! let mut kdtree = KdTreeBuilder::new().dimensions::<2>().build().unwrap();
+//! kdtree.add([1.0, 2.0, 3.0], "a").unwrap();
+//! ```
+//!
+//! ```compile_fail
+//! use kdtree::builder::KdTreeBuilder;
+//! use kdtree::fixed::squared_euclidean;
+//!
+//! let kdtree = KdTreeBuilder::new().dimensions::<2>().build_fixed::<f64, &str>().unwrap();
+//! kdtree.nearest(&[1.0, 2.0, 3.0], 1, &squared_euclidean).unwrap();
+//! ```
+
+use std::marker::PhantomData;
+
+use num_traits::Float;
+
+use crate::fixed::FixedKdTree;
+use crate::jitter::JitteredKdTree;
+use crate::kdtree::{ErrorKind, KdTree};
+
+/// The dimension size has not been given yet.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoDims;
+
+/// The dimension size is `K`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Dims<const K: usize>;
+
+#[derive(Clone, Copy, Debug)]
+pub struct KdTreeBuilder<D> {
+    capacity: usize,
+    leaf_limit: Option<usize>,
+    strict: bool,
+    dims: PhantomData<D>,
+}
+
+impl KdTreeBuilder<NoDims> {
+    pub fn new() -> Self {
+        KdTreeBuilder {
+            capacity: 2_usize.pow(4),
+            leaf_limit: None,
+            strict: false,
+            dims: PhantomData,
+        }
+    }
+
+    /// Fix the dimension size of the points to `K`.
+    pub fn dimensions<const K: usize>(self) -> KdTreeBuilder<Dims<K>> {
+        KdTreeBuilder {
+            capacity: self.capacity,
+            leaf_limit: self.leaf_limit,
+            strict: self.strict,
+            dims: PhantomData,
+        }
+    }
+}
+
+impl Default for KdTreeBuilder<NoDims> {
+    fn default() -> Self {
+        KdTreeBuilder::new()
+    }
+}
+
+impl<D> KdTreeBuilder<D> {
+    /// Set the capacity of leaf nodes, 16 unless set.
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Set the leaf limit, see [`KdTree::set_leaf_limit`].
+    pub fn leaf_limit(mut self, limit: usize) -> Self {
+        self.leaf_limit = Some(limit);
+        self
+    }
+
+    /// Reject points exceeding the leaf limit, see [`KdTree::set_strict`].
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+}
+
+impl<const K: usize> KdTreeBuilder<Dims<K>> {
+    /// Build an empty tree.
+    ///
+    /// Fails with [`ErrorKind::ZeroCapacity`] for a capacity of zero, and with
+    /// [`ErrorKind::WrongDimension`] for a dimension size of zero.
+    pub fn build<A: Float, T: PartialEq>(self) -> Result<KdTree<A, T, [A; K]>, ErrorKind> {
+        self.check()?;
+        let mut tree = KdTree::with_capacity(K, self.capacity);
+        self.configure(&mut tree);
+        Ok(tree)
+    }
+
+    /// Build an empty [`FixedKdTree`], whose queries take `[A; K]` points too.
+    pub fn build_fixed<A: Float, T: PartialEq>(self) -> Result<FixedKdTree<A, T, K>, ErrorKind> {
+        FixedKdTree::from_tree(self.build()?)
+    }
+
+    /// Build a tree holding `points`, as [`KdTree::bulk_load`].
+    pub fn bulk_load<A: Float, T: PartialEq>(
+        self,
+        points: Vec<([A; K], T)>,
+    ) -> Result<KdTree<A, T, [A; K]>, ErrorKind> {
+        self.check()?;
+        let mut tree = KdTree::bulk_load(K, self.capacity, points)?;
+        self.configure(&mut tree);
+        Ok(tree)
+    }
+
+    /// Build an empty tree jittering duplicate points by up to `magnitude`, with offsets derived
+    /// from `seed`, see [`JitteredKdTree`]. The leaf limit applies to the jittered points.
+    pub fn jittered<A: Float, T: PartialEq>(
+        self,
+        magnitude: A,
+        seed: u64,
+    ) -> Result<JitteredKdTree<A, T, [A; K]>, ErrorKind> {
+        self.check()?;
+        let mut tree = JitteredKdTree::with_capacity(K, self.capacity, magnitude, seed)?;
+        tree.set_leaf_limit(self.leaf_limit);
+        tree.set_strict(self.strict);
+        Ok(tree)
+    }
+
+    fn configure<A: Float, T: PartialEq>(&self, tree: &mut KdTree<A, T, [A; K]>) {
+        tree.set_leaf_limit(self.leaf_limit);
+        tree.set_strict(self.strict);
+    }
+
+    fn check(&self) -> Result<(), ErrorKind> {
+        if self.capacity == 0 {
+            return Err(ErrorKind::ZeroCapacity);
+        }
+        if K == 0 {
+            return Err(ErrorKind::WrongDimension);
+        }
+        Ok(())
+    }
+}
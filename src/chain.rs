@@ -0,0 +1,93 @@
+//! Greedy nearest neighbour chains, as for tour construction and scan-line ordering.
+
+use num_traits::Float;
+
+use crate::kdtree::{ErrorKind, KdTree};
+
+/// Counts of visited indices, summed over ranges in logarithmic time.
+struct Visited {
+    // a Fenwick tree over the visited flags
+    counts: Vec<usize>,
+}
+
+impl Visited {
+    fn new(len: usize) -> Self {
+        Visited {
+            counts: vec![0; len + 1],
+        }
+    }
+
+    fn insert(&mut self, index: usize) {
+        let mut i = index + 1;
+        while i < self.counts.len() {
+            self.counts[i] += 1;
+            i += i & i.wrapping_neg();
+        }
+    }
+
+    /// The number of visited indices below `end`.
+    fn below(&self, end: usize) -> usize {
+        let (mut i, mut sum) = (end, 0);
+        while i > 0 {
+            sum += self.counts[i];
+            i -= i & i.wrapping_neg();
+        }
+        sum
+    }
+
+    fn count(&self, start: usize, end: usize) -> usize {
+        self.below(end) - self.below(start)
+    }
+}
+
+impl<A: Float, T: PartialEq, U: AsRef<[A]> + PartialEq> KdTree<A, T, U> {
+    /// Hop from `start` to the nearest stored point, from there to the nearest point not visited
+    /// yet, and so on for up to `num` hops, returning the length of each hop and the index of
+    /// the point it reached, in the order of [`iter`](Self::iter).
+    ///
+    /// Visited points are tracked internally; subtrees whose points have all been visited are
+    /// skipped, so late hops do not get slower as the chain grows. Ties are broken arbitrarily.
+    ///
+    /// ```
+    /// use kdtree::distance::squared_euclidean;
+    /// use kdtree::KdTree;
+    ///
+    /// let mut kdtree = KdTree::new(1);
+    /// for &x in &[4.0, 0.0, 9.0, 1.0] {
+    ///     kdtree.add([x], x).unwrap();
+    /// }
+    /// let chain = kdtree.nearest_chain(&[0.5], 4, &squared_euclidean).unwrap();
+    /// let order: Vec<f64> = chain.iter().map(|&(_, i)| kdtree.iter().nth(i).unwrap().0[0]).collect();
+    /// assert_eq!(order[2..], [4.0, 9.0]);
+    /// assert_eq!(chain[3].0, 25.0);
+    /// ```
+    pub fn nearest_chain<F>(&self, start: &[A], num: usize, distance: &F) -> Result<Vec<(A, usize)>, ErrorKind>
+    where
+        F: Fn(&[A], &[A]) -> A,
+    {
+        self.check_point(start)?;
+        let num = num.min(self.size);
+        let mut visited = Visited::new(self.size);
+        let mut chain = Vec::with_capacity(num);
+        let mut current: Option<&[A]> = None;
+        while chain.len() < num {
+            let from = current.unwrap_or(start);
+            let next = self.nearest_indexed_in(
+                from,
+                1,
+                A::infinity(),
+                distance,
+                |i| visited.count(i, i + 1) == 0,
+                |start, end| visited.count(start, end) < end - start,
+            );
+            let (d, index, _) = match next.first() {
+                Some(&next) => next,
+                None => break,
+            };
+            visited.insert(index);
+            chain.push((d, index));
+            current = self.get_by_index(index).map(|(point, _)| point.as_ref());
+        }
+        Ok(chain)
+    }
+}
@@ -0,0 +1,50 @@
+//! Exchanging points and payloads with other k-d tree crates as separate columns.
+//!
+//! Crates like `kiddo` store `[A; K]` points with a `u64` item each, and leave it to the caller to
+//! map items back to payloads. Splitting a tree into a column of points and one of payloads
+//! fits that directly: the position of a point is its item, and the same position in the
+//! payloads holds its payload.
+
+use num_traits::Float;
+
+use crate::kdtree::{ErrorKind, KdTree};
+
+impl<A: Float, T: PartialEq, U: AsRef<[A]> + PartialEq> KdTree<A, T, U> {
+    /// Take the tree apart into its points and their payloads, in the order of
+    /// [`iter`](Self::iter), without cloning them.
+    ///
+    /// ```
+    /// use kdtree::KdTree;
+    ///
+    /// let mut kdtree = KdTree::new(2);
+    /// kdtree.add([1.0, 2.0], "a").unwrap();
+    /// kdtree.add([3.0, 4.0], "b").unwrap();
+    ///
+    /// let (points, payloads) = kdtree.into_columns();
+    /// // e.g. `other.add(point, item)` for another crate's tree
+    /// let items: Vec<(&[f64; 2], u64)> = points.iter().zip(0..).collect();
+    /// assert_eq!(payloads[items[1].1 as usize], "b");
+    /// ```
+    pub fn into_columns(self) -> (Vec<U>, Vec<T>) {
+        self.into_points().into_iter().unzip()
+    }
+
+    /// Build a balanced tree from a column of points and one of their payloads, as
+    /// [`build`](Self::build).
+    ///
+    /// Fails with [`ErrorKind::ColumnLengthMismatch`] unless both columns have the same length.
+    pub fn from_columns(points: Vec<U>, payloads: Vec<T>) -> Result<Self, ErrorKind> {
+        if points.len() != payloads.len() {
+            return Err(ErrorKind::ColumnLengthMismatch);
+        }
+        KdTree::build(points.into_iter().zip(payloads).collect())
+    }
+}
+
+impl<A: Float, T: PartialEq + Clone, U: AsRef<[A]> + PartialEq + Clone> KdTree<A, T, U> {
+    /// Like [`into_columns`](Self::into_columns), but cloning the points and payloads so the
+    /// tree stays intact.
+    pub fn to_columns(&self) -> (Vec<U>, Vec<T>) {
+        self.iter().map(|(point, data)| (point.clone(), data.clone())).unzip()
+    }
+}
@@ -0,0 +1,53 @@
+//! Migrating trees persisted with the upstream `kdtree` crate.
+//!
+//! Ingestion code written against upstream keeps compiling after switching the dependency, as
+//! [`KdTree::new`], [`KdTree::add`] and the queries have the same signatures here. Trees that were
+//! built and serialized with upstream's `serialize` feature can be loaded with [`from_upstream`]
+//! instead of being rebuilt from their original input. It reads upstream's serde representation,
+//! which lacks the node IDs and limits of this crate, and gives every node its ID.
+//!
+//! ```
+//! # extern crate kdtree;
+//! # extern crate serde_json;
+//! use kdtree::compat::from_upstream;
+//! use kdtree::distance::squared_euclidean;
+//! use kdtree::KdTree;
+//!
+//! # fn main() {
+//! // as written by upstream `serde_json::to_string(&kdtree)`
+//! let json = r#"{
+//!     "left": null, "right": null, "dimensions": 2, "capacity": 16, "size": 1,
+//!     "min_bounds": [1.0, 2.0], "max_bounds": [1.0, 2.0],
+//!     "split_value": null, "split_dimension": null,
+//!     "points": [[1.0, 2.0]], "bucket": ["a"]
+//! }"#;
+//! let mut deserializer = serde_json::Deserializer::from_str(json);
+//! let kdtree: KdTree<f64, String, [f64; 2]> = from_upstream(&mut deserializer).unwrap();
+//! assert_eq!(kdtree.nearest(&[0.0, 0.0], 1, &squared_euclidean).unwrap()[0].1, "a");
+//! # }
+//! ```
+//!
+//! As a serde deserializer function, it can also load upstream trees nested in other types with
+//! `#[serde(deserialize_with = "kdtree::compat::from_upstream")]`.
+
+use num_traits::Float;
+use serde::{Deserialize, Deserializer};
+
+use crate::kdtree::KdTree;
+
+/// Deserialize a tree serialized by the upstream `kdtree` crate.
+///
+/// Trees serialized by this crate load as well, but lose their [generation](KdTree::generation)
+/// and node IDs, which are assigned anew.
+pub fn from_upstream<'de, D, A, T, U>(deserializer: D) -> Result<KdTree<A, T, U>, D::Error>
+where
+    D: Deserializer<'de>,
+    A: Float,
+    T: PartialEq,
+    U: AsRef<[A]> + PartialEq,
+    KdTree<A, T, U>: Deserialize<'de>,
+{
+    let mut tree = KdTree::deserialize(deserializer)?;
+    tree.reset_ids();
+    Ok(tree)
+}
@@ -0,0 +1,129 @@
+//! Nearest neighbours within a cone of directions, as for target selection and directional
+//! sensors.
+
+use std::collections::BinaryHeap;
+
+use num_traits::Float;
+
+use crate::distance::squared_euclidean;
+use crate::heap_element::HeapElement;
+use crate::kdtree::{ErrorKind, KdTree};
+use crate::util;
+
+/// A cone given by its apex, the unit vector along its axis and its half angle.
+struct Cone<'a, A> {
+    origin: &'a [A],
+    axis: Vec<A>,
+    half_angle: A,
+}
+
+impl<'a, A: Float> Cone<'a, A> {
+    fn contains(&self, point: &[A]) -> bool {
+        let (dot, length) = self.project(point);
+        length > A::zero() && dot >= length * self.half_angle.cos()
+    }
+
+    /// Whether the box from `min` to `max` may hold points in the cone, by testing the sphere
+    /// around the box.
+    fn may_intersect(&self, min: &[A], max: &[A]) -> bool {
+        let two = A::one() + A::one();
+        let center: Vec<A> = min.iter().zip(max).map(|(&lo, &hi)| (lo + hi) / two).collect();
+        let radius = squared_euclidean(&center, max).sqrt();
+        let (dot, length) = self.project(&center);
+        if length <= radius {
+            return true;
+        }
+        let angle = (dot / length).max(-A::one()).min(A::one()).acos();
+        angle - (radius / length).asin() <= self.half_angle
+    }
+
+    /// The projection of `point` onto the axis and its distance, both relative to the apex.
+    fn project(&self, point: &[A]) -> (A, A) {
+        let mut dot = A::zero();
+        let mut squared = A::zero();
+        for ((&x, &o), &a) in point.iter().zip(self.origin).zip(&self.axis) {
+            dot = dot + (x - o) * a;
+            squared = squared + (x - o) * (x - o);
+        }
+        (dot, squared.sqrt())
+    }
+}
+
+impl<A: Float, T: PartialEq, U: AsRef<[A]> + PartialEq> KdTree<A, T, U> {
+    /// The `num` stored points nearest to `origin` whose direction from it lies within
+    /// `half_angle` radians of `direction`, nearest first, with their squared euclidean distance.
+    ///
+    /// Points at `origin` itself have no direction and are left out. Subtrees whose bounds lie
+    /// entirely outside of the cone are skipped. Fails with [`ErrorKind::InvalidCone`] unless
+    /// `direction` is non-zero and `half_angle` lies within `0..=π`.
+    pub fn nearest_in_cone(
+        &self,
+        origin: &[A],
+        direction: &[A],
+        half_angle: A,
+        num: usize,
+    ) -> Result<Vec<(A, &T)>, ErrorKind> {
+        self.check_point(origin)?;
+        self.check_point(direction)?;
+        let length = squared_euclidean(direction, &vec![A::zero(); direction.len()]).sqrt();
+        let pi = A::from(std::f64::consts::PI).unwrap();
+        if length == A::zero() || !(half_angle >= A::zero() && half_angle <= pi) {
+            return Err(ErrorKind::InvalidCone);
+        }
+        let cone = Cone {
+            origin,
+            axis: direction.iter().map(|&x| x / length).collect(),
+            half_angle,
+        };
+        let num = std::cmp::min(num, self.size);
+        if num == 0 {
+            return Ok(vec![]);
+        }
+
+        let mut pending = BinaryHeap::new();
+        let mut evaluated = BinaryHeap::<HeapElement<A, &T>>::new();
+        pending.push(HeapElement {
+            distance: -A::zero(),
+            element: self,
+        });
+        while let Some(HeapElement {
+            distance,
+            element: node,
+        }) = pending.pop()
+        {
+            if evaluated.len() == num && -distance > evaluated.peek().unwrap().distance {
+                break;
+            }
+            if node.is_leaf() {
+                let points = node.points.as_ref().unwrap().iter();
+                for (p, data) in points.zip(node.bucket.as_ref().unwrap()) {
+                    if !cone.contains(p.as_ref()) {
+                        continue;
+                    }
+                    let element = HeapElement {
+                        distance: squared_euclidean(origin, p.as_ref()),
+                        element: data,
+                    };
+                    if evaluated.len() < num {
+                        evaluated.push(element);
+                    } else if element < *evaluated.peek().unwrap() {
+                        evaluated.pop();
+                        evaluated.push(element);
+                    }
+                }
+            } else {
+                for child in node.left.iter().chain(node.right.iter()) {
+                    if child.size > 0 && cone.may_intersect(&child.min_bounds, &child.max_bounds) {
+                        let to_space =
+                            util::distance_to_space(origin, &child.min_bounds, &child.max_bounds, &squared_euclidean);
+                        pending.push(HeapElement {
+                            distance: -to_space,
+                            element: &**child,
+                        });
+                    }
+                }
+            }
+        }
+        Ok(evaluated.into_sorted_vec().into_iter().map(Into::into).collect())
+    }
+}
@@ -0,0 +1,132 @@
+//! Pair counts per distance bin, the two-point correlation function.
+
+use num_traits::Float;
+
+use crate::distance::squared_euclidean;
+use crate::kdtree::{ErrorKind, KdTree};
+
+/// Index of the bin `[edges[k], edges[k + 1])` holding the squared distance `d`, if any.
+fn bin<A: Float>(squared_edges: &[A], d: A) -> Option<usize> {
+    let k = squared_edges.iter().take_while(|&&e| e <= d).count();
+    if k == 0 || k == squared_edges.len() {
+        None
+    } else {
+        Some(k - 1)
+    }
+}
+
+fn check_edges<A: Float>(bin_edges: &[A]) -> Result<(), ErrorKind> {
+    let non_negative = bin_edges.iter().all(|&e| e >= A::zero());
+    if !non_negative || bin_edges.windows(2).any(|w| w[0] > w[1]) {
+        return Err(ErrorKind::InvalidBinEdges);
+    }
+    Ok(())
+}
+
+/// Smallest and largest squared euclidean distance between two boxes.
+fn box_distances<A: Float>(min1: &[A], max1: &[A], min2: &[A], max2: &[A]) -> (A, A) {
+    let mut near = A::zero();
+    let mut far = A::zero();
+    for i in 0..min1.len() {
+        let gap = (min2[i] - max1[i]).max(min1[i] - max2[i]).max(A::zero());
+        let span = (max2[i] - min1[i]).abs().max((max1[i] - min2[i]).abs());
+        near = near + gap * gap;
+        far = far + span * span;
+    }
+    (near, far)
+}
+
+/// Add the number of pairs between `a` and `b` to `counts`, counting a whole pair of nodes at
+/// once when all of their pairs fall in the same bin, and skipping it when none can fall in any.
+fn count_pairs<A, T1, U1, T2, U2>(
+    a: &KdTree<A, T1, U1>,
+    b: &KdTree<A, T2, U2>,
+    squared_edges: &[A],
+    counts: &mut [usize],
+) where
+    A: Float,
+    T1: PartialEq,
+    U1: AsRef<[A]> + PartialEq,
+    T2: PartialEq,
+    U2: AsRef<[A]> + PartialEq,
+{
+    if a.size == 0 || b.size == 0 {
+        return;
+    }
+    let (near, far) = box_distances(&a.min_bounds, &a.max_bounds, &b.min_bounds, &b.max_bounds);
+    if far < squared_edges[0] || near >= squared_edges[squared_edges.len() - 1] {
+        return;
+    }
+    if let Some(k) = bin(squared_edges, near) {
+        if bin(squared_edges, far) == Some(k) {
+            counts[k] += a.size * b.size;
+            return;
+        }
+    }
+    let split_a = !a.is_leaf() && (b.is_leaf() || a.size >= b.size);
+    if split_a {
+        count_pairs(a.left.as_deref().unwrap(), b, squared_edges, counts);
+        count_pairs(a.right.as_deref().unwrap(), b, squared_edges, counts);
+    } else if !b.is_leaf() {
+        count_pairs(a, b.left.as_deref().unwrap(), squared_edges, counts);
+        count_pairs(a, b.right.as_deref().unwrap(), squared_edges, counts);
+    } else {
+        for p in a.points.as_ref().unwrap() {
+            for q in b.points.as_ref().unwrap() {
+                if let Some(k) = bin(squared_edges, squared_euclidean(p.as_ref(), q.as_ref())) {
+                    counts[k] += 1;
+                }
+            }
+        }
+    }
+}
+
+impl<A: Float, T: PartialEq, U: AsRef<[A]> + PartialEq> KdTree<A, T, U> {
+    /// The number of distinct pairs of points per euclidean distance bin, the bins being
+    /// `[bin_edges[k], bin_edges[k + 1])`.
+    ///
+    /// Pairs farther apart than the last edge or closer than the first one are not counted.
+    /// Computed with dual-tree traversal, counting pairs of nodes whose distances all fall into
+    /// the same bin at once. Fails with [`ErrorKind::InvalidBinEdges`] unless `bin_edges` are
+    /// ascending and zero or positive.
+    pub fn distance_histogram(&self, bin_edges: &[A]) -> Result<Vec<usize>, ErrorKind> {
+        check_edges(bin_edges)?;
+        let mut counts = self.cross_distance_histogram_unchecked(self, bin_edges);
+        // every point got paired with itself, and every other pair got counted twice
+        if let Some(k) = bin(bin_edges, A::zero()) {
+            counts[k] -= self.size;
+        }
+        Ok(counts.iter().map(|c| c / 2).collect())
+    }
+
+    /// The number of pairs of a point in this tree and a point in `other` per euclidean
+    /// distance bin, as [`distance_histogram`](Self::distance_histogram).
+    pub fn cross_distance_histogram<T2, U2>(
+        &self,
+        other: &KdTree<A, T2, U2>,
+        bin_edges: &[A],
+    ) -> Result<Vec<usize>, ErrorKind>
+    where
+        T2: PartialEq,
+        U2: AsRef<[A]> + PartialEq,
+    {
+        check_edges(bin_edges)?;
+        if self.size > 0 && other.size > 0 && self.dimensions != other.dimensions {
+            return Err(ErrorKind::WrongDimension);
+        }
+        Ok(self.cross_distance_histogram_unchecked(other, bin_edges))
+    }
+
+    fn cross_distance_histogram_unchecked<T2, U2>(&self, other: &KdTree<A, T2, U2>, bin_edges: &[A]) -> Vec<usize>
+    where
+        T2: PartialEq,
+        U2: AsRef<[A]> + PartialEq,
+    {
+        let mut counts = vec![0; bin_edges.len().saturating_sub(1)];
+        if bin_edges.len() >= 2 {
+            let squared_edges: Vec<A> = bin_edges.iter().map(|&e| e * e).collect();
+            count_pairs(self, other, &squared_edges, &mut counts);
+        }
+        counts
+    }
+}
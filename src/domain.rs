@@ -0,0 +1,148 @@
+//! Trees restricted to a declared range of valid coordinates.
+//!
+//! A single glitched reading far outside of the expected range inflates the bounds of every node
+//! on its way into the tree, and pruning suffers for as long as the tree lives. [`DomainKdTree`]
+//! checks points against a domain before they get there, rejecting or clamping them.
+
+use num_traits::Float;
+
+use crate::kdtree::{ErrorKind, KdTree};
+use crate::partition::Aabb;
+
+/// What [`DomainKdTree::add`] does with points outside of the domain.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutOfDomain {
+    /// Fail with [`ErrorKind::OutOfDomain`].
+    Reject,
+    /// Move every coordinate into its range.
+    Clamp,
+}
+
+/// A tree only accepting points within a domain.
+#[derive(Clone, Debug)]
+pub struct DomainKdTree<A, T: PartialEq, U: AsRef<[A]> + PartialEq> {
+    tree: KdTree<A, T, U>,
+    domain: Aabb<A>,
+    policy: OutOfDomain,
+    clamp_queries: bool,
+}
+
+impl<A: Float, T: PartialEq, U: AsRef<[A]> + AsMut<[A]> + PartialEq> DomainKdTree<A, T, U> {
+    /// Create a new tree for points within `domain`, its dimension size being that of the
+    /// domain.
+    ///
+    /// Fails with [`ErrorKind::InvalidDomain`] if the corners of `domain` differ in dimension
+    /// size, are not finite or the minimum exceeds the maximum somewhere.
+    pub fn new(domain: Aabb<A>, policy: OutOfDomain) -> Result<Self, ErrorKind> {
+        DomainKdTree::with_capacity(domain, policy, 2_usize.pow(4))
+    }
+
+    /// Like [`new`](Self::new), specifying the capacity of leaf nodes.
+    pub fn with_capacity(domain: Aabb<A>, policy: OutOfDomain, capacity: usize) -> Result<Self, ErrorKind> {
+        let valid = domain.min.len() == domain.max.len()
+            && domain
+                .min
+                .iter()
+                .zip(&domain.max)
+                .all(|(&min, &max)| min.is_finite() && max.is_finite() && min <= max);
+        if !valid {
+            return Err(ErrorKind::InvalidDomain);
+        }
+        Ok(DomainKdTree {
+            tree: KdTree::with_capacity(domain.min.len(), capacity),
+            domain,
+            policy,
+            clamp_queries: false,
+        })
+    }
+
+    /// Clamp query points into the domain too, so a query from outside finds what a query from
+    /// the nearest position inside would.
+    pub fn clamp_queries(mut self, clamp_queries: bool) -> Self {
+        self.clamp_queries = clamp_queries;
+        self
+    }
+
+    pub fn domain(&self) -> &Aabb<A> {
+        &self.domain
+    }
+
+    pub fn size(&self) -> usize {
+        self.tree.size()
+    }
+
+    /// The tree holding the points, for queries not wrapped here.
+    pub fn tree(&self) -> &KdTree<A, T, U> {
+        &self.tree
+    }
+
+    /// Add `data` at `point`, treating points outside of the domain according to the policy.
+    pub fn add(&mut self, mut point: U, data: T) -> Result<(), ErrorKind> {
+        self.admit(&mut point)?;
+        self.tree.add(point, data)
+    }
+
+    /// Remove all entries holding `data` at `point`, returning how many were removed. With
+    /// [`OutOfDomain::Clamp`], `point` is clamped first, like it was when added.
+    pub fn remove(&mut self, point: &U, data: &T) -> Result<usize, ErrorKind>
+    where
+        U: Clone,
+    {
+        let mut point = point.clone();
+        match self.admit(&mut point) {
+            Ok(()) => self.tree.remove(&point, data),
+            Err(ErrorKind::OutOfDomain) => Ok(0),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// The `num` nearest neighbours of `point`, nearest first.
+    pub fn nearest<F>(&self, point: &[A], num: usize, distance: &F) -> Result<Vec<(A, &T)>, ErrorKind>
+    where
+        F: Fn(&[A], &[A]) -> A,
+    {
+        self.tree.nearest(&self.query(point), num, distance)
+    }
+
+    /// All points within `radius` of `point`, nearest first.
+    pub fn within<F>(&self, point: &[A], radius: A, distance: &F) -> Result<Vec<(A, &T)>, ErrorKind>
+    where
+        F: Fn(&[A], &[A]) -> A,
+    {
+        self.tree.within(&self.query(point), radius, distance)
+    }
+
+    fn admit(&self, point: &mut U) -> Result<(), ErrorKind> {
+        if point.as_ref().len() != self.domain.min.len() {
+            return Err(ErrorKind::WrongDimension);
+        }
+        match self.policy {
+            OutOfDomain::Reject if !self.domain.contains(point.as_ref()) => {
+                // leave reporting non-finite coordinates to the tree
+                if point.as_ref().iter().all(|x| x.is_finite()) {
+                    return Err(ErrorKind::OutOfDomain);
+                }
+            }
+            OutOfDomain::Reject => {}
+            OutOfDomain::Clamp => self.clamp(point.as_mut()),
+        }
+        Ok(())
+    }
+
+    fn query(&self, point: &[A]) -> Vec<A> {
+        let mut point = point.to_vec();
+        if self.clamp_queries && point.len() == self.domain.min.len() {
+            self.clamp(&mut point);
+        }
+        point
+    }
+
+    /// Clamp the finite coordinates of `point`, non-finite ones are left for the tree to reject.
+    fn clamp(&self, point: &mut [A]) {
+        for ((x, &min), &max) in point.iter_mut().zip(&self.domain.min).zip(&self.domain.max) {
+            if x.is_finite() {
+                *x = x.max(min).min(max);
+            }
+        }
+    }
+}
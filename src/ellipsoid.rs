@@ -0,0 +1,109 @@
+//! Range queries over ellipsoids, like the gating regions of tracking filters.
+
+use num_traits::Float;
+
+use crate::distance::weighted_squared_euclidean;
+use crate::kdtree::{ErrorKind, KdTree};
+use crate::util;
+
+/// The lower triangular `L` with `L * L^T = matrix`, row-major, if `matrix` is symmetric and
+/// positive definite.
+fn cholesky<A: Float>(matrix: &[A], dims: usize) -> Option<Vec<A>> {
+    let mut lower = vec![A::zero(); dims * dims];
+    for i in 0..dims {
+        for j in 0..=i {
+            if matrix[i * dims + j] != matrix[j * dims + i] {
+                return None;
+            }
+            let sum = (0..j).fold(matrix[i * dims + j], |sum, k| {
+                sum - lower[i * dims + k] * lower[j * dims + k]
+            });
+            if i == j {
+                if !(sum > A::zero() && sum.is_finite()) {
+                    return None;
+                }
+                lower[i * dims + i] = sum.sqrt();
+            } else {
+                lower[i * dims + j] = sum / lower[j * dims + j];
+            }
+        }
+    }
+    Some(lower)
+}
+
+impl<A: Float, T: PartialEq, U: AsRef<[A]> + PartialEq> KdTree<A, T, U> {
+    /// All points within the axis-aligned ellipsoid around `center` with semi-axes `radii`,
+    /// nearest first, with `sum(((x - center) / radii)^2)`, which is at most 1.
+    ///
+    /// Subtrees are skipped as in [`within`](Self::within). Fails with
+    /// [`ErrorKind::InvalidEllipsoid`] unless all radii are finite and positive.
+    pub fn within_ellipsoid(&self, center: &[A], radii: &[A]) -> Result<Vec<(A, &T)>, ErrorKind> {
+        self.check_point(center)?;
+        if radii.len() != self.dimensions {
+            return Err(ErrorKind::WrongDimension);
+        }
+        let weights: Vec<A> = radii.iter().map(|&r| (r * r).recip()).collect();
+        if !weights.iter().all(|&w| w > A::zero() && w.is_finite()) {
+            return Err(ErrorKind::InvalidEllipsoid);
+        }
+        self.within(center, A::one(), &weighted_squared_euclidean(weights))
+    }
+
+    /// All points whose squared Mahalanobis distance from `center` under `covariance` is at most
+    /// `gate`, nearest first, with that distance.
+    ///
+    /// `covariance` holds the rows of a symmetric positive definite matrix `S` one after another,
+    /// and a point `x` is inside when `(x - center)^T S^-1 (x - center) <= gate`. Subtrees
+    /// outside of the bounding box of the ellipsoid, which extends `sqrt(gate * S[i][i])` from
+    /// `center` along dimension `i`, are skipped before testing the points in the others. Fails
+    /// with [`ErrorKind::InvalidEllipsoid`] if `covariance` does not hold a symmetric positive
+    /// definite matrix or `gate` is negative or not finite.
+    ///
+    /// The matrix is taken as a flat slice rather than as an `nalgebra` matrix, so the crate does
+    /// not depend on a linear algebra library. As `S` is symmetric, the column-major storage of
+    /// an `nalgebra` matrix can be passed as it is, with `matrix.as_slice()`.
+    pub fn within_ellipsoid_matrix(&self, center: &[A], covariance: &[A], gate: A) -> Result<Vec<(A, &T)>, ErrorKind> {
+        self.check_point(center)?;
+        let dims = center.len();
+        if covariance.len() != dims * dims {
+            return Err(ErrorKind::WrongDimension);
+        }
+        let lower = match cholesky(covariance, dims) {
+            Some(lower) if gate >= A::zero() && gate.is_finite() => lower,
+            _ => return Err(ErrorKind::InvalidEllipsoid),
+        };
+        let extent: Vec<A> = (0..dims).map(|i| (gate * covariance[i * dims + i]).sqrt()).collect();
+        let min: Vec<A> = center.iter().zip(&extent).map(|(&c, &e)| c - e).collect();
+        let max: Vec<A> = center.iter().zip(&extent).map(|(&c, &e)| c + e).collect();
+
+        let mut offset = vec![A::zero(); dims];
+        let mut found = vec![];
+        let mut pending = vec![self];
+        while let Some(node) = pending.pop() {
+            if node.size == 0 || !util::boxes_overlap(&node.min_bounds, &node.max_bounds, &min, &max) {
+                continue;
+            }
+            if let (Some(points), Some(bucket)) = (node.points.as_ref(), node.bucket.as_ref()) {
+                for (point, data) in points.iter().zip(bucket) {
+                    // solve L * y = x - center, the distance being |y|^2
+                    let mut squared = A::zero();
+                    for i in 0..dims {
+                        let sum = (0..i).fold(point.as_ref()[i] - center[i], |sum, k| {
+                            sum - lower[i * dims + k] * offset[k]
+                        });
+                        offset[i] = sum / lower[i * dims + i];
+                        squared = squared + offset[i] * offset[i];
+                    }
+                    if squared <= gate {
+                        found.push((squared, data));
+                    }
+                }
+            } else {
+                pending.extend(node.right.as_deref());
+                pending.extend(node.left.as_deref());
+            }
+        }
+        found.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        Ok(found)
+    }
+}
@@ -0,0 +1,159 @@
+//! Empty space between the stored points, for placement and coverage gap analyses.
+
+use num_traits::Float;
+
+use crate::kdtree::{ErrorKind, KdTree};
+use crate::partition::Aabb;
+
+/// Dimension size up to which boxes are handled through their `2^dimensions` corners or halves.
+const MAX_DIMENSIONS: usize = 16;
+
+impl<A: Float, T: PartialEq, U: AsRef<[A]> + PartialEq> KdTree<A, T, U> {
+    /// The radius of the largest ball around `point` holding no stored point in its interior,
+    /// which is the distance to the nearest stored point, `None` for an empty tree.
+    pub fn largest_empty_radius<F>(&self, point: &[A], distance: &F) -> Result<Option<A>, ErrorKind>
+    where
+        F: Fn(&[A], &[A]) -> A,
+    {
+        Ok(self.nearest(point, 1, distance)?.first().map(|&(d, _)| d))
+    }
+
+    /// An approximation of the largest ball centered within the box from `min` to `max` that
+    /// holds no stored point in its interior, as its center and radius.
+    ///
+    /// Large gaps between points are bordered by leaf cells, so the corners of all
+    /// [leaf cells](Self::leaf_cells) and of the box itself, moved into the box, are tried as
+    /// centers, keeping the one farthest from its nearest stored point. The ball found is always
+    /// empty, but a larger one may exist. Parts of it may reach out of the box. `None` for an
+    /// empty tree or a box with a minimum above its maximum.
+    ///
+    /// As every cell has `2^dimensions` corners, this fails with
+    /// [`ErrorKind::TooManyDimensions`] for more than 16 dimensions.
+    pub fn largest_empty_ball_within<F>(
+        &self,
+        min: &[A],
+        max: &[A],
+        distance: &F,
+    ) -> Result<Option<(Vec<A>, A)>, ErrorKind>
+    where
+        F: Fn(&[A], &[A]) -> A,
+    {
+        self.check_point(min)?;
+        self.check_point(max)?;
+        if min.len() > MAX_DIMENSIONS {
+            return Err(ErrorKind::TooManyDimensions(MAX_DIMENSIONS));
+        }
+        if self.size == 0 || min.iter().zip(max).any(|(lo, hi)| lo > hi) {
+            return Ok(None);
+        }
+        let mut boxes: Vec<(&[A], &[A])> = vec![(min, max)];
+        let cells = self.leaf_cells();
+        boxes.extend(cells.iter().map(|(cell, _, _)| (&cell.min[..], &cell.max[..])));
+
+        let mut best: Option<(Vec<A>, A)> = None;
+        let mut center = vec![A::zero(); min.len()];
+        for (lo, hi) in boxes {
+            for corner in 0..1_usize << min.len() {
+                for (i, c) in center.iter_mut().enumerate() {
+                    let x = if corner >> i & 1 == 0 { lo[i] } else { hi[i] };
+                    *c = x.max(min[i]).min(max[i]);
+                }
+                let radius = self.nearest_unchecked(&center, 1, distance)[0].0;
+                if best.as_ref().map_or(true, |(_, best)| radius > *best) {
+                    best = Some((center.clone(), radius));
+                }
+            }
+        }
+        Ok(best)
+    }
+
+    /// The cells of a grid over the box from `min` to `max` whose centers are farther than
+    /// `radius` from every stored point, as a map of coverage gaps.
+    ///
+    /// The box is halved along every dimension `depth` times, giving `2^depth` cells per
+    /// dimension. Parts of the box entirely within `radius` of the stored point nearest to their
+    /// center are skipped without being divided further, so well covered areas cost little.
+    /// Coverage is judged by that nearest point rather than by node bounds, which tell where
+    /// points lie but not what area they cover. `radius` is in the units of `distance`, like for
+    /// [`within`](Self::within). An empty tree leaves every cell uncovered.
+    ///
+    /// As every part is halved into `2^dimensions` smaller ones, this fails with
+    /// [`ErrorKind::TooManyDimensions`] for more than 16 dimensions.
+    pub fn coverage_gaps<F>(
+        &self,
+        min: &[A],
+        max: &[A],
+        depth: u32,
+        radius: A,
+        distance: &F,
+    ) -> Result<Vec<Aabb<A>>, ErrorKind>
+    where
+        F: Fn(&[A], &[A]) -> A,
+    {
+        self.check_point(min)?;
+        self.check_point(max)?;
+        if min.len() > MAX_DIMENSIONS {
+            return Err(ErrorKind::TooManyDimensions(MAX_DIMENSIONS));
+        }
+        let mut gaps = vec![];
+        if min.iter().zip(max).any(|(lo, hi)| lo > hi) {
+            return Ok(gaps);
+        }
+        let two = A::one() + A::one();
+        let mut pending = vec![(
+            Aabb {
+                min: min.to_vec(),
+                max: max.to_vec(),
+            },
+            0,
+        )];
+        while let Some((cell, level)) = pending.pop() {
+            let center: Vec<A> = cell
+                .min
+                .iter()
+                .zip(&cell.max)
+                .map(|(&lo, &hi)| (lo + hi) / two)
+                .collect();
+            let nearest = self.nearest_indexed(&center, 1, A::infinity(), distance, |_| true);
+            let (center_distance, index) = match nearest.first() {
+                Some(&(d, index, _)) => (d, Some(index)),
+                None => (A::infinity(), None),
+            };
+            if level == depth {
+                if center_distance > radius {
+                    gaps.push(cell);
+                }
+                continue;
+            }
+            if let Some((point, _)) = index.and_then(|index| self.get_by_index(index)) {
+                let point = point.as_ref();
+                // the corner of the cell farthest from the point
+                let farthest: Vec<A> = (0..point.len())
+                    .map(|i| {
+                        let (lo, hi) = (cell.min[i], cell.max[i]);
+                        if point[i] - lo > hi - point[i] {
+                            lo
+                        } else {
+                            hi
+                        }
+                    })
+                    .collect();
+                if distance(point, &farthest) <= radius {
+                    continue;
+                }
+            }
+            for part in 0..1_usize << center.len() {
+                let mut half = cell.clone();
+                for (i, &c) in center.iter().enumerate() {
+                    if part >> i & 1 == 0 {
+                        half.max[i] = c;
+                    } else {
+                        half.min[i] = c;
+                    }
+                }
+                pending.push((half, level + 1));
+            }
+        }
+        Ok(gaps)
+    }
+}
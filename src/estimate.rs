@@ -0,0 +1,73 @@
+//! Cheap estimates of how many points a query would find, without running it.
+
+use num_traits::Float;
+
+use crate::distance::squared_euclidean;
+use crate::kdtree::{ErrorKind, KdTree};
+use crate::util;
+
+/// An estimated number of points, with bounds the exact number is guaranteed to lie within.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CountEstimate {
+    pub estimate: f64,
+    pub min: usize,
+    pub max: usize,
+}
+
+impl<A: Float, T: PartialEq, U: AsRef<[A]> + PartialEq> KdTree<A, T, U> {
+    /// Estimate the number of points within euclidean distance `radius` of `point`, without
+    /// looking at any single point. Unlike [`within`](Self::within), `radius` is the plain
+    /// distance, not its square.
+    ///
+    /// Nodes whose bounds lie entirely within the ball or entirely outside of it count exactly.
+    /// Leaves the sphere passes through count with the fraction of their bounds covered by the
+    /// ball's bounding box, assuming their points are spread evenly; `min` and `max` count them
+    /// as not found at all and as found entirely.
+    pub fn estimate_count_within(&self, point: &[A], radius: A) -> Result<CountEstimate, ErrorKind> {
+        self.check_point(point)?;
+        let squared_radius = radius * radius;
+        let mut found = CountEstimate {
+            estimate: 0.0,
+            min: 0,
+            max: 0,
+        };
+        let mut pending = vec![self];
+        while let Some(node) = pending.pop() {
+            if node.size == 0
+                || util::distance_to_space(point, &node.min_bounds, &node.max_bounds, &squared_euclidean)
+                    > squared_radius
+            {
+                continue;
+            }
+            let farthest = point
+                .iter()
+                .zip(node.min_bounds.iter().zip(node.max_bounds.iter()))
+                .map(|(&x, (&min, &max))| (x - min).abs().max((max - x).abs()))
+                .fold(A::zero(), |sum, d| sum + d * d);
+            if farthest <= squared_radius {
+                found.estimate += node.size as f64;
+                found.min += node.size;
+                found.max += node.size;
+            } else if node.is_leaf() {
+                let fraction = point
+                    .iter()
+                    .zip(node.min_bounds.iter().zip(node.max_bounds.iter()))
+                    .map(|(&x, (&min, &max))| {
+                        let covered = (x + radius).min(max) - (x - radius).max(min);
+                        if max > min {
+                            (covered / (max - min)).max(A::zero()).min(A::one())
+                        } else {
+                            A::one()
+                        }
+                    })
+                    .fold(A::one(), |product, fraction| product * fraction);
+                found.estimate += node.size as f64 * fraction.to_f64().unwrap_or(1.0);
+                found.max += node.size;
+            } else {
+                pending.extend(node.left.as_deref());
+                pending.extend(node.right.as_deref());
+            }
+        }
+        Ok(found)
+    }
+}
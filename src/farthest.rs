@@ -0,0 +1,86 @@
+//! Farthest neighbour queries, as for diameter estimation and maximal spread sampling.
+
+use std::collections::BinaryHeap;
+
+use num_traits::Float;
+
+use crate::heap_element::HeapElement;
+use crate::kdtree::{ErrorKind, KdTree};
+
+impl<A: Float, T: PartialEq, U: AsRef<[A]> + PartialEq> KdTree<A, T, U> {
+    /// The `num` stored points farthest from `point`, farthest first.
+    ///
+    /// Nodes are visited by the distance to the corner of their bounds farthest from `point`,
+    /// which no point in them can exceed, and skipped once that is below the `num`th farthest
+    /// point found. This requires `distance` to never shrink when a coordinate moves farther
+    /// from `point`, which holds for every distance in [`distance`](crate::distance) except
+    /// [`haversine`](crate::distance::haversine).
+    pub fn farthest<F>(&self, point: &[A], num: usize, distance: &F) -> Result<Vec<(A, &T)>, ErrorKind>
+    where
+        F: Fn(&[A], &[A]) -> A,
+    {
+        self.check_point(point)?;
+        let num = std::cmp::min(num, self.size);
+        if num == 0 {
+            return Ok(vec![]);
+        }
+        let mut pending = BinaryHeap::new();
+        // negated distances, so the nearest of the farthest points found is on top
+        let mut evaluated = BinaryHeap::<HeapElement<A, &T>>::new();
+        pending.push(HeapElement {
+            distance: self.distance_to_far_corner(point, distance),
+            element: self,
+        });
+        while let Some(HeapElement {
+            distance: bound,
+            element: node,
+        }) = pending.pop()
+        {
+            if evaluated.len() == num && bound < -evaluated.peek().unwrap().distance {
+                break;
+            }
+            if node.is_leaf() {
+                let points = node.points.as_ref().unwrap().iter();
+                for (p, data) in points.zip(node.bucket.as_ref().unwrap()) {
+                    let element = HeapElement {
+                        distance: -distance(point, p.as_ref()),
+                        element: data,
+                    };
+                    if evaluated.len() < num {
+                        evaluated.push(element);
+                    } else if element < *evaluated.peek().unwrap() {
+                        evaluated.pop();
+                        evaluated.push(element);
+                    }
+                }
+            } else {
+                for child in node.left.iter().chain(node.right.iter()) {
+                    if child.size > 0 {
+                        pending.push(HeapElement {
+                            distance: child.distance_to_far_corner(point, distance),
+                            element: &**child,
+                        });
+                    }
+                }
+            }
+        }
+        Ok(evaluated
+            .into_sorted_vec()
+            .into_iter()
+            .map(|e| (-e.distance, e.element))
+            .collect())
+    }
+
+    /// The distance from `point` to the corner of this node's bounds farthest from it.
+    fn distance_to_far_corner<F>(&self, point: &[A], distance: &F) -> A
+    where
+        F: Fn(&[A], &[A]) -> A,
+    {
+        let corner: Vec<A> = point
+            .iter()
+            .zip(self.min_bounds.iter().zip(self.max_bounds.iter()))
+            .map(|(&x, (&lo, &hi))| if x - lo > hi - x { lo } else { hi })
+            .collect();
+        distance(point, &corner)
+    }
+}
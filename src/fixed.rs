@@ -0,0 +1,144 @@
+//! Trees whose dimension size is a const generic.
+//!
+//! A [`FixedKdTree`] stores `[A; K]` points and only takes `[A; K]` points as queries, so points
+//! of the wrong dimension size do not compile and never have to be checked while running. Its
+//! distance functions take `&[A; K]` as well, which lets the compiler unroll their loops.
+//!
+//! ```
+//! use kdtree::fixed::{squared_euclidean, FixedKdTree};
+//!
+//! let mut kdtree = FixedKdTree::new();
+//! kdtree.add([1.0, 2.0], "a").unwrap();
+//! kdtree.add([3.0, 1.0], "b").unwrap();
+//! assert_eq!(kdtree.nearest(&[2.5, 1.0], 1, &squared_euclidean).unwrap(), vec![(0.25, &"b")]);
+//! ```
+//!
+//! ```compile_fail
+//! use kdtree::fixed::FixedKdTree;
+//!
+//! let mut kdtree = FixedKdTree::<f64, &str, 2>::new();
+//! kdtree.add([1.0, 2.0, 3.0], "a").unwrap();
+//! ```
+
+use std::convert::TryFrom;
+
+use num_traits::Float;
+
+use crate::kdtree::{ErrorKind, Iter, KdTree};
+
+/// Returns the squared euclidean distance between two points, like
+/// [`distance::squared_euclidean`](crate::distance::squared_euclidean) for points of `K`
+/// dimensions.
+pub fn squared_euclidean<A: Float, const K: usize>(a: &[A; K], b: &[A; K]) -> A {
+    let mut sum = A::zero();
+    for i in 0..K {
+        let d = a[i] - b[i];
+        sum = sum + d * d;
+    }
+    sum
+}
+
+/// A tree of `K`-dimensional points.
+#[derive(Clone, Debug)]
+pub struct FixedKdTree<A: PartialEq, T: PartialEq, const K: usize> {
+    tree: KdTree<A, T, [A; K]>,
+}
+
+impl<A: Float, T: PartialEq, const K: usize> FixedKdTree<A, T, K> {
+    pub fn new() -> Self {
+        FixedKdTree { tree: KdTree::new(K) }
+    }
+
+    /// Create a new fixed tree, specifying the capacity of leaf nodes
+    pub fn with_capacity(capacity: usize) -> Self {
+        FixedKdTree {
+            tree: KdTree::with_capacity(K, capacity),
+        }
+    }
+
+    /// Wrap `tree`, failing with [`ErrorKind::WrongDimension`] unless its dimension size is
+    /// `K`.
+    pub fn from_tree(tree: KdTree<A, T, [A; K]>) -> Result<Self, ErrorKind> {
+        if tree.dimensions() != K {
+            return Err(ErrorKind::WrongDimension);
+        }
+        Ok(FixedKdTree { tree })
+    }
+
+    pub fn size(&self) -> usize {
+        self.tree.size()
+    }
+
+    /// The underlying tree, for queries not offered here.
+    pub fn tree(&self) -> &KdTree<A, T, [A; K]> {
+        &self.tree
+    }
+
+    pub fn into_inner(self) -> KdTree<A, T, [A; K]> {
+        self.tree
+    }
+
+    pub fn iter(&self) -> Iter<'_, A, T, [A; K]> {
+        self.tree.iter()
+    }
+
+    /// Add `data` at `point`. Fails with [`ErrorKind::NonFiniteCoordinate`],
+    /// [`ErrorKind::ZeroCapacity`] or, for trees built with a strict
+    /// [leaf limit](KdTree::set_leaf_limit), [`ErrorKind::LeafLimitExceeded`], but never with
+    /// [`ErrorKind::WrongDimension`].
+    pub fn add(&mut self, point: [A; K], data: T) -> Result<(), ErrorKind> {
+        if self.tree.capacity == 0 {
+            return Err(ErrorKind::ZeroCapacity);
+        }
+        if self.tree.leaf_limit.is_some() {
+            return self.tree.add(point, data);
+        }
+        check_finite(&point)?;
+        self.tree.add_unchecked(point, data);
+        Ok(())
+    }
+
+    /// Remove all entries at `point` holding `data`, returning how many were removed.
+    pub fn remove(&mut self, point: &[A; K], data: &T) -> Result<usize, ErrorKind> {
+        self.tree.remove(point, data)
+    }
+
+    /// The `num` nearest neighbours of `point`, nearest first.
+    pub fn nearest<F>(&self, point: &[A; K], num: usize, distance: &F) -> Result<Vec<(A, &T)>, ErrorKind>
+    where
+        F: Fn(&[A; K], &[A; K]) -> A,
+    {
+        check_finite(point)?;
+        Ok(self.tree.nearest_unchecked(point, num, &fixed(distance)))
+    }
+
+    /// All points within `radius` of `point`, nearest first.
+    pub fn within<F>(&self, point: &[A; K], radius: A, distance: &F) -> Result<Vec<(A, &T)>, ErrorKind>
+    where
+        F: Fn(&[A; K], &[A; K]) -> A,
+    {
+        self.tree.within(point, radius, &fixed(distance))
+    }
+}
+
+impl<A: Float, T: PartialEq, const K: usize> Default for FixedKdTree<A, T, K> {
+    fn default() -> Self {
+        FixedKdTree::new()
+    }
+}
+
+/// The tree hands out slices, which all have `K` elements here.
+fn fixed<'a, A: Float, F, const K: usize>(distance: &'a F) -> impl Fn(&[A], &[A]) -> A + 'a
+where
+    F: Fn(&[A; K], &[A; K]) -> A,
+{
+    move |a, b| distance(<&[A; K]>::try_from(a).unwrap(), <&[A; K]>::try_from(b).unwrap())
+}
+
+fn check_finite<A: Float>(point: &[A]) -> Result<(), ErrorKind> {
+    if point.iter().all(|x| x.is_finite()) {
+        Ok(())
+    } else {
+        Err(ErrorKind::NonFiniteCoordinate)
+    }
+}
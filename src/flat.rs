@@ -0,0 +1,233 @@
+//! Packed buffers of coordinates and results, in and out of trees.
+
+use std::convert::TryFrom;
+use std::sync::Arc;
+
+use num_traits::Float;
+
+use crate::kdtree::{ErrorKind, KdTree};
+
+/// How the coordinates of many points are packed into one buffer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Layout {
+    /// All coordinates of the first point, then all coordinates of the second point, and so on:
+    /// `x0 y0 x1 y1 x2 y2`.
+    RowMajor,
+    /// The first coordinate of every point, then the second coordinate of every point, and so
+    /// on: `x0 x1 x2 y0 y1 y2`.
+    ColumnMajor,
+}
+
+/// A point stored in a buffer shared by all points of a tree built with
+/// [`KdTree::build_from_flat`], as the range of its coordinates in it.
+///
+/// Points compare equal by their coordinates. Further points can be added to such a tree with
+/// [`FlatPoint::new`], each in a buffer of its own.
+#[derive(Clone, Debug)]
+pub struct FlatPoint<A> {
+    coords: Arc<[A]>,
+    start: usize,
+    end: usize,
+}
+
+impl<A> FlatPoint<A> {
+    /// A point holding `coords` alone.
+    pub fn new(coords: Vec<A>) -> Self {
+        let end = coords.len();
+        FlatPoint {
+            coords: coords.into(),
+            start: 0,
+            end,
+        }
+    }
+}
+
+impl<A> AsRef<[A]> for FlatPoint<A> {
+    fn as_ref(&self) -> &[A] {
+        &self.coords[self.start..self.end]
+    }
+}
+
+impl<A: PartialEq> PartialEq for FlatPoint<A> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_ref() == other.as_ref()
+    }
+}
+
+impl<A: Float, T: PartialEq> KdTree<A, T, FlatPoint<A>> {
+    /// Build a tree from the coordinates of `n_points` points packed into a single buffer, as
+    /// they often arrive through FFI, from a file mapping or from column oriented storage, with
+    /// one payload per point.
+    ///
+    /// `coords` must hold exactly `dimensions` coordinates for each point, otherwise
+    /// [`ErrorKind::WrongDimension`] is returned, and there must be one payload per point,
+    /// otherwise [`ErrorKind::ColumnLengthMismatch`] is. The coordinates are copied once, into a
+    /// single row-major buffer shared by all points, which refer to their range of it as
+    /// [`FlatPoint`]s, and the tree is balanced like by
+    /// [`build_with_capacity`](Self::build_with_capacity).
+    ///
+    /// ```
+    /// use kdtree::{KdTree, Layout};
+    ///
+    /// let coords = [0.0, 1.0, 2.0, 5.0, 6.0, 7.0];
+    /// let kdtree = KdTree::build_from_flat(&coords, Layout::ColumnMajor, 3, 2, 16, vec!['a', 'b', 'c']).unwrap();
+    /// assert_eq!(kdtree.iter().find(|&(_, &d)| d == 'c').unwrap().0.as_ref(), &[2.0, 7.0]);
+    /// ```
+    pub fn build_from_flat(
+        coords: &[A],
+        layout: Layout,
+        n_points: usize,
+        dimensions: usize,
+        capacity: usize,
+        payloads: Vec<T>,
+    ) -> Result<Self, ErrorKind> {
+        if dimensions == 0 || n_points.checked_mul(dimensions) != Some(coords.len()) {
+            return Err(ErrorKind::WrongDimension);
+        }
+        if payloads.len() != n_points {
+            return Err(ErrorKind::ColumnLengthMismatch);
+        }
+        let buffer: Arc<[A]> = match layout {
+            Layout::RowMajor => coords.into(),
+            Layout::ColumnMajor => (0..coords.len())
+                .map(|k| coords[(k % dimensions) * n_points + k / dimensions])
+                .collect(),
+        };
+        let points = payloads
+            .into_iter()
+            .enumerate()
+            .map(|(i, data)| {
+                let point = FlatPoint {
+                    coords: Arc::clone(&buffer),
+                    start: i * dimensions,
+                    end: (i + 1) * dimensions,
+                };
+                (point, data)
+            })
+            .collect::<Vec<_>>();
+        if points.is_empty() {
+            // an empty buffer still fixes the dimension size
+            return KdTree::bulk_load(dimensions, capacity, points);
+        }
+        KdTree::build_with_capacity(capacity, points)
+    }
+}
+
+impl<A: Float, T: PartialEq> KdTree<A, T, Vec<A>> {
+    /// Add points given as one slice per dimension, `[x, y, z]`, as many file formats and sensors
+    /// hand them out, with one payload per point.
+    ///
+    /// There must be a slice for every dimension of the tree, each holding one coordinate per
+    /// payload, otherwise [`ErrorKind::WrongDimension`] is returned. All points are validated
+    /// before any is added, an invalid one being reported like by
+    /// [`bulk_load`](Self::bulk_load).
+    ///
+    /// With a strict [leaf limit](Self::set_strict), the points are checked against the limit
+    /// before any is added too, counting the points of the batch at the same coordinates, and
+    /// [`ErrorKind::LeafLimitExceeded`] is returned if one would exceed it. Either all points are
+    /// added or none.
+    ///
+    /// ```
+    /// use kdtree::KdTree;
+    ///
+    /// let mut kdtree = KdTree::new(2);
+    /// let (x, y) = ([0.0, 1.0, 2.0], [5.0, 6.0, 7.0]);
+    /// kdtree.add_from_components(&[&x, &y], vec!['a', 'b', 'c']).unwrap();
+    /// assert_eq!(kdtree.iter().last(), Some((&vec![2.0, 7.0], &'c')));
+    /// ```
+    pub fn add_from_components(&mut self, components: &[&[A]], payloads: Vec<T>) -> Result<(), ErrorKind> {
+        if self.capacity == 0 {
+            return Err(ErrorKind::ZeroCapacity);
+        }
+        let inferring = self.dimensions == 0 && self.size == 0;
+        let wrong_shape = components.iter().any(|component| component.len() != payloads.len());
+        if components.is_empty() || wrong_shape || (components.len() != self.dimensions && !inferring) {
+            return Err(ErrorKind::WrongDimension);
+        }
+        let points: Vec<(Vec<A>, T)> = payloads
+            .into_iter()
+            .enumerate()
+            .map(|(i, data)| (components.iter().map(|component| component[i]).collect(), data))
+            .collect();
+        self.check_points(&points)?;
+        if let (Some(limit), true) = (self.leaf_limit, self.strict) {
+            let pending = duplicates_before(&points);
+            for ((point, _), &pending) in points.iter().zip(&pending) {
+                if self.exceeds_leaf_limit(point, pending, limit) {
+                    return Err(ErrorKind::LeafLimitExceeded(limit));
+                }
+            }
+        }
+        for (point, data) in points {
+            self.add_unchecked(point, data);
+        }
+        Ok(())
+    }
+}
+
+/// For every point, the number of points before it at exactly the same coordinates.
+fn duplicates_before<A: Float, T>(points: &[(Vec<A>, T)]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..points.len()).collect();
+    // stable, so equal points stay in the order they were given; coordinates are finite
+    order.sort_by(|&i, &j| points[i].0.partial_cmp(&points[j].0).unwrap());
+    let mut pending = vec![0; points.len()];
+    for k in 1..order.len() {
+        if points[order[k]].0 == points[order[k - 1]].0 {
+            pending[order[k]] = pending[order[k - 1]] + 1;
+        }
+    }
+    pending
+}
+
+impl<A: Float, T: PartialEq, U: AsRef<[A]> + PartialEq> KdTree<A, T, U> {
+    /// Find the `k` nearest neighbours of many points at once, writing them into buffers
+    /// allocated by the caller, the way FAISS and scikit-learn hand out results.
+    ///
+    /// `queries` holds the coordinates of the query points in [`Layout::RowMajor`]. Row `i` of
+    /// `distances_out` and `indices_out`, each `k` wide, receives the neighbours of query `i`,
+    /// nearest first, with indices referring to the order of [`iter`](Self::iter). When the tree
+    /// holds fewer than `k` points, the rest of a row is padded with infinite distances and
+    /// `u32::MAX` as index.
+    ///
+    /// Fails with [`ErrorKind::WrongDimension`] if `queries` does not hold whole points,
+    /// [`ErrorKind::WrongOutputLength`] if a buffer does not hold exactly `k` results per query
+    /// and [`ErrorKind::TooManyPayloads`] if the tree holds too many points to index with `u32`.
+    /// Nothing is written then.
+    pub fn knn_into<F>(
+        &self,
+        queries: &[A],
+        k: usize,
+        distance: &F,
+        distances_out: &mut [A],
+        indices_out: &mut [u32],
+    ) -> Result<(), ErrorKind>
+    where
+        F: Fn(&[A], &[A]) -> A,
+    {
+        if self.dimensions == 0 || queries.len() % self.dimensions != 0 {
+            return Err(ErrorKind::WrongDimension);
+        }
+        let n_queries = queries.len() / self.dimensions;
+        if distances_out.len() != n_queries * k || indices_out.len() != n_queries * k {
+            return Err(ErrorKind::WrongOutputLength);
+        }
+        if u32::try_from(self.size).is_err() {
+            return Err(ErrorKind::TooManyPayloads);
+        }
+        for query in queries.chunks(self.dimensions) {
+            self.check_point(query)?;
+        }
+        let rows = distances_out.chunks_mut(k.max(1)).zip(indices_out.chunks_mut(k.max(1)));
+        for (query, (distances, indices)) in queries.chunks(self.dimensions).zip(rows) {
+            let found = self.nearest_indexed(query, k, A::infinity(), distance, |_| true);
+            for i in 0..k {
+                let (d, index) = found
+                    .get(i)
+                    .map_or((A::infinity(), u32::MAX), |&(d, index, _)| (d, index as u32));
+                distances[i] = d;
+                indices[i] = index;
+            }
+        }
+        Ok(())
+    }
+}
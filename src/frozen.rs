@@ -0,0 +1,607 @@
+//! An immutable, compressed representation of a tree.
+//!
+//! A [`KdTree`] allocates every node and every leaf's points separately, and stores bounds and a
+//! copy of its configuration in every node. [`FrozenKdTree`] stores the same structure in a
+//! handful of flat arrays instead: one for nodes in depth-first order, one for all coordinates and
+//! one for all payloads, so a query touches far fewer allocations.
+//!
+//! Nodes are compressed as well:
+//!
+//! - Links are delta-encoded. A stem stores the offset of its right child from itself and the
+//!   number of points left of its split, both as `u32`; the left child directly follows it.
+//! - Leaves are packed. A leaf stores only the number of its points, which lie next to each other
+//!   in the coordinate and payload arrays, right after those of the leaves before it.
+//! - Bounds are not stored. Queries derive the cell of every node from the root's bounds and the
+//!   split values on the way down, which prunes a little less than the tight bounds of a
+//!   [`KdTree`], but costs no memory.
+//!
+//! Split values themselves are kept as they are, as any rounding would send queries down the
+//! wrong side. The node and point indices being `u32`, a tree of more than `u32::MAX` points
+//! cannot be frozen.
+
+use std::collections::BinaryHeap;
+use std::convert::TryFrom;
+
+use num_traits::Float;
+
+use crate::heap_element::HeapElement;
+use crate::kdtree::{ErrorKind, KdTree};
+use crate::util;
+
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug)]
+enum Node<A> {
+    /// The left child directly follows its parent, the right child is `right` nodes after it.
+    /// The left subtree holds `left_size` points.
+    Stem {
+        split_value: A,
+        split_dimension: u32,
+        right: u32,
+        left_size: u32,
+    },
+    /// `len` points, following those of all leaves before it, and whether the leaf could not be
+    /// split at some point.
+    Leaf {
+        len: u32,
+        #[cfg_attr(feature = "serialize", serde(default))]
+        unsplittable: bool,
+    },
+}
+
+/// A read-only tree answering queries like [`KdTree`], created with [`KdTree::freeze`].
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug)]
+pub struct FrozenKdTree<A, T> {
+    dimensions: usize,
+    capacity: usize,
+    nodes: Vec<Node<A>>,
+    /// The minimum, then the maximum bounds of the root.
+    bounds: Vec<A>,
+    /// The coordinates of all points, point by point.
+    coords: Vec<A>,
+    payloads: Vec<T>,
+    // configuration of the root, restored by `unfreeze`
+    #[cfg_attr(feature = "serialize", serde(default))]
+    leaf_limit: Option<usize>,
+    #[cfg_attr(feature = "serialize", serde(default))]
+    strict: bool,
+}
+
+/// A node to visit, with the index of its first point and its cell, the minimum then the
+/// maximum bounds its splits confine it to.
+struct Visit<A> {
+    index: usize,
+    start: usize,
+    cell: Box<[A]>,
+}
+
+fn to_u32(value: usize) -> u32 {
+    u32::try_from(value).expect("a frozen tree holds at most u32::MAX points and nodes")
+}
+
+impl<A: Float, T: PartialEq, U: AsRef<[A]> + PartialEq> KdTree<A, T, U> {
+    /// Convert into a [`FrozenKdTree`], keeping the structure of the tree.
+    ///
+    /// # Panics
+    ///
+    /// If the tree holds more than `u32::MAX` points or nodes.
+    pub fn freeze(self) -> FrozenKdTree<A, T> {
+        let mut bounds = self.min_bounds.to_vec();
+        bounds.extend_from_slice(&self.max_bounds);
+        let mut frozen = FrozenKdTree {
+            dimensions: self.dimensions,
+            capacity: self.capacity,
+            nodes: vec![],
+            bounds,
+            coords: Vec::with_capacity(self.size * self.dimensions),
+            payloads: Vec::with_capacity(self.size),
+            leaf_limit: self.leaf_limit,
+            strict: self.strict,
+        };
+        frozen.push(self);
+        frozen
+    }
+}
+
+impl<A: Float, T: PartialEq> FrozenKdTree<A, T> {
+    fn push<U: AsRef<[A]> + PartialEq>(&mut self, node: KdTree<A, T, U>) {
+        let index = self.nodes.len();
+        let start = self.payloads.len();
+        self.nodes.push(Node::Leaf {
+            len: 0,
+            unsplittable: node.unsplittable,
+        });
+        self.nodes[index] = if node.is_leaf() {
+            for point in node.points.unwrap_or_default() {
+                self.coords.extend_from_slice(point.as_ref());
+            }
+            self.payloads.extend(node.bucket.unwrap_or_default());
+            Node::Leaf {
+                len: to_u32(self.payloads.len() - start),
+                unsplittable: node.unsplittable,
+            }
+        } else {
+            self.push(*node.left.unwrap());
+            let left_size = to_u32(self.payloads.len() - start);
+            let right = to_u32(self.nodes.len() - index);
+            self.push(*node.right.unwrap());
+            Node::Stem {
+                split_value: node.split_value.unwrap(),
+                split_dimension: to_u32(node.split_dimension.unwrap()),
+                right,
+                left_size,
+            }
+        };
+    }
+
+    /// Convert back into a mutable [`KdTree`] with the same structure, leaf limit and
+    /// strictness.
+    ///
+    /// Nodes are numbered afresh, as in a tree just [built](KdTree::build), so the
+    /// [generation](KdTree::generation) is the number of stems. Bounds are computed from the
+    /// points, so they are tight even if the tree they were frozen from had points removed.
+    ///
+    /// Fails with [`ErrorKind::WrongDimension`] if `U` cannot hold points of this tree's dimension
+    /// size.
+    pub fn unfreeze<U>(self) -> Result<KdTree<A, T, U>, ErrorKind>
+    where
+        U: AsRef<[A]> + PartialEq + TryFrom<Vec<A>>,
+    {
+        let mut points = Vec::with_capacity(self.payloads.len());
+        for point in self.coords.chunks(self.dimensions.max(1)) {
+            points.push(U::try_from(point.to_vec()).map_err(|_| ErrorKind::WrongDimension)?);
+        }
+        let mut entries = points.into_iter().zip(self.payloads);
+        let mut tree = Self::thaw(&self.nodes, self.dimensions, self.capacity, 0, &mut entries);
+        tree.recompute_bounds();
+        tree.leaf_limit = self.leaf_limit;
+        tree.strict = self.strict;
+        tree.reset_ids();
+        Ok(tree)
+    }
+
+    fn thaw<U, I>(
+        nodes: &[Node<A>],
+        dimensions: usize,
+        capacity: usize,
+        index: usize,
+        entries: &mut I,
+    ) -> KdTree<A, T, U>
+    where
+        U: AsRef<[A]> + PartialEq,
+        I: Iterator<Item = (U, T)>,
+    {
+        let mut node = KdTree::with_capacity(dimensions, capacity);
+        match nodes[index] {
+            Node::Stem {
+                split_value,
+                split_dimension,
+                right,
+                ..
+            } => {
+                let left = Self::thaw(nodes, dimensions, capacity, index + 1, entries);
+                let right = Self::thaw(nodes, dimensions, capacity, index + right as usize, entries);
+                node.size = left.size + right.size;
+                node.split_dimension = Some(split_dimension as usize);
+                node.split_value = Some(split_value);
+                node.left = Some(Box::new(left));
+                node.right = Some(Box::new(right));
+                node.points = None;
+                node.bucket = None;
+            }
+            Node::Leaf { len, unsplittable } => {
+                let (points, bucket) = entries.take(len as usize).unzip();
+                node.size = len as usize;
+                node.unsplittable = unsplittable;
+                node.points = Some(points);
+                node.bucket = Some(bucket);
+                node.refresh_summary();
+            }
+        }
+        node
+    }
+
+    pub fn size(&self) -> usize {
+        self.payloads.len()
+    }
+
+    pub fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    /// Iterate over all points and their data, in the same order as [`KdTree::iter`].
+    pub fn iter(&self) -> impl Iterator<Item = (&[A], &T)> {
+        self.coords.chunks(self.dimensions.max(1)).zip(&self.payloads)
+    }
+
+    /// The `num` points nearest to `point`, as [`KdTree::nearest`].
+    pub fn nearest<F>(&self, point: &[A], num: usize, distance: &F) -> Result<Vec<(A, &T)>, ErrorKind>
+    where
+        F: Fn(&[A], &[A]) -> A,
+    {
+        self.check_point(point)?;
+        Ok(self.nearest_unchecked(point, num, distance))
+    }
+
+    /// The nearest neighbours for several values of `num` at once, as
+    /// [`KdTree::nearest_multi_k`].
+    pub fn nearest_multi_k<F>(&self, point: &[A], nums: &[usize], distance: &F) -> Result<Vec<Vec<(A, &T)>>, ErrorKind>
+    where
+        F: Fn(&[A], &[A]) -> A,
+    {
+        let max = nums.iter().copied().max().unwrap_or(0);
+        let found = self.nearest(point, max, distance)?;
+        Ok(nums.iter().map(|&num| found[..num.min(found.len())].to_vec()).collect())
+    }
+
+    /// Like [`nearest`](Self::nearest), but skips validating `point`, as
+    /// [`KdTree::nearest_unchecked`].
+    pub fn nearest_unchecked<F>(&self, point: &[A], num: usize, distance: &F) -> Vec<(A, &T)>
+    where
+        F: Fn(&[A], &[A]) -> A,
+    {
+        debug_assert_eq!(self.check_point(point), Ok(()));
+        self.search(point, num, A::infinity(), A::one(), distance)
+    }
+
+    /// Neighbours at most `1 + epsilon` times as far as the exact ones, as
+    /// [`KdTree::approx_nearest`].
+    pub fn approx_nearest<F>(
+        &self,
+        point: &[A],
+        num: usize,
+        distance: &F,
+        epsilon: A,
+    ) -> Result<Vec<(A, &T)>, ErrorKind>
+    where
+        F: Fn(&[A], &[A]) -> A,
+    {
+        self.check_point(point)?;
+        if !(epsilon >= A::zero() && epsilon.is_finite()) {
+            return Err(ErrorKind::InvalidEpsilon);
+        }
+        Ok(self.search(point, num, A::infinity(), A::one() + epsilon, distance))
+    }
+
+    /// The `num` nearest neighbours at most `max_dist` from `point`, as
+    /// [`KdTree::nearest_within`].
+    pub fn nearest_within<F>(
+        &self,
+        point: &[A],
+        num: usize,
+        max_dist: A,
+        distance: &F,
+    ) -> Result<Vec<(A, &T)>, ErrorKind>
+    where
+        F: Fn(&[A], &[A]) -> A,
+    {
+        self.check_point(point)?;
+        Ok(self.search(point, num, max_dist, A::one(), distance))
+    }
+
+    /// The `num` nearest neighbours whose data satisfies `predicate`, as
+    /// [`KdTree::nearest_filtered`].
+    pub fn nearest_filtered<F, P>(
+        &self,
+        point: &[A],
+        num: usize,
+        distance: &F,
+        predicate: P,
+    ) -> Result<Vec<(A, &T)>, ErrorKind>
+    where
+        F: Fn(&[A], &[A]) -> A,
+        P: Fn(&T) -> bool,
+    {
+        Ok(self
+            .iter_nearest(point, distance)?
+            .filter(|(_, data)| predicate(data))
+            .take(num)
+            .collect())
+    }
+
+    /// All points within `radius` of `point`, nearest first, as [`KdTree::within`].
+    pub fn within<F>(&self, point: &[A], radius: A, distance: &F) -> Result<Vec<(A, &T)>, ErrorKind>
+    where
+        F: Fn(&[A], &[A]) -> A,
+    {
+        self.check_point(point)?;
+        Ok(self.search(point, self.size(), radius, A::one(), distance))
+    }
+
+    /// All points inside the axis-aligned box from `min` to `max`, bounds included, as
+    /// [`KdTree::within_box`].
+    pub fn within_box(&self, min: &[A], max: &[A]) -> Result<Vec<(&[A], &T)>, ErrorKind> {
+        self.check_point(min)?;
+        self.check_point(max)?;
+        let dims = self.dimensions;
+        let mut found = vec![];
+        let mut pending = self.root().into_iter().collect::<Vec<_>>();
+        while let Some(visit) = pending.pop() {
+            let (cell_min, cell_max) = visit.cell.split_at(dims);
+            if !util::boxes_overlap(cell_min, cell_max, min, max) {
+                continue;
+            }
+            match self.nodes[visit.index] {
+                Node::Stem { .. } => pending.extend(self.children(&visit)),
+                Node::Leaf { len, .. } => {
+                    let inside = util::box_contains(min, max, cell_min, cell_max);
+                    found.extend(
+                        self.points(visit.start, len as usize)
+                            .filter(|(p, _)| inside || util::box_contains(min, max, p, p)),
+                    );
+                }
+            }
+        }
+        Ok(found)
+    }
+
+    /// Like [`within`](Self::within), but growing `radius` by `growth_factor` until at least
+    /// `num` points are found, as [`KdTree::nearest_at_least`].
+    pub fn nearest_at_least<F>(
+        &self,
+        point: &[A],
+        num: usize,
+        radius: A,
+        growth_factor: A,
+        distance: &F,
+    ) -> Result<Vec<(A, &T)>, ErrorKind>
+    where
+        F: Fn(&[A], &[A]) -> A,
+    {
+        if !(radius > A::zero() && growth_factor > A::one()) {
+            return Err(ErrorKind::InvalidRadiusGrowth);
+        }
+        let mut radius = radius;
+        let mut found = vec![];
+        let mut nearest = self.iter_nearest(point, distance)?.peekable();
+        while let Some(&(d, _)) = nearest.peek() {
+            if d > radius {
+                if found.len() >= num {
+                    break;
+                }
+                while radius < d {
+                    radius = radius * growth_factor;
+                }
+            }
+            found.extend(nearest.next());
+        }
+        Ok(found)
+    }
+
+    /// All points ordered by their distance to `point`, nearest first, as
+    /// [`KdTree::iter_nearest`].
+    pub fn iter_nearest<'a, 'b, F>(
+        &'b self,
+        point: &'a [A],
+        distance: &'a F,
+    ) -> Result<FrozenNearestIter<'a, 'b, A, T, F>, ErrorKind>
+    where
+        F: Fn(&[A], &[A]) -> A,
+    {
+        self.check_point(point)?;
+        let pending = self
+            .root()
+            .map(|visit| HeapElement {
+                distance: A::zero(),
+                element: visit,
+            })
+            .into_iter()
+            .collect();
+        Ok(FrozenNearestIter {
+            tree: self,
+            point,
+            pending,
+            evaluated: BinaryHeap::new(),
+            distance,
+        })
+    }
+
+    /// Like [`nearest`](Self::nearest), but returning clones of the payloads.
+    pub fn nearest_cloned<F>(&self, point: &[A], num: usize, distance: &F) -> Result<Vec<(A, T)>, ErrorKind>
+    where
+        F: Fn(&[A], &[A]) -> A,
+        T: Clone,
+    {
+        Ok(Self::cloned(self.nearest(point, num, distance)?))
+    }
+
+    /// Like [`within`](Self::within), but returning clones of the payloads.
+    pub fn within_cloned<F>(&self, point: &[A], radius: A, distance: &F) -> Result<Vec<(A, T)>, ErrorKind>
+    where
+        F: Fn(&[A], &[A]) -> A,
+        T: Clone,
+    {
+        Ok(Self::cloned(self.within(point, radius, distance)?))
+    }
+
+    fn cloned(found: Vec<(A, &T)>) -> Vec<(A, T)>
+    where
+        T: Clone,
+    {
+        found.into_iter().map(|(d, data)| (d, data.clone())).collect()
+    }
+
+    fn check_point(&self, point: &[A]) -> Result<(), ErrorKind> {
+        if self.size() > 0 && point.len() != self.dimensions {
+            return Err(ErrorKind::WrongDimension);
+        }
+        if point.iter().any(|c| !c.is_finite()) {
+            return Err(ErrorKind::NonFiniteCoordinate);
+        }
+        Ok(())
+    }
+
+    /// The root to start a traversal from, if the tree holds any point.
+    fn root(&self) -> Option<Visit<A>> {
+        if self.payloads.is_empty() {
+            return None;
+        }
+        Some(Visit {
+            index: 0,
+            start: 0,
+            cell: self.bounds.clone().into_boxed_slice(),
+        })
+    }
+
+    /// The children of a stem, their cells being split at its split value.
+    fn children(&self, visit: &Visit<A>) -> Vec<Visit<A>> {
+        match self.nodes[visit.index] {
+            Node::Stem {
+                split_value,
+                split_dimension,
+                right,
+                left_size,
+            } => {
+                let dim = split_dimension as usize;
+                let mut left = visit.cell.clone();
+                let mut right_cell = visit.cell.clone();
+                // points equal to the split value may be on either side
+                left[self.dimensions + dim] = left[self.dimensions + dim].min(split_value);
+                right_cell[dim] = right_cell[dim].max(split_value);
+                vec![
+                    Visit {
+                        index: visit.index + 1,
+                        start: visit.start,
+                        cell: left,
+                    },
+                    Visit {
+                        index: visit.index + right as usize,
+                        start: visit.start + left_size as usize,
+                        cell: right_cell,
+                    },
+                ]
+            }
+            Node::Leaf { .. } => vec![],
+        }
+    }
+
+    /// The `len` points starting at `start`.
+    fn points(&self, start: usize, len: usize) -> impl Iterator<Item = (&[A], &T)> {
+        let dims = self.dimensions;
+        self.coords[start * dims..(start + len) * dims]
+            .chunks(dims.max(1))
+            .zip(&self.payloads[start..start + len])
+    }
+
+    fn distance_to_cell<F>(&self, point: &[A], cell: &[A], distance: &F) -> A
+    where
+        F: Fn(&[A], &[A]) -> A,
+    {
+        let (min, max) = cell.split_at(self.dimensions);
+        util::distance_to_space(point, min, max, distance)
+    }
+
+    /// The `num` points nearest to `point` no farther than `max_dist`, nearest first, up to
+    /// `factor` as in [`KdTree::approx_nearest`].
+    fn search<F>(&self, point: &[A], num: usize, max_dist: A, factor: A, distance: &F) -> Vec<(A, &T)>
+    where
+        F: Fn(&[A], &[A]) -> A,
+    {
+        let root = match self.root() {
+            Some(root) if num > 0 => root,
+            _ => return vec![],
+        };
+        let mut pending = BinaryHeap::new();
+        let mut evaluated = BinaryHeap::<HeapElement<A, &T>>::new();
+        pending.push(HeapElement {
+            distance: A::zero(),
+            element: root,
+        });
+        while let Some(HeapElement {
+            distance: node_distance,
+            element: visit,
+        }) = pending.pop()
+        {
+            let bound = if evaluated.len() == num {
+                max_dist.min(evaluated.peek().unwrap().distance / factor)
+            } else {
+                max_dist
+            };
+            if -node_distance > bound {
+                break;
+            }
+            match self.nodes[visit.index] {
+                Node::Stem { .. } => {
+                    for child in self.children(&visit) {
+                        let to_cell = self.distance_to_cell(point, &child.cell, distance);
+                        if to_cell <= bound {
+                            pending.push(HeapElement {
+                                distance: -to_cell,
+                                element: child,
+                            });
+                        }
+                    }
+                }
+                Node::Leaf { len, .. } => {
+                    for (p, data) in self.points(visit.start, len as usize) {
+                        let element = HeapElement {
+                            distance: distance(point, p),
+                            element: data,
+                        };
+                        if element.distance > max_dist {
+                            continue;
+                        }
+                        if evaluated.len() < num {
+                            evaluated.push(element);
+                        } else if element < *evaluated.peek().unwrap() {
+                            evaluated.pop();
+                            evaluated.push(element);
+                        }
+                    }
+                }
+            }
+        }
+        evaluated.into_sorted_vec().into_iter().map(Into::into).collect()
+    }
+}
+
+/// All points of a [`FrozenKdTree`] ordered by their distance to a point, nearest first,
+/// created with [`FrozenKdTree::iter_nearest`].
+pub struct FrozenNearestIter<'a, 'b, A, T, F> {
+    tree: &'b FrozenKdTree<A, T>,
+    point: &'a [A],
+    pending: BinaryHeap<HeapElement<A, Visit<A>>>,
+    evaluated: BinaryHeap<HeapElement<A, &'b T>>,
+    distance: &'a F,
+}
+
+impl<'a, 'b, A: Float, T: PartialEq, F> Iterator for FrozenNearestIter<'a, 'b, A, T, F>
+where
+    F: Fn(&[A], &[A]) -> A,
+{
+    type Item = (A, &'b T);
+
+    fn next(&mut self) -> Option<(A, &'b T)> {
+        let (tree, point, distance) = (self.tree, self.point, self.distance);
+        // negated distances in both heaps, so the nearest node and point are on top
+        while let Some(next) = self.pending.peek() {
+            if self
+                .evaluated
+                .peek()
+                .map_or(false, |found| found.distance >= next.distance)
+            {
+                break;
+            }
+            let visit = self.pending.pop().unwrap().element;
+            match tree.nodes[visit.index] {
+                Node::Stem { .. } => {
+                    for child in tree.children(&visit) {
+                        self.pending.push(HeapElement {
+                            distance: -tree.distance_to_cell(point, &child.cell, distance),
+                            element: child,
+                        });
+                    }
+                }
+                Node::Leaf { len, .. } => {
+                    for (p, data) in tree.points(visit.start, len as usize) {
+                        self.evaluated.push(HeapElement {
+                            distance: -distance(point, p),
+                            element: data,
+                        });
+                    }
+                }
+            }
+        }
+        self.evaluated.pop().map(|found| (-found.distance, found.element))
+    }
+}
@@ -0,0 +1,128 @@
+//! Geospatial helpers controlling how latitude/longitude get converted before insertion.
+//!
+//! Mixing coordinate reference systems between insertion and queries silently produces wrong
+//! neighbours. [`GeoKdTree`] stores its [`Projection`] next to the points, so every insertion and
+//! every query go through the very same transform.
+//!
+//! Like [`SphereKdTree`](crate::SphereKdTree), every method takes latitude first, then longitude.
+
+use num_traits::Float;
+
+use crate::distance::squared_euclidean;
+use crate::kdtree::{ErrorKind, KdTree};
+
+/// Semi-major axis of the WGS84 ellipsoid in meters.
+pub const WGS84_A: f64 = 6_378_137.0;
+/// Flattening of the WGS84 ellipsoid.
+pub const WGS84_F: f64 = 1.0 / 298.257_223_563;
+/// Mean earth radius in meters.
+pub const EARTH_RADIUS: f64 = 6_371_008.8;
+
+/// How latitude/longitude in degrees are converted to tree coordinates.
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Projection<A> {
+    /// Longitude and latitude in degrees, used as they are, with longitude as the first tree
+    /// coordinate. Only suitable for small areas far from the poles and the antimeridian.
+    LonLat,
+    /// Earth-centered, earth-fixed cartesian coordinates in meters on the WGS84 ellipsoid, at
+    /// height zero. Distances are straight-line chord lengths through the earth, which rank
+    /// neighbours exactly like great-circle distances.
+    Ecef,
+    /// Equirectangular projection in meters around a reference latitude in degrees. Cheap and
+    /// accurate for areas spanning a few degrees around that latitude.
+    Equirectangular { reference_lat: A },
+}
+
+impl<A: Float> Projection<A> {
+    /// Number of tree dimensions produced by this projection.
+    pub fn dimensions(&self) -> usize {
+        match self {
+            Projection::Ecef => 3,
+            Projection::LonLat | Projection::Equirectangular { .. } => 2,
+        }
+    }
+
+    /// Convert latitude and longitude in degrees to tree coordinates.
+    pub fn project(&self, lat: A, lon: A) -> Vec<A> {
+        match *self {
+            Projection::LonLat => vec![lon, lat],
+            Projection::Ecef => {
+                let a = A::from(WGS84_A).unwrap();
+                let f = A::from(WGS84_F).unwrap();
+                let e2 = f * (A::from(2.0).unwrap() - f);
+                let (lon, lat) = (lon.to_radians(), lat.to_radians());
+                let n = a / (A::one() - e2 * lat.sin() * lat.sin()).sqrt();
+                vec![
+                    n * lat.cos() * lon.cos(),
+                    n * lat.cos() * lon.sin(),
+                    n * (A::one() - e2) * lat.sin(),
+                ]
+            }
+            Projection::Equirectangular { reference_lat } => {
+                let r = A::from(EARTH_RADIUS).unwrap();
+                vec![
+                    r * lon.to_radians() * reference_lat.to_radians().cos(),
+                    r * lat.to_radians(),
+                ]
+            }
+        }
+    }
+}
+
+/// A tree of latitude/longitude points, projected with a fixed [`Projection`].
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug)]
+pub struct GeoKdTree<A: PartialEq, T: PartialEq> {
+    tree: KdTree<A, T, Vec<A>>,
+    projection: Projection<A>,
+}
+
+impl<A: Float, T: PartialEq> GeoKdTree<A, T> {
+    pub fn new(projection: Projection<A>) -> Self {
+        GeoKdTree {
+            tree: KdTree::new(projection.dimensions()),
+            projection,
+        }
+    }
+
+    /// Create a new geo tree, specifying the projection and the capacity of leaf nodes
+    pub fn with_capacity(projection: Projection<A>, capacity: usize) -> Self {
+        GeoKdTree {
+            tree: KdTree::with_capacity(projection.dimensions(), capacity),
+            projection,
+        }
+    }
+
+    pub fn projection(&self) -> &Projection<A> {
+        &self.projection
+    }
+
+    pub fn size(&self) -> usize {
+        self.tree.size()
+    }
+
+    /// Add a point given by latitude and longitude in degrees.
+    pub fn add(&mut self, lat: A, lon: A, data: T) -> Result<(), ErrorKind> {
+        self.tree.add(self.projection.project(lat, lon), data)
+    }
+
+    /// The `num` points nearest to the given latitude and longitude, with their euclidean
+    /// distance in projected units (meters, or degrees for [`Projection::LonLat`]).
+    pub fn nearest(&self, lat: A, lon: A, num: usize) -> Result<Vec<(A, &T)>, ErrorKind> {
+        let point = self.projection.project(lat, lon);
+        let found = self.tree.nearest(&point, num, &squared_euclidean)?;
+        Ok(found.into_iter().map(|(d, data)| (d.sqrt(), data)).collect())
+    }
+
+    /// All points within `radius` projected units of the given latitude and longitude. A
+    /// negative or non-finite `radius` fails with [`ErrorKind::InvalidRadius`].
+    pub fn within(&self, lat: A, lon: A, radius: A) -> Result<Vec<(A, &T)>, ErrorKind> {
+        if !radius.is_finite() || radius < A::zero() {
+            return Err(ErrorKind::InvalidRadius);
+        }
+        let point = self.projection.project(lat, lon);
+        let found = self.tree.within(&point, radius * radius, &squared_euclidean)?;
+        Ok(found.into_iter().map(|(d, data)| (d.sqrt(), data)).collect())
+    }
+}
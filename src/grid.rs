@@ -0,0 +1,218 @@
+//! Regular grids over the tree's space, filled cell by cell from nearest neighbour queries.
+//!
+//! Cells are numbered with the first dimension varying fastest, so for a 2D grid the index of
+//! cell `(x, y)` is `x + y * resolution[0]`. A row is a run of cells along the first dimension.
+
+use num_traits::Float;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use crate::kdtree::{ErrorKind, KdTree};
+use crate::util;
+
+/// An axis-aligned box split into `resolution[i]` equally sized cells along each dimension `i`.
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Grid<A> {
+    min: Vec<A>,
+    max: Vec<A>,
+    resolution: Vec<usize>,
+}
+
+impl<A: Float> Grid<A> {
+    /// Create a grid spanning `min` to `max`, with `resolution[i]` cells along dimension `i`.
+    ///
+    /// Fails with [`ErrorKind::WrongDimension`] unless all three have the same, non-zero length.
+    pub fn new(min: Vec<A>, max: Vec<A>, resolution: Vec<usize>) -> Result<Self, ErrorKind> {
+        if min.is_empty() || min.len() != max.len() || min.len() != resolution.len() {
+            return Err(ErrorKind::WrongDimension);
+        }
+        if min.iter().chain(&max).any(|c| !c.is_finite()) {
+            return Err(ErrorKind::NonFiniteCoordinate);
+        }
+        Ok(Grid { min, max, resolution })
+    }
+
+    pub fn dimensions(&self) -> usize {
+        self.min.len()
+    }
+
+    pub fn resolution(&self) -> &[usize] {
+        &self.resolution
+    }
+
+    /// Total number of cells.
+    pub fn len(&self) -> usize {
+        self.resolution.iter().product()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Coordinates of the center of the cell at `index`.
+    pub fn cell_center(&self, mut index: usize) -> Vec<A> {
+        let half = A::from(0.5).unwrap();
+        (0..self.dimensions())
+            .map(|i| {
+                let cell = index % self.resolution[i];
+                index /= self.resolution[i];
+                let step = (self.max[i] - self.min[i]) / A::from(self.resolution[i]).unwrap();
+                self.min[i] + (A::from(cell).unwrap() + half) * step
+            })
+            .collect()
+    }
+
+    fn rows(&self) -> usize {
+        if self.is_empty() {
+            0
+        } else {
+            self.len() / self.resolution[0]
+        }
+    }
+}
+
+impl<A: Float, T: PartialEq, U: AsRef<[A]> + PartialEq> KdTree<A, T, U> {
+    /// The value of the payload nearest to each cell center of `grid`, in cell order.
+    ///
+    /// This is a rasterized Voronoi diagram of the stored points. Empty if the tree is.
+    pub fn rasterize_nearest<F, V, G>(&self, grid: &Grid<A>, distance: &F, value: G) -> Result<Vec<V>, ErrorKind>
+    where
+        F: Fn(&[A], &[A]) -> A,
+        G: Fn(&T) -> V,
+    {
+        self.check_grid(grid)?;
+        Ok((0..grid.rows())
+            .flat_map(|row| self.nearest_in_row(grid, row, distance))
+            .map(|(_, data)| value(data))
+            .collect())
+    }
+
+    /// As [`rasterize_nearest`](Self::rasterize_nearest), computing rows in parallel, still in cell
+    /// order.
+    #[cfg(feature = "rayon")]
+    pub fn par_rasterize_nearest<F, V, G>(&self, grid: &Grid<A>, distance: &F, value: G) -> Result<Vec<V>, ErrorKind>
+    where
+        A: Sync,
+        T: Sync,
+        U: Sync,
+        F: Fn(&[A], &[A]) -> A + Sync,
+        V: Send,
+        G: Fn(&T) -> V + Sync,
+    {
+        self.check_grid(grid)?;
+        let rows: Vec<Vec<V>> = (0..grid.rows())
+            .into_par_iter()
+            .map(|row| {
+                self.nearest_in_row(grid, row, distance)
+                    .into_iter()
+                    .map(|(_, data)| value(data))
+                    .collect()
+            })
+            .collect();
+        Ok(rows.into_iter().flatten().collect())
+    }
+
+    /// The distance from each cell center of `grid` to the nearest stored point, in cell order.
+    ///
+    /// Distances are as returned by `distance`, so take their square root for a euclidean
+    /// distance field when querying with [`squared_euclidean`](crate::distance::squared_euclidean).
+    /// Empty if the tree is.
+    pub fn distance_field<F>(&self, grid: &Grid<A>, distance: &F) -> Result<Vec<A>, ErrorKind>
+    where
+        F: Fn(&[A], &[A]) -> A,
+    {
+        self.check_grid(grid)?;
+        Ok((0..grid.rows())
+            .flat_map(|row| self.nearest_in_row(grid, row, distance))
+            .map(|(d, _)| d)
+            .collect())
+    }
+
+    /// As [`distance_field`](Self::distance_field), computing rows in parallel, still in cell
+    /// order.
+    #[cfg(feature = "rayon")]
+    pub fn par_distance_field<F>(&self, grid: &Grid<A>, distance: &F) -> Result<Vec<A>, ErrorKind>
+    where
+        A: Send + Sync,
+        T: Sync,
+        U: Sync,
+        F: Fn(&[A], &[A]) -> A + Sync,
+    {
+        self.check_grid(grid)?;
+        let rows: Vec<Vec<A>> = (0..grid.rows())
+            .into_par_iter()
+            .map(|row| {
+                self.nearest_in_row(grid, row, distance)
+                    .into_iter()
+                    .map(|(d, _)| d)
+                    .collect()
+            })
+            .collect();
+        Ok(rows.into_iter().flatten().collect())
+    }
+
+    fn check_grid(&self, grid: &Grid<A>) -> Result<(), ErrorKind> {
+        if self.size > 0 && grid.dimensions() != self.dimensions {
+            return Err(ErrorKind::WrongDimension);
+        }
+        Ok(())
+    }
+
+    /// The nearest point to each cell center of `row`, empty if the tree is.
+    ///
+    /// Neighbouring cells mostly share their nearest point, so each search starts out bounded by
+    /// the distance to the point found for the previous cell.
+    fn nearest_in_row<F>(&self, grid: &Grid<A>, row: usize, distance: &F) -> Vec<(A, &T)>
+    where
+        F: Fn(&[A], &[A]) -> A,
+    {
+        let start = row * grid.resolution[0];
+        let mut previous: Option<(&U, &T)> = None;
+        let mut found = Vec::with_capacity(grid.resolution[0]);
+        for cell in start..start + grid.resolution[0] {
+            let center = grid.cell_center(cell);
+            let mut best = previous.map(|(point, data)| (distance(&center, point.as_ref()), point, data));
+            self.nearest_bounded(&center, distance, &mut best);
+            if let Some((d, point, data)) = best {
+                found.push((d, data));
+                previous = Some((point, data));
+            }
+        }
+        found
+    }
+
+    /// Depth-first search for a point nearer to `point` than `best`, replacing it if found.
+    fn nearest_bounded<'a, F>(&'a self, point: &[A], distance: &F, best: &mut Option<(A, &'a U, &'a T)>)
+    where
+        F: Fn(&[A], &[A]) -> A,
+    {
+        if self.size == 0 {
+            return;
+        }
+        if let Some((bound, _, _)) = *best {
+            if util::distance_to_space(point, &self.min_bounds, &self.max_bounds, distance) >= bound {
+                return;
+            }
+        }
+        if self.is_leaf() {
+            let points = self.points.as_ref().unwrap().iter();
+            let bucket = self.bucket.as_ref().unwrap().iter();
+            for (p, data) in points.zip(bucket) {
+                let d = distance(point, p.as_ref());
+                if best.map_or(true, |(bound, _, _)| d < bound) {
+                    *best = Some((d, p, data));
+                }
+            }
+        } else {
+            let (left, right) = (self.left.as_deref().unwrap(), self.right.as_deref().unwrap());
+            let (near, far) = if self.belongs_in_left(point) {
+                (left, right)
+            } else {
+                (right, left)
+            };
+            near.nearest_bounded(point, distance, best);
+            far.nearest_bounded(point, distance, best);
+        }
+    }
+}
@@ -0,0 +1,88 @@
+//! Handles on entries that notice when the tree changed under them.
+//!
+//! Indices into the order of [`KdTree::iter`] silently refer to other entries once points are
+//! added or removed. An [`EntryHandle`] remembers the revision of the tree it was handed out for,
+//! and using it with any other revision fails with [`ErrorKind::StaleHandle`].
+//!
+//! Revisions are unique across all trees of a process: a tree takes a fresh one whenever it is
+//! created or entries move to other indices. Handles thus also fail on rebuilt trees and on
+//! clones modified since, while copies that are still the same keep accepting them. Node IDs,
+//! as in [`SplitPlane`](crate::SplitPlane), need no tagging, as they are never reused within a
+//! tree.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use num_traits::Float;
+
+use crate::kdtree::{ErrorKind, KdTree};
+
+static REVISIONS: AtomicU64 = AtomicU64::new(1);
+
+pub(crate) fn next_revision() -> u64 {
+    REVISIONS.fetch_add(1, Ordering::Relaxed)
+}
+
+/// An index into the order of [`KdTree::iter`], valid for one revision of a tree.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct EntryHandle {
+    index: usize,
+    revision: u64,
+}
+
+impl EntryHandle {
+    /// The index of the entry, in the order of [`KdTree::iter`] at the time the handle was
+    /// handed out.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+impl<A: Float, T: PartialEq, U: AsRef<[A]> + PartialEq> KdTree<A, T, U> {
+    /// The current revision, changing whenever entries move to other indices.
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// A handle on the entry at `index` in the order of [`iter`](Self::iter), `None` if there is
+    /// no such entry.
+    pub fn handle(&self, index: usize) -> Option<EntryHandle> {
+        if index < self.size {
+            Some(EntryHandle {
+                index,
+                revision: self.revision,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// The `num` nearest neighbours of `point`, nearest first, as handles.
+    pub fn nearest_handles<F>(&self, point: &[A], num: usize, distance: &F) -> Result<Vec<(A, EntryHandle)>, ErrorKind>
+    where
+        F: Fn(&[A], &[A]) -> A,
+    {
+        self.check_point(point)?;
+        Ok(self
+            .nearest_indexed(point, num, A::infinity(), distance, |_| true)
+            .into_iter()
+            .map(|(d, index, _)| {
+                let handle = EntryHandle {
+                    index,
+                    revision: self.revision,
+                };
+                (d, handle)
+            })
+            .collect())
+    }
+
+    /// The point and data `handle` refers to.
+    ///
+    /// Fails with [`ErrorKind::StaleHandle`] if the handle was handed out for another revision
+    /// of the tree, or by another tree.
+    pub fn get(&self, handle: EntryHandle) -> Result<(&U, &T), ErrorKind> {
+        if handle.revision != self.revision {
+            return Err(ErrorKind::StaleHandle);
+        }
+        self.get_by_index(handle.index).ok_or(ErrorKind::StaleHandle)
+    }
+}
@@ -0,0 +1,103 @@
+//! Convex hull extraction.
+
+use num_traits::Float;
+
+use crate::kdtree::{ErrorKind, KdTree};
+
+fn cross<A: Float>(o: &[A], a: &[A], b: &[A]) -> A {
+    (a[0] - o[0]) * (b[1] - o[1]) - (a[1] - o[1]) * (b[0] - o[0])
+}
+
+/// Andrew's monotone chain, returning the hull counter-clockwise without collinear points.
+fn monotone_chain<A: Float, P: Copy, F: Fn(P) -> [A; 2]>(mut points: Vec<P>, coords: F) -> Vec<P> {
+    points.sort_by(|a, b| {
+        let (a, b) = (coords(*a), coords(*b));
+        a[0].partial_cmp(&b[0]).unwrap().then(a[1].partial_cmp(&b[1]).unwrap())
+    });
+    if points.len() < 3 {
+        points.dedup_by(|a, b| coords(*a) == coords(*b));
+        return points;
+    }
+    let mut hull: Vec<P> = Vec::with_capacity(2 * points.len());
+    for pass in 0..2 {
+        let start = hull.len();
+        for &p in points.iter() {
+            while hull.len() >= start + 2
+                && cross(&coords(hull[hull.len() - 2]), &coords(hull[hull.len() - 1]), &coords(p)) <= A::zero()
+            {
+                hull.pop();
+            }
+            hull.push(p);
+        }
+        hull.pop();
+        if pass == 0 {
+            points.reverse();
+        }
+    }
+    hull
+}
+
+fn strictly_inside<A: Float>(polygon: &[[A; 2]], point: &[A]) -> bool {
+    polygon.len() >= 3
+        && (0..polygon.len()).all(|i| cross(&polygon[i], &polygon[(i + 1) % polygon.len()], point) > A::zero())
+}
+
+impl<A: Float, T: PartialEq, U: AsRef<[A]> + PartialEq> KdTree<A, T, U> {
+    /// The convex hull of a two dimensional tree, counter-clockwise starting from the lowest
+    /// leftmost point.
+    ///
+    /// Collinear points on the hull and duplicates of hull points are left out. The extreme points
+    /// in eight directions span an initial polygon; subtrees whose bounds lie strictly inside of
+    /// it are discarded without being visited, and the exact hull is computed on the surviving
+    /// points only.
+    pub fn convex_hull_2d(&self) -> Result<Vec<(&U, &T)>, ErrorKind> {
+        if self.dimensions != 2 {
+            return Err(ErrorKind::WrongDimension);
+        }
+        let (one, zero) = (A::one(), A::zero());
+        let directions = [
+            [one, zero],
+            [one, one],
+            [zero, one],
+            [-one, one],
+            [-one, zero],
+            [-one, -one],
+            [zero, -one],
+            [one, -one],
+        ];
+        let extremes: Vec<&U> = directions
+            .iter()
+            .flat_map(|direction| self.top_k_by_linear_points(direction, 1))
+            .map(|(_, point, _)| point)
+            .collect();
+        let polygon = monotone_chain(extremes.iter().map(|p| [p.as_ref()[0], p.as_ref()[1]]).collect(), |p| p);
+
+        let mut candidates = vec![];
+        let mut pending = vec![self];
+        while let Some(node) = pending.pop() {
+            if node.size == 0 {
+                continue;
+            }
+            let (min, max) = (&node.min_bounds, &node.max_bounds);
+            let corners = [[min[0], min[1]], [min[0], max[1]], [max[0], min[1]], [max[0], max[1]]];
+            if corners.iter().all(|corner| strictly_inside(&polygon, corner)) {
+                continue;
+            }
+            if node.is_leaf() {
+                let points = node.points.as_ref().unwrap().iter();
+                let bucket = node.bucket.as_ref().unwrap().iter();
+                candidates.extend(
+                    points
+                        .zip(bucket)
+                        .filter(|(p, _)| !strictly_inside(&polygon, p.as_ref())),
+                );
+            } else {
+                pending.extend(node.left.as_deref());
+                pending.extend(node.right.as_deref());
+            }
+        }
+        Ok(monotone_chain(candidates, |(p, _): (&U, &T)| {
+            [p.as_ref()[0], p.as_ref()[1]]
+        }))
+    }
+}
@@ -0,0 +1,173 @@
+//! Convex hull extraction in three dimensions.
+
+use num_traits::Float;
+
+use crate::kdtree::{ErrorKind, KdTree};
+
+fn sub<A: Float>(a: &[A; 3], b: &[A; 3]) -> [A; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn cross<A: Float>(a: &[A; 3], b: &[A; 3]) -> [A; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+fn dot<A: Float>(a: &[A; 3], b: &[A; 3]) -> A {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+/// Six times the signed volume of the tetrahedron `a b c p`, positive if `p` is on the side of
+/// the triangle `a b c` it is counter-clockwise from.
+fn orient<A: Float>(a: &[A; 3], b: &[A; 3], c: &[A; 3], p: &[A; 3]) -> A {
+    dot(&cross(&sub(b, a), &sub(c, a)), &sub(p, a))
+}
+
+/// The first index maximizing `key`, if its value is positive.
+fn arg_max<A: Float, F: Fn(usize) -> A>(len: usize, key: F) -> Option<usize> {
+    let (best, value) =
+        (0..len).map(|i| (i, key(i))).fold(
+            (0, A::zero()),
+            |best, candidate| {
+                if candidate.1 > best.1 {
+                    candidate
+                } else {
+                    best
+                }
+            },
+        );
+    if value > A::zero() {
+        Some(best)
+    } else {
+        None
+    }
+}
+
+/// Incremental convex hull, returning triangles counter-clockwise seen from outside, without
+/// points on the faces of the hull. Empty if all points are coplanar.
+fn incremental_hull<A: Float, P: Copy, F: Fn(P) -> [A; 3]>(points: Vec<P>, coords: F) -> Vec<[P; 3]> {
+    let at: Vec<[A; 3]> = points.iter().map(|&p| coords(p)).collect();
+    if at.is_empty() {
+        return vec![];
+    }
+    // an initial tetrahedron of points as far apart as possible
+    let a = 0;
+    let b = match arg_max(at.len(), |i| dot(&sub(&at[i], &at[a]), &sub(&at[i], &at[a]))) {
+        Some(b) => b,
+        None => return vec![],
+    };
+    let c = match arg_max(at.len(), |i| {
+        let normal = cross(&sub(&at[b], &at[a]), &sub(&at[i], &at[a]));
+        dot(&normal, &normal)
+    }) {
+        Some(c) => c,
+        None => return vec![],
+    };
+    let d = match arg_max(at.len(), |i| orient(&at[a], &at[b], &at[c], &at[i]).abs()) {
+        Some(d) => d,
+        None => return vec![],
+    };
+    let (b, c) = if orient(&at[a], &at[b], &at[c], &at[d]) > A::zero() {
+        (c, b)
+    } else {
+        (b, c)
+    };
+    let mut faces = vec![[a, b, c], [a, d, b], [b, d, c], [c, d, a]];
+
+    for p in 0..at.len() {
+        if p == a || p == b || p == c || p == d {
+            continue;
+        }
+        let side = |f: &[usize; 3]| orient(&at[f[0]], &at[f[1]], &at[f[2]], &at[p]);
+        if faces.iter().all(|f| side(f) <= A::zero()) {
+            continue;
+        }
+        // faces in a plane with `p` are replaced too, so points inside of a face of the final
+        // hull that were added before its corners do not stay on it
+        let (visible, hidden): (Vec<[usize; 3]>, Vec<[usize; 3]>) =
+            faces.into_iter().partition(|f| side(f) >= A::zero());
+        faces = hidden;
+        // edges of the visible region not shared by two visible faces form its horizon
+        let edges: Vec<(usize, usize)> = visible
+            .iter()
+            .flat_map(|f| vec![(f[0], f[1]), (f[1], f[2]), (f[2], f[0])])
+            .collect();
+        for &(from, to) in &edges {
+            if !edges.contains(&(to, from)) {
+                faces.push([from, to, p]);
+            }
+        }
+    }
+    faces
+        .into_iter()
+        .map(|f| [points[f[0]], points[f[1]], points[f[2]]])
+        .collect()
+}
+
+impl<A: Float, T: PartialEq, U: AsRef<[A]> + PartialEq> KdTree<A, T, U> {
+    /// The convex hull of a three dimensional tree, as triangles whose points are
+    /// counter-clockwise seen from outside of the hull.
+    ///
+    /// Points on the faces of the hull and duplicates of hull points are left out, and the hull
+    /// of points all lying in one plane is empty. The extreme points in 26 directions span an
+    /// initial polytope; subtrees whose bounds lie strictly inside of it are discarded without
+    /// being visited, and the exact hull is computed on the surviving points only.
+    pub fn convex_hull_3d(&self) -> Result<Vec<[(&U, &T); 3]>, ErrorKind> {
+        if self.dimensions != 3 {
+            return Err(ErrorKind::WrongDimension);
+        }
+        let coords = |p: &[A]| [p[0], p[1], p[2]];
+        let steps = [-A::one(), A::zero(), A::one()];
+        let mut extremes: Vec<[A; 3]> = vec![];
+        for &x in &steps {
+            for &y in &steps {
+                for &z in &steps {
+                    if x == A::zero() && y == A::zero() && z == A::zero() {
+                        continue;
+                    }
+                    for (_, point, _) in self.top_k_by_linear_points(&[x, y, z], 1) {
+                        if !extremes.contains(&coords(point.as_ref())) {
+                            extremes.push(coords(point.as_ref()));
+                        }
+                    }
+                }
+            }
+        }
+        let polytope = incremental_hull(extremes, |p| p);
+        let strictly_inside = |point: &[A; 3]| {
+            !polytope.is_empty() && polytope.iter().all(|f| orient(&f[0], &f[1], &f[2], point) < A::zero())
+        };
+
+        let mut candidates = vec![];
+        let mut pending = vec![self];
+        while let Some(node) = pending.pop() {
+            if node.size == 0 {
+                continue;
+            }
+            let (min, max) = (&node.min_bounds, &node.max_bounds);
+            let corners = (0..8).map(|k| {
+                let pick = |dim: usize| if k & (1 << dim) == 0 { min[dim] } else { max[dim] };
+                [pick(0), pick(1), pick(2)]
+            });
+            if corners.into_iter().all(|corner| strictly_inside(&corner)) {
+                continue;
+            }
+            if node.is_leaf() {
+                let points = node.points.as_ref().unwrap().iter();
+                let bucket = node.bucket.as_ref().unwrap().iter();
+                candidates.extend(
+                    points
+                        .zip(bucket)
+                        .filter(|(p, _)| !strictly_inside(&coords(p.as_ref()))),
+                );
+            } else {
+                pending.extend(node.left.as_deref());
+                pending.extend(node.right.as_deref());
+            }
+        }
+        Ok(incremental_hull(candidates, |(p, _): (&U, &T)| coords(p.as_ref())))
+    }
+}
@@ -0,0 +1,164 @@
+//! Nearest neighbour searches aware of point indices.
+//!
+//! Indices refer to the order of [`KdTree::iter`]: walking down, the left child of a node holds
+//! the indices starting at the node's own, the right child those following the left child's
+//! `size`.
+
+use std::collections::BinaryHeap;
+
+use num_traits::Float;
+
+use crate::heap_element::HeapElement;
+use crate::kdtree::{ErrorKind, KdTree};
+use crate::util;
+
+impl<A: Float, T: PartialEq, U: AsRef<[A]> + PartialEq> KdTree<A, T, U> {
+    /// The `num` points nearest to `point`, as [`nearest`](Self::nearest), but skipping the
+    /// stored point at index `excluded`.
+    ///
+    /// Querying every stored point with its own index excluded gives leave-one-out evaluation
+    /// without building one tree per point or fetching an extra neighbour. Indices refer to the
+    /// order of [`iter`](Self::iter).
+    pub fn nearest_excluding_index<F>(
+        &self,
+        point: &[A],
+        num: usize,
+        distance: &F,
+        excluded: usize,
+    ) -> Result<Vec<(A, &T)>, ErrorKind>
+    where
+        F: Fn(&[A], &[A]) -> A,
+    {
+        self.check_point(point)?;
+        Ok(self
+            .nearest_indexed(point, num, A::infinity(), distance, |i| i != excluded)
+            .into_iter()
+            .map(|(d, _, data)| (d, data))
+            .collect())
+    }
+
+    /// The `num` points nearest to `point` no farther than `max_dist` whose index passes `keep`,
+    /// nearest first, with their index.
+    pub(crate) fn nearest_indexed<F, P>(
+        &self,
+        point: &[A],
+        num: usize,
+        max_dist: A,
+        distance: &F,
+        keep: P,
+    ) -> Vec<(A, usize, &T)>
+    where
+        F: Fn(&[A], &[A]) -> A,
+        P: Fn(usize) -> bool,
+    {
+        self.nearest_indexed_in(point, num, max_dist, distance, keep, |_, _| true)
+    }
+
+    /// Like [`nearest_indexed`](Self::nearest_indexed), but skipping every subtree whose indices
+    /// `start..end` do not pass `keep_range`.
+    pub(crate) fn nearest_indexed_in<F, P, R>(
+        &self,
+        point: &[A],
+        num: usize,
+        max_dist: A,
+        distance: &F,
+        keep: P,
+        keep_range: R,
+    ) -> Vec<(A, usize, &T)>
+    where
+        F: Fn(&[A], &[A]) -> A,
+        P: Fn(usize) -> bool,
+        R: Fn(usize, usize) -> bool,
+    {
+        let to_box = |point: &[A], min: &[A], max: &[A]| util::distance_to_space(point, min, max, distance);
+        self.nearest_indexed_by(point, num, max_dist, distance, keep, keep_range, to_box)
+    }
+
+    /// Like [`nearest_indexed_in`](Self::nearest_indexed_in), but measuring the distance from
+    /// `point` to the bounds of a subtree, from `min` to `max`, with `to_box`. It must not exceed
+    /// the distance to any point within the bounds, for spaces where the nearest point of a box
+    /// is not found by clamping, like periodic ones.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn nearest_indexed_by<F, P, R, B>(
+        &self,
+        point: &[A],
+        num: usize,
+        max_dist: A,
+        distance: &F,
+        keep: P,
+        keep_range: R,
+        to_box: B,
+    ) -> Vec<(A, usize, &T)>
+    where
+        F: Fn(&[A], &[A]) -> A,
+        P: Fn(usize) -> bool,
+        R: Fn(usize, usize) -> bool,
+        B: Fn(&[A], &[A], &[A]) -> A,
+    {
+        if num == 0 || self.size == 0 {
+            return vec![];
+        }
+        let mut pending = BinaryHeap::new();
+        let mut evaluated = BinaryHeap::<HeapElement<A, (usize, &T)>>::new();
+        pending.push(HeapElement {
+            distance: A::zero(),
+            element: (self, 0),
+        });
+        while let Some(HeapElement {
+            distance: node_distance,
+            element: (node, base),
+        }) = pending.pop()
+        {
+            let bound = if evaluated.len() == num {
+                max_dist.min(evaluated.peek().unwrap().distance)
+            } else {
+                max_dist
+            };
+            if -node_distance > bound {
+                break;
+            }
+            if node.is_leaf() {
+                let points = node.points.as_ref().unwrap().iter();
+                let bucket = node.bucket.as_ref().unwrap().iter();
+                for (i, (p, data)) in points.zip(bucket).enumerate() {
+                    if !keep(base + i) {
+                        continue;
+                    }
+                    let element = HeapElement {
+                        distance: distance(point, p.as_ref()),
+                        element: (base + i, data),
+                    };
+                    if element.distance > max_dist {
+                        continue;
+                    }
+                    if evaluated.len() < num {
+                        evaluated.push(element);
+                    } else if element < *evaluated.peek().unwrap() {
+                        evaluated.pop();
+                        evaluated.push(element);
+                    }
+                }
+            } else {
+                let left = node.left.as_deref().unwrap();
+                let right = node.right.as_deref().unwrap();
+                for &(child, child_base) in &[(left, base), (right, base + left.size)] {
+                    if child.size == 0 || !keep_range(child_base, child_base + child.size) {
+                        continue;
+                    }
+                    let to_space = to_box(point, &child.min_bounds, &child.max_bounds);
+                    if to_space <= bound {
+                        pending.push(HeapElement {
+                            distance: -to_space,
+                            element: (child, child_base),
+                        });
+                    }
+                }
+            }
+        }
+        evaluated
+            .into_sorted_vec()
+            .into_iter()
+            .map(|e| (e.distance, e.element.0, e.element.1))
+            .collect()
+    }
+}
@@ -0,0 +1,109 @@
+//! A tree that stores each distinct payload only once.
+//!
+//! Useful when millions of points share a few hundred distinct payloads, like class labels or
+//! material ids: leaves only hold a small id per point, and queries still return `&T`.
+
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::hash::{BuildHasher, Hash, Hasher};
+
+use num_traits::Float;
+
+use crate::kdtree::{ErrorKind, KdTree};
+
+#[derive(Clone, Debug)]
+pub struct InternedKdTree<A, T: Eq + Hash, U: AsRef<[A]> + PartialEq> {
+    tree: KdTree<A, u32, U>,
+    payloads: Vec<T>,
+    // ids of the payloads by their hash, so that each payload is only held in `payloads`
+    ids: HashMap<u64, Vec<u32>>,
+    hasher: RandomState,
+}
+
+impl<A: Float, T: Eq + Hash, U: AsRef<[A]> + PartialEq> InternedKdTree<A, T, U> {
+    /// Create a new interned KD tree, specifying the dimension size of each point
+    pub fn new(dims: usize) -> Self {
+        InternedKdTree::from_tree(KdTree::new(dims))
+    }
+
+    /// Create a new interned KD tree, specifying the dimension size of each point and the capacity
+    /// of leaf nodes
+    pub fn with_capacity(dimensions: usize, capacity: usize) -> Self {
+        InternedKdTree::from_tree(KdTree::with_capacity(dimensions, capacity))
+    }
+
+    fn from_tree(tree: KdTree<A, u32, U>) -> Self {
+        InternedKdTree {
+            tree,
+            payloads: vec![],
+            ids: HashMap::new(),
+            hasher: RandomState::new(),
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.tree.size()
+    }
+
+    /// All distinct payloads added so far, in order of their first insertion.
+    ///
+    /// Payloads stay interned even after every point referring to them got removed.
+    pub fn distinct_payloads(&self) -> &[T] {
+        &self.payloads
+    }
+
+    pub fn add(&mut self, point: U, data: T) -> Result<(), ErrorKind> {
+        let hash = self.hash(&data);
+        match self.find(hash, &data) {
+            Some(id) => self.tree.add(point, id),
+            None => {
+                let id = u32::try_from(self.payloads.len()).map_err(|_| ErrorKind::TooManyPayloads)?;
+                self.tree.add(point, id)?;
+                self.payloads.push(data);
+                self.ids.entry(hash).or_default().push(id);
+                Ok(())
+            }
+        }
+    }
+
+    pub fn remove(&mut self, point: &U, data: &T) -> Result<usize, ErrorKind> {
+        self.tree.check_point(point.as_ref())?;
+        match self.find(self.hash(data), data) {
+            Some(id) => self.tree.remove(point, &id),
+            None => Ok(0),
+        }
+    }
+
+    pub fn nearest<F>(&self, point: &[A], num: usize, distance: &F) -> Result<Vec<(A, &T)>, ErrorKind>
+    where
+        F: Fn(&[A], &[A]) -> A,
+    {
+        Ok(self.resolve(self.tree.nearest(point, num, distance)?))
+    }
+
+    pub fn within<F>(&self, point: &[A], radius: A, distance: &F) -> Result<Vec<(A, &T)>, ErrorKind>
+    where
+        F: Fn(&[A], &[A]) -> A,
+    {
+        Ok(self.resolve(self.tree.within(point, radius, distance)?))
+    }
+
+    fn hash(&self, data: &T) -> u64 {
+        let mut hasher = self.hasher.build_hasher();
+        data.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn find(&self, hash: u64, data: &T) -> Option<u32> {
+        let ids = self.ids.get(&hash)?;
+        ids.iter().copied().find(|&id| self.payloads[id as usize] == *data)
+    }
+
+    fn resolve(&self, found: Vec<(A, &u32)>) -> Vec<(A, &T)> {
+        found
+            .into_iter()
+            .map(|(distance, &id)| (distance, &self.payloads[id as usize]))
+            .collect()
+    }
+}
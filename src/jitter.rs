@@ -0,0 +1,162 @@
+//! Spreading out duplicate points to keep trees balanced.
+//!
+//! Leaves whose points all share the same coordinates cannot be split, so heavily quantized input
+//! like pixel grids piles up in a few huge leaves. [`JitteredKdTree`] moves every point added at
+//! the coordinates of a stored point by a tiny, deterministic offset before it goes into the tree,
+//! while results still refer to the original coordinates.
+
+use std::cmp::Ordering;
+
+use num_traits::Float;
+
+use crate::kdtree::{ErrorKind, KdTree};
+use crate::util;
+
+#[derive(Clone, Debug)]
+pub struct JitteredKdTree<A: PartialEq, T: PartialEq, U: AsRef<[A]> + PartialEq> {
+    /// Points at their jittered coordinates, with their original coordinates as part of the
+    /// payload.
+    tree: KdTree<A, (U, T), Vec<A>>,
+    magnitude: A,
+    seed: u64,
+    /// Number of points jittered so far, so every one of them gets a different offset.
+    jittered: u64,
+}
+
+impl<A: Float, T: PartialEq, U: AsRef<[A]> + PartialEq> JitteredKdTree<A, T, U> {
+    /// Create a new tree, specifying the dimension size of each point, and moving duplicates by
+    /// up to `magnitude` in every coordinate, with offsets derived from `seed`.
+    ///
+    /// Fails with [`ErrorKind::InvalidJitter`] unless `magnitude` is finite and zero or positive.
+    pub fn new(dims: usize, magnitude: A, seed: u64) -> Result<Self, ErrorKind> {
+        JitteredKdTree::with_capacity(dims, 2_usize.pow(4), magnitude, seed)
+    }
+
+    /// Like [`new`](Self::new), specifying the capacity of leaf nodes.
+    pub fn with_capacity(dimensions: usize, capacity: usize, magnitude: A, seed: u64) -> Result<Self, ErrorKind> {
+        if !(magnitude >= A::zero() && magnitude.is_finite()) {
+            return Err(ErrorKind::InvalidJitter);
+        }
+        Ok(JitteredKdTree {
+            tree: KdTree::with_capacity(dimensions, capacity),
+            magnitude,
+            seed,
+            jittered: 0,
+        })
+    }
+
+    pub fn size(&self) -> usize {
+        self.tree.size()
+    }
+
+    /// The tree holding the jittered points, for queries not wrapped here.
+    pub fn tree(&self) -> &KdTree<A, (U, T), Vec<A>> {
+        &self.tree
+    }
+
+    /// Set the leaf limit of the tree holding the jittered points, see
+    /// [`KdTree::set_leaf_limit`].
+    pub fn set_leaf_limit(&mut self, limit: Option<usize>) {
+        self.tree.set_leaf_limit(limit);
+    }
+
+    /// Reject points exceeding the leaf limit, see [`KdTree::set_strict`].
+    pub fn set_strict(&mut self, strict: bool) {
+        self.tree.set_strict(strict);
+    }
+
+    /// All points at their original coordinates and their data, in the order of
+    /// [`KdTree::iter`].
+    pub fn iter(&self) -> impl Iterator<Item = (&U, &T)> {
+        self.tree.iter().map(|(_, (point, data))| (point, data))
+    }
+
+    /// Add `data` at `point`, jittering the coordinates it goes into the tree with if a point is
+    /// stored at exactly these coordinates already.
+    pub fn add(&mut self, point: U, data: T) -> Result<(), ErrorKind> {
+        self.tree.check_point(point.as_ref())?;
+        let mut coordinates = point.as_ref().to_vec();
+        if self.magnitude > A::zero() && !self.stored_at(point.as_ref()).is_empty() {
+            self.jittered += 1;
+            for (i, x) in coordinates.iter_mut().enumerate() {
+                *x = *x + self.offset(self.jittered, i);
+            }
+        }
+        self.tree.add(coordinates, (point, data))
+    }
+
+    /// Remove all entries holding `data` at the original coordinates `point`, returning how many
+    /// were removed.
+    pub fn remove(&mut self, point: &U, data: &T) -> Result<usize, ErrorKind>
+    where
+        U: Clone,
+        T: Clone,
+    {
+        self.tree.check_point(point.as_ref())?;
+        let entry = (point.clone(), data.clone());
+        let mut removed = 0;
+        for coordinates in self.stored_at(point.as_ref()) {
+            removed += self.tree.remove(&coordinates, &entry)?;
+        }
+        Ok(removed)
+    }
+
+    /// The `num` nearest neighbours of `point`, nearest first, with distances to their original
+    /// coordinates.
+    ///
+    /// The neighbours are found by their jittered coordinates, so when several points are about
+    /// as far away as the `num`th neighbour, a slightly farther one may be returned instead.
+    pub fn nearest<F>(&self, point: &[A], num: usize, distance: &F) -> Result<Vec<(A, &T)>, ErrorKind>
+    where
+        F: Fn(&[A], &[A]) -> A,
+    {
+        let found = self.tree.nearest(point, num, distance)?;
+        Ok(Self::original_distances(point, found, distance))
+    }
+
+    /// All points within `radius` of `point` by their original coordinates, nearest first.
+    ///
+    /// The points are found by their jittered coordinates, so duplicates lying within the jitter
+    /// of the border of the ball may be missed.
+    pub fn within<F>(&self, point: &[A], radius: A, distance: &F) -> Result<Vec<(A, &T)>, ErrorKind>
+    where
+        F: Fn(&[A], &[A]) -> A,
+    {
+        let found = self.tree.within(point, radius, distance)?;
+        let mut found = Self::original_distances(point, found, distance);
+        found.retain(|&(d, _)| d <= radius);
+        Ok(found)
+    }
+
+    fn original_distances<'a, F>(point: &[A], found: Vec<(A, &'a (U, T))>, distance: &F) -> Vec<(A, &'a T)>
+    where
+        F: Fn(&[A], &[A]) -> A,
+    {
+        let mut found: Vec<_> = found
+            .into_iter()
+            .map(|(_, (original, data))| (distance(point, original.as_ref()), data))
+            .collect();
+        found.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+        found
+    }
+
+    /// The jittered coordinates of all points stored at the original coordinates `point`.
+    fn stored_at(&self, point: &[A]) -> Vec<Vec<A>> {
+        let min: Vec<A> = point.iter().map(|&x| x - self.magnitude).collect();
+        let max: Vec<A> = point.iter().map(|&x| x + self.magnitude).collect();
+        self.tree
+            .within_box(&min, &max)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|(_, (original, _))| original.as_ref() == point)
+            .map(|(coordinates, _)| coordinates.clone())
+            .collect()
+    }
+
+    /// The offset of coordinate `i` of the `n`th jittered point, up to the magnitude either way.
+    fn offset(&self, n: u64, i: usize) -> A {
+        let bits = util::splitmix64(self.seed ^ util::splitmix64(n.wrapping_mul(0x1_0000_0001).wrapping_add(i as u64)));
+        let unit = (bits >> 11) as f64 / (1u64 << 53) as f64;
+        self.magnitude * A::from(2.0 * unit - 1.0).unwrap()
+    }
+}
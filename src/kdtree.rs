@@ -1,31 +1,57 @@
 use std::collections::BinaryHeap;
 
-use num_traits::{Float, One, Zero};
+use num_traits::Float;
 use thiserror::Error;
 
+use crate::axis::Axis;
+use crate::handle;
 use crate::heap_element::HeapElement;
+use crate::partition::{ChangeLog, StructureChange};
+use crate::stats::Summary;
+use crate::tolerance::Tolerance;
 use crate::util;
 
 #[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 #[derive(Clone, Debug)]
 pub struct KdTree<A, T: std::cmp::PartialEq, U: AsRef<[A]> + std::cmp::PartialEq> {
     // node
-    left: Option<Box<KdTree<A, T, U>>>,
-    right: Option<Box<KdTree<A, T, U>>>,
+    pub(crate) left: Option<Box<KdTree<A, T, U>>>,
+    pub(crate) right: Option<Box<KdTree<A, T, U>>>,
     // common
-    dimensions: usize,
-    capacity: usize,
-    size: usize,
-    min_bounds: Box<[A]>,
-    max_bounds: Box<[A]>,
+    pub(crate) dimensions: usize,
+    pub(crate) capacity: usize,
+    pub(crate) size: usize,
+    pub(crate) min_bounds: Box<[A]>,
+    pub(crate) max_bounds: Box<[A]>,
     // stem
-    split_value: Option<A>,
-    split_dimension: Option<usize>,
+    pub(crate) split_value: Option<A>,
+    pub(crate) split_dimension: Option<usize>,
+    // diagnostics, whether the leaf could not be split at some point
+    #[cfg_attr(feature = "serialize", serde(default))]
+    pub(crate) unsplittable: bool,
+    // identity, the change log is only kept up to date in the root
+    #[cfg_attr(feature = "serialize", serde(default))]
+    pub(crate) id: u64,
+    #[cfg_attr(feature = "serialize", serde(default))]
+    pub(crate) changes: ChangeLog,
+    // changes whenever entries move to other indices, only kept up to date in the root
+    #[cfg_attr(feature = "serialize", serde(skip, default = "handle::next_revision"))]
+    pub(crate) revision: u64,
+    // limits, only kept in the root
+    #[cfg_attr(feature = "serialize", serde(default))]
+    pub(crate) leaf_limit: Option<usize>,
+    #[cfg_attr(feature = "serialize", serde(default))]
+    pub(crate) strict: bool,
     // leaf
-    points: Option<Vec<U>>,
-    bucket: Option<Vec<T>>,
+    pub(crate) points: Option<Vec<U>>,
+    pub(crate) bucket: Option<Vec<T>>,
+    #[cfg_attr(feature = "serialize", serde(skip, default = "Option::default"))]
+    pub(crate) summary: Option<Box<Summary<A>>>,
 }
 
+/// The entries of the left and the right child of a stem being built.
+type Halves<U, T> = (Vec<(U, T)>, Vec<(U, T)>);
+
 #[derive(Error, Debug, PartialEq, Eq)]
 pub enum ErrorKind {
     #[error("wrong dimension")]
@@ -34,18 +60,69 @@ pub enum ErrorKind {
     NonFiniteCoordinate,
     #[error("zero capacity")]
     ZeroCapacity,
+    #[error("too many distinct payloads")]
+    TooManyPayloads,
+    #[error("wrong dimension of point {0}")]
+    WrongDimensionAt(usize),
+    #[error("non-finite coordinate in point {0}")]
+    NonFiniteCoordinateAt(usize),
+    #[error("coordinate outside of the domain")]
+    OutOfDomain,
+    #[error("invalid domain")]
+    InvalidDomain,
+    #[error("radius must be positive and grow by a factor greater than one")]
+    InvalidRadiusGrowth,
+    #[error("wrong length of output buffer")]
+    WrongOutputLength,
+    #[error("a leaf would hold more than {0} points that cannot be split; deduplicate the points or jitter their coordinates")]
+    LeafLimitExceeded(usize),
+    #[error("epsilon must be zero or positive")]
+    InvalidEpsilon,
+    #[error("jitter must be finite and zero or positive")]
+    InvalidJitter,
+    #[error("handle refers to an earlier revision of the tree")]
+    StaleHandle,
+    #[error("points and payloads differ in length")]
+    ColumnLengthMismatch,
+    #[error("box lengths must be finite and positive")]
+    InvalidPeriod,
+    #[error("cone direction must be non-zero and its half angle within 0 and pi")]
+    InvalidCone,
+    #[error("ellipsoid radii must be finite and positive and its matrix symmetric positive definite")]
+    InvalidEllipsoid,
+    #[error("at most {0} dimensions are supported")]
+    TooManyDimensions(usize),
+    #[error("angle must be finite and zero or positive")]
+    InvalidAngle,
+    #[error("query panicked")]
+    QueryPanicked,
+    #[error("bin edges must be zero or positive and ascending")]
+    InvalidBinEdges,
+    #[error("radius must be finite and zero or positive")]
+    InvalidRadius,
 }
 
-impl<A: Float + Zero + One, T: std::cmp::PartialEq, U: AsRef<[A]> + std::cmp::PartialEq> KdTree<A, T, U> {
+impl<A: Axis, T: std::cmp::PartialEq, U: AsRef<[A]> + std::cmp::PartialEq> KdTree<A, T, U> {
     /// Create a new KD tree, specifying the dimension size of each point
     pub fn new(dims: usize) -> Self {
         KdTree::with_capacity(dims, 2_usize.pow(4))
     }
 
+    /// Create a new KD tree whose dimension size is inferred from the first point added.
+    ///
+    /// Until then queries find nothing; afterwards points of any other dimension size are
+    /// rejected with [`ErrorKind::WrongDimension`], like for a tree created with [`new`](Self::new).
+    pub fn new_infer() -> Self {
+        KdTree::new(0)
+    }
+
     /// Create a new KD tree, specifying the dimension size of each point and the capacity of leaf nodes
+    ///
+    /// A dimension size of zero means the dimension size is not known yet, see
+    /// [`new_infer`](Self::new_infer).
     pub fn with_capacity(dimensions: usize, capacity: usize) -> Self {
-        let min_bounds = vec![A::infinity(); dimensions];
-        let max_bounds = vec![A::neg_infinity(); dimensions];
+        let min_bounds = vec![A::greatest(); dimensions];
+        let max_bounds = vec![A::least(); dimensions];
         KdTree {
             left: None,
             right: None,
@@ -56,23 +133,314 @@ impl<A: Float + Zero + One, T: std::cmp::PartialEq, U: AsRef<[A]> + std::cmp::Pa
             max_bounds: max_bounds.into_boxed_slice(),
             split_value: None,
             split_dimension: None,
+            unsplittable: false,
+            id: 0,
+            changes: ChangeLog::default(),
+            revision: handle::next_revision(),
+            leaf_limit: None,
+            strict: false,
             points: Some(vec![]),
             bucket: Some(vec![]),
+            summary: None,
+        }
+    }
+
+    /// Build a KD tree from many points at once, specifying the dimension size of each point and
+    /// the capacity of leaf nodes.
+    ///
+    /// All points are validated before anything is inserted. The first invalid point is reported
+    /// by its index in `points`, with [`ErrorKind::WrongDimensionAt`] or
+    /// [`ErrorKind::NonFiniteCoordinateAt`]. A dimension size of zero is inferred from the first
+    /// point.
+    pub fn bulk_load(dimensions: usize, capacity: usize, points: Vec<(U, T)>) -> Result<Self, ErrorKind> {
+        if capacity == 0 {
+            return Err(ErrorKind::ZeroCapacity);
+        }
+        let dimensions = match points.first() {
+            Some((point, _)) if dimensions == 0 => point.as_ref().len(),
+            _ => dimensions,
+        };
+        let mut tree = KdTree::with_capacity(dimensions, capacity);
+        tree.check_points(&points)?;
+        for (point, data) in points {
+            tree.add_recursive(point, data);
+        }
+        Ok(tree)
+    }
+
+    /// Build a balanced KD tree from many points at once, the dimension size being that of the
+    /// first point.
+    ///
+    /// Unlike adding the points one by one, every stem splits its points at their median along
+    /// the dimension they spread the most in, so the shape of the tree does not depend on the
+    /// order of `points` and queries on static data visit fewer nodes. Points are validated like
+    /// for [`bulk_load`](Self::bulk_load).
+    pub fn build(points: Vec<(U, T)>) -> Result<Self, ErrorKind> {
+        KdTree::build_with_capacity(2_usize.pow(4), points)
+    }
+
+    /// Like [`build`](Self::build), specifying the capacity of leaf nodes.
+    pub fn build_with_capacity(capacity: usize, points: Vec<(U, T)>) -> Result<Self, ErrorKind> {
+        if capacity == 0 {
+            return Err(ErrorKind::ZeroCapacity);
+        }
+        let dimensions = points.first().map_or(0, |(point, _)| point.as_ref().len());
+        let mut tree = KdTree::with_capacity(dimensions, capacity);
+        tree.check_points(&points)?;
+        tree.build_node(points);
+        tree.assign_ids();
+        Ok(tree)
+    }
+
+    /// Like [`build`](Self::build), building the subtrees of every stem in parallel.
+    ///
+    /// The resulting tree is the same as the one built sequentially, node IDs included.
+    #[cfg(feature = "rayon")]
+    pub fn par_build(points: Vec<(U, T)>) -> Result<Self, ErrorKind>
+    where
+        A: Send,
+        T: Send,
+        U: Send,
+    {
+        KdTree::par_build_with_capacity(2_usize.pow(4), points)
+    }
+
+    /// Like [`build_with_capacity`](Self::build_with_capacity), building the subtrees of every
+    /// stem in parallel.
+    #[cfg(feature = "rayon")]
+    pub fn par_build_with_capacity(capacity: usize, points: Vec<(U, T)>) -> Result<Self, ErrorKind>
+    where
+        A: Send,
+        T: Send,
+        U: Send,
+    {
+        if capacity == 0 {
+            return Err(ErrorKind::ZeroCapacity);
+        }
+        let dimensions = points.first().map_or(0, |(point, _)| point.as_ref().len());
+        let mut tree = KdTree::with_capacity(dimensions, capacity);
+        tree.check_points(&points)?;
+        tree.par_build_node(points);
+        tree.assign_ids();
+        Ok(tree)
+    }
+
+    fn build_node(&mut self, entries: Vec<(U, T)>) {
+        if let Some((left_entries, right_entries)) = self.split_entries(entries) {
+            self.left.as_mut().unwrap().build_node(left_entries);
+            self.right.as_mut().unwrap().build_node(right_entries);
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    fn par_build_node(&mut self, entries: Vec<(U, T)>)
+    where
+        A: Send,
+        T: Send,
+        U: Send,
+    {
+        // below this, splitting up the work costs more than it saves
+        const SEQUENTIAL: usize = 4096;
+        if entries.len() <= SEQUENTIAL {
+            self.build_node(entries);
+        } else if let Some((left_entries, right_entries)) = self.split_entries(entries) {
+            let (left, right) = (self.left.as_mut().unwrap(), self.right.as_mut().unwrap());
+            rayon::join(
+                || left.par_build_node(left_entries),
+                || right.par_build_node(right_entries),
+            );
+        }
+    }
+
+    /// Make this node a leaf holding `entries` if they fit or cannot be split, or a stem with
+    /// empty children otherwise, returning the entries for each child.
+    fn split_entries(&mut self, mut entries: Vec<(U, T)>) -> Option<Halves<U, T>> {
+        self.size = entries.len();
+        for (point, _) in &entries {
+            self.extend(point.as_ref());
         }
+        let mut spread = 0.0;
+        for dim in 0..self.dimensions {
+            let diff = A::spread(self.min_bounds[dim], self.max_bounds[dim]);
+            if diff > spread {
+                spread = diff;
+                self.split_dimension = Some(dim);
+            }
+        }
+        let dim = match self.split_dimension {
+            Some(dim) if entries.len() > self.capacity => dim,
+            _ => {
+                if entries.len() > self.capacity {
+                    self.mark_unsplittable("all points of the leaf are identical, it cannot be split");
+                }
+                self.split_dimension = None;
+                let (points, bucket) = entries.into_iter().unzip();
+                self.points = Some(points);
+                self.bucket = Some(bucket);
+                self.refresh_summary();
+                return None;
+            }
+        };
+        let middle = entries.len() / 2;
+        entries.select_nth_unstable_by(middle, |a, b| {
+            a.0.as_ref()[dim]
+                .partial_cmp(&b.0.as_ref()[dim])
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        // with the median at the minimum, belongs_in_left keeps it left, so both sides get points
+        self.split_value = Some(entries[middle].0.as_ref()[dim]);
+        let (left_entries, right_entries) = entries
+            .into_iter()
+            .partition(|(point, _)| self.belongs_in_left(point.as_ref()));
+        self.left = Some(Box::new(KdTree::with_capacity(self.dimensions, self.capacity)));
+        self.right = Some(Box::new(KdTree::with_capacity(self.dimensions, self.capacity)));
+        self.points = None;
+        self.bucket = None;
+        Some((left_entries, right_entries))
+    }
+
+    /// Give all nodes their IDs anew, as for a freshly built tree.
+    pub(crate) fn reset_ids(&mut self) {
+        self.id = 0;
+        self.assign_ids();
+    }
+
+    /// Give all nodes below the root of a freshly built tree their IDs, depth first, logging a
+    /// split for every stem.
+    fn assign_ids(&mut self) {
+        let mut changes = ChangeLog::default();
+        let mut pending = vec![&mut *self];
+        while let Some(node) = pending.pop() {
+            if let (Some(left), Some(right)) = (node.left.as_mut(), node.right.as_mut()) {
+                let (left_id, right_id) = changes.split(node.id);
+                left.id = left_id;
+                right.id = right_id;
+            }
+            pending.extend(node.right.as_deref_mut());
+            pending.extend(node.left.as_deref_mut());
+        }
+        self.changes = changes;
     }
 
     pub fn size(&self) -> usize {
         self.size
     }
 
+    /// Dimension size of each point, zero while it is still to be inferred.
+    pub fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    /// Number of structural changes to the tree so far, leaves split and stems
+    /// [collapsed](Self::compact).
+    ///
+    /// Node IDs, as found in [`LeafRef`](crate::LeafRef) and [`SplitPlane`](crate::SplitPlane),
+    /// stay the same until the generation changes: adding points to a leaf without splitting
+    /// it or removing points keep them. When the generation changes, new nodes get IDs never
+    /// used before in the tree, so caches keyed by node ID only need to drop the entries of
+    /// nodes that are gone. The root always has ID 0.
+    ///
+    /// Trees deserialized from data written before node IDs existed have the ID 0 for all nodes.
+    pub fn generation(&self) -> u64 {
+        self.changes.generation
+    }
+
+    /// The structural changes that moved the tree from `generation` to the current
+    /// [`generation`](Self::generation), oldest first.
+    ///
+    /// The tree keeps a log of all changes since it was created, deserialized or the log was
+    /// last [cleared](Self::clear_changes). `None` means the changes are no longer known, and
+    /// anything derived from the nodes of the tree has to be rebuilt from scratch.
+    pub fn changes_since(&self, generation: u64) -> Option<&[StructureChange]> {
+        let first = self.changes.generation - self.changes.entries.len() as u64;
+        if generation < first || generation > self.changes.generation {
+            return None;
+        }
+        Some(&self.changes.entries[(generation - first) as usize..])
+    }
+
+    /// Forget the logged structural changes, freeing their memory. The generation stays.
+    pub fn clear_changes(&mut self) {
+        self.changes.entries = vec![];
+    }
+
+    /// The `num` nearest neighbours of `point`, nearest first.
+    ///
+    /// `distance` is called both between points and between `point` and the point of a node's
+    /// bounding box nearest to it along every dimension, and subtrees are skipped by the latter.
+    /// Results are exact for distances that never grow when a coordinate moves closer to the
+    /// query, which holds for every distance in [`distance`](crate::distance) except
+    /// [`haversine`](crate::distance::haversine). Great-circle distances on latitude/longitude
+    /// do not qualify near the poles or across the antimeridian; see
+    /// [`SphereKdTree`](crate::SphereKdTree) for those.
     pub fn nearest<F>(&self, point: &[A], num: usize, distance: &F) -> Result<Vec<(A, &T)>, ErrorKind>
     where
         F: Fn(&[A], &[A]) -> A,
     {
         self.check_point(point)?;
+        Ok(self.nearest_unchecked(point, num, distance))
+    }
+
+    /// The nearest neighbours for several values of `num` at once, in the order of `nums`.
+    ///
+    /// Only a single traversal for the largest value is made, the shorter lists being prefixes of
+    /// it, which is handy for sweeping `k` in k-NN classifiers.
+    pub fn nearest_multi_k<F>(&self, point: &[A], nums: &[usize], distance: &F) -> Result<Vec<Vec<(A, &T)>>, ErrorKind>
+    where
+        F: Fn(&[A], &[A]) -> A,
+    {
+        let max = nums.iter().copied().max().unwrap_or(0);
+        let found = self.nearest(point, max, distance)?;
+        Ok(nums.iter().map(|&num| found[..num.min(found.len())].to_vec()).collect())
+    }
+
+    /// Like [`nearest`](Self::nearest), but skips validating `point`.
+    ///
+    /// Meant for hot loops whose query points have already been validated. The caller must make
+    /// sure that `point` has the dimension size of the tree and only finite coordinates; this is
+    /// only checked by a debug assertion, results for invalid points are unspecified.
+    pub fn nearest_unchecked<F>(&self, point: &[A], num: usize, distance: &F) -> Vec<(A, &T)>
+    where
+        F: Fn(&[A], &[A]) -> A,
+    {
+        debug_assert_eq!(self.check_point(point), Ok(()));
+        self.nearest_within_factor(point, num, A::one(), usize::MAX, distance).0
+    }
+
+    /// The `num` nearest neighbours up to `factor`, looking at `max_leaves` leaves at most, and
+    /// whether the search finished within that budget.
+    pub(crate) fn nearest_within_factor<F>(
+        &self,
+        point: &[A],
+        num: usize,
+        factor: A,
+        max_leaves: usize,
+        distance: &F,
+    ) -> (Vec<(A, &T)>, bool)
+    where
+        F: Fn(&[A], &[A]) -> A,
+    {
+        self.nearest_counted(point, num, factor, max_leaves, distance, |_, _| {})
+    }
+
+    /// Like [`nearest_within_factor`](Self::nearest_within_factor), calling `count` with the
+    /// number of nodes walked through and points compared for every leaf scanned.
+    pub(crate) fn nearest_counted<F, C>(
+        &self,
+        point: &[A],
+        num: usize,
+        factor: A,
+        max_leaves: usize,
+        distance: &F,
+        mut count: C,
+    ) -> (Vec<(A, &T)>, bool)
+    where
+        F: Fn(&[A], &[A]) -> A,
+        C: FnMut(usize, usize),
+    {
         let num = std::cmp::min(num, self.size);
         if num == 0 {
-            return Ok(vec![]);
+            return (vec![], true);
         }
         let mut pending = BinaryHeap::new();
         let mut evaluated = BinaryHeap::<HeapElement<A, &T>>::new();
@@ -80,19 +448,96 @@ impl<A: Float + Zero + One, T: std::cmp::PartialEq, U: AsRef<[A]> + std::cmp::Pa
             distance: A::zero(),
             element: self,
         });
-        while !pending.is_empty()
-            && (evaluated.len() < num || (-pending.peek().unwrap().distance <= evaluated.peek().unwrap().distance))
-        {
-            self.nearest_step(point, num, A::infinity(), distance, &mut pending, &mut evaluated);
-        }
-        Ok(evaluated
+        let mut leaves = 0;
+        let complete = loop {
+            let finished = pending.is_empty()
+                || (evaluated.len() == num
+                    && -pending.peek().unwrap().distance * factor > evaluated.peek().unwrap().distance);
+            if finished {
+                break true;
+            }
+            if leaves == max_leaves {
+                break false;
+            }
+            let (nodes, points) = self.nearest_step(point, num, A::greatest(), distance, &mut pending, &mut evaluated);
+            count(nodes, points);
+            leaves += 1;
+        };
+        let found = evaluated
             .into_sorted_vec()
             .into_iter()
             .take(num)
             .map(Into::into)
+            .collect();
+        (found, complete)
+    }
+
+    /// The `num` nearest neighbours of `point` that are at most `max_dist` from it, nearest
+    /// first.
+    ///
+    /// Unlike filtering the results of [`nearest`](Self::nearest), subtrees farther than
+    /// `max_dist` are never visited, so a small radius keeps the search local even when fewer
+    /// than `num` points lie within it.
+    pub fn nearest_within<F>(
+        &self,
+        point: &[A],
+        num: usize,
+        max_dist: A,
+        distance: &F,
+    ) -> Result<Vec<(A, &T)>, ErrorKind>
+    where
+        F: Fn(&[A], &[A]) -> A,
+    {
+        self.check_point(point)?;
+        let num = std::cmp::min(num, self.size);
+        if num == 0 {
+            return Ok(vec![]);
+        }
+        let mut pending = BinaryHeap::new();
+        let mut evaluated = BinaryHeap::<HeapElement<A, &T>>::new();
+        pending.push(HeapElement {
+            distance: A::zero(),
+            element: self,
+        });
+        while let Some(next) = pending.peek() {
+            let bound = if evaluated.len() == num {
+                util::min(max_dist, evaluated.peek().unwrap().distance)
+            } else {
+                max_dist
+            };
+            if -next.distance > bound {
+                break;
+            }
+            self.nearest_step(point, num, max_dist, distance, &mut pending, &mut evaluated);
+        }
+        Ok(evaluated.into_sorted_vec().into_iter().map(Into::into).collect())
+    }
+
+    /// The `num` nearest neighbours of `point` whose data satisfies `predicate`, nearest first.
+    ///
+    /// Entries failing `predicate` are passed over during the search, which keeps going until
+    /// `num` passing ones are found, so unlike filtering the results of [`nearest`](Self::nearest)
+    /// fewer than `num` are only returned when the tree holds no more.
+    pub fn nearest_filtered<F, P>(
+        &self,
+        point: &[A],
+        num: usize,
+        distance: &F,
+        predicate: P,
+    ) -> Result<Vec<(A, &T)>, ErrorKind>
+    where
+        F: Fn(&[A], &[A]) -> A,
+        P: Fn(&T) -> bool,
+    {
+        Ok(self
+            .iter_nearest(point, distance)?
+            .filter(|&(_, data)| predicate(data))
+            .take(num)
             .collect())
     }
 
+    /// All points at most `radius` from `point`, nearest first, with the same requirements on
+    /// `distance` as [`nearest`](Self::nearest).
     pub fn within<F>(&self, point: &[A], radius: A, distance: &F) -> Result<Vec<(A, &T)>, ErrorKind>
     where
         F: Fn(&[A], &[A]) -> A,
@@ -113,6 +558,79 @@ impl<A: Float + Zero + One, T: std::cmp::PartialEq, U: AsRef<[A]> + std::cmp::Pa
         Ok(evaluated.into_sorted_vec().into_iter().map(Into::into).collect())
     }
 
+    /// All points within the box from `min` to `max`, bounds included, in the order of
+    /// [`iter`](Self::iter).
+    ///
+    /// Subtrees whose bounds lie outside of the box are skipped, and those entirely inside of it
+    /// are taken whole, without looking at their points one by one. A box with `min` exceeding
+    /// `max` in any dimension is empty.
+    pub fn within_box(&self, min: &[A], max: &[A]) -> Result<Vec<(&U, &T)>, ErrorKind> {
+        self.check_point(min)?;
+        self.check_point(max)?;
+        let mut found = vec![];
+        let mut pending = vec![self];
+        while let Some(node) = pending.pop() {
+            if node.size == 0 || !util::boxes_overlap(&node.min_bounds, &node.max_bounds, min, max) {
+                continue;
+            }
+            let inside = util::box_contains(min, max, &node.min_bounds, &node.max_bounds);
+            if let (Some(points), Some(bucket)) = (node.points.as_ref(), node.bucket.as_ref()) {
+                found.extend(
+                    points
+                        .iter()
+                        .zip(bucket)
+                        .filter(|(point, _)| inside || util::box_contains(min, max, point.as_ref(), point.as_ref())),
+                );
+            } else if inside {
+                found.extend(node.iter());
+            } else {
+                pending.extend(node.right.as_deref());
+                pending.extend(node.left.as_deref());
+            }
+        }
+        Ok(found)
+    }
+
+    /// Like [`within`](Self::within), but growing `radius` by `growth_factor` until at least
+    /// `num` points are found or the tree holds no more.
+    ///
+    /// All points within the final radius are returned, nearest first, so there may be more
+    /// than `num`. The radius grows during a single best-first traversal, points already looked
+    /// at are not visited again. Fails with [`ErrorKind::InvalidRadiusGrowth`] unless `radius`
+    /// is positive and `growth_factor` greater than one.
+    pub fn nearest_at_least<F>(
+        &self,
+        point: &[A],
+        num: usize,
+        radius: A,
+        growth_factor: A,
+        distance: &F,
+    ) -> Result<Vec<(A, &T)>, ErrorKind>
+    where
+        F: Fn(&[A], &[A]) -> A,
+    {
+        if !(radius > A::zero() && growth_factor > A::one()) {
+            return Err(ErrorKind::InvalidRadiusGrowth);
+        }
+        let mut radius = radius;
+        let mut found = vec![];
+        let mut nearest = self.iter_nearest(point, distance)?.peekable();
+        while let Some(&(d, _)) = nearest.peek() {
+            if d > radius {
+                if found.len() >= num {
+                    break;
+                }
+                while radius < d {
+                    radius = radius * growth_factor;
+                }
+            }
+            found.extend(nearest.next());
+        }
+        Ok(found)
+    }
+
+    /// Scan the leaf below the nearest pending node, returning the number of nodes walked
+    /// through on the way and of points compared.
     fn nearest_step<'b, F>(
         &self,
         point: &[A],
@@ -121,21 +639,24 @@ impl<A: Float + Zero + One, T: std::cmp::PartialEq, U: AsRef<[A]> + std::cmp::Pa
         distance: &F,
         pending: &mut BinaryHeap<HeapElement<A, &'b Self>>,
         evaluated: &mut BinaryHeap<HeapElement<A, &'b T>>,
-    ) where
+    ) -> (usize, usize)
+    where
         F: Fn(&[A], &[A]) -> A,
     {
+        let mut nodes = 1;
         let mut curr = pending.pop().unwrap().element;
         debug_assert!(evaluated.len() <= num);
         let evaluated_dist = if evaluated.len() == num {
             // We only care about the nearest `num` points, so if we already have `num` points,
             // any more point we add to `evaluated` must be nearer then one of the point already in
             // `evaluated`.
-            max_dist.min(evaluated.peek().unwrap().distance)
+            util::min(max_dist, evaluated.peek().unwrap().distance)
         } else {
             max_dist
         };
 
         while !curr.is_leaf() {
+            nodes += 1;
             let candidate;
             if curr.belongs_in_left(point) {
                 candidate = curr.right.as_ref().unwrap();
@@ -144,11 +665,15 @@ impl<A: Float + Zero + One, T: std::cmp::PartialEq, U: AsRef<[A]> + std::cmp::Pa
                 candidate = curr.left.as_ref().unwrap();
                 curr = curr.right.as_ref().unwrap();
             }
+            // empty nodes have bounds no point lies in, too far out for integer distances
+            if candidate.size == 0 {
+                continue;
+            }
             let candidate_to_space =
                 util::distance_to_space(point, &candidate.min_bounds, &candidate.max_bounds, distance);
             if candidate_to_space <= evaluated_dist {
                 pending.push(HeapElement {
-                    distance: candidate_to_space * -A::one(),
+                    distance: -candidate_to_space,
                     element: &**candidate,
                 });
             }
@@ -170,8 +695,44 @@ impl<A: Float + Zero + One, T: std::cmp::PartialEq, U: AsRef<[A]> + std::cmp::Pa
                 }
             }
         }
+        (nodes, curr.size)
+    }
+
+    /// Iterate over all points and their data, leaf by leaf, left before right.
+    ///
+    /// This order is stable as long as the tree is not modified, and is the order index based
+    /// APIs refer to.
+    pub fn iter(&self) -> Iter<'_, A, T, U> {
+        Iter {
+            pending: vec![self],
+            points: [].iter(),
+            bucket: [].iter(),
+        }
+    }
+
+    /// Take the tree apart into its points and their data, in the order of [`iter`](Self::iter),
+    /// without cloning them.
+    pub fn into_points(self) -> Vec<(U, T)> {
+        let mut entries = Vec::with_capacity(self.size);
+        let mut pending = vec![self];
+        while let Some(node) = pending.pop() {
+            if let (Some(points), Some(bucket)) = (node.points, node.bucket) {
+                entries.extend(points.into_iter().zip(bucket));
+            } else {
+                pending.extend(node.right.map(|right| *right));
+                pending.extend(node.left.map(|left| *left));
+            }
+        }
+        entries
     }
 
+    /// All stored points, nearest to `point` first, found lazily.
+    ///
+    /// Nodes are visited best first, ordered by the distance of their bounds, and a point is only
+    /// yielded once no unvisited node can hold a nearer one. Only as much of the tree is searched
+    /// as the results taken so far require, so this fits queries without a fixed number of
+    /// neighbours, like finding the nearest point satisfying a predicate with
+    /// [`Iterator::find`].
     pub fn iter_nearest<'a, 'b, F>(
         &'b self,
         point: &'a [A],
@@ -195,6 +756,7 @@ impl<A: Float + Zero + One, T: std::cmp::PartialEq, U: AsRef<[A]> + std::cmp::Pa
         })
     }
 
+    /// Like [`iter_nearest`](Self::iter_nearest), handing out mutable references to the data.
     pub fn iter_nearest_mut<'a, 'b, F>(
         &'b mut self,
         point: &'a [A],
@@ -222,14 +784,101 @@ impl<A: Float + Zero + One, T: std::cmp::PartialEq, U: AsRef<[A]> + std::cmp::Pa
         if self.capacity == 0 {
             return Err(ErrorKind::ZeroCapacity);
         }
+        // a rejected first point must not fix the dimension size
         self.check_point(point.as_ref())?;
-        self.add_unchecked(point, data)
+        self.infer_dimensions(point.as_ref());
+        match self.leaf_limit {
+            Some(limit) if self.strict && self.exceeds_leaf_limit(point.as_ref(), 0, limit) => {
+                return Err(ErrorKind::LeafLimitExceeded(limit));
+            }
+            #[cfg(feature = "tracing")]
+            Some(limit) => {
+                // once per leaf, when it grows past the limit; it already warned when it could
+                // not be split, which happened in an earlier `add`
+                let leaf = self.leaf_for(point.as_ref());
+                if leaf.unsplittable && leaf.size == limit && leaf.contains_point(point.as_ref()) {
+                    tracing::warn!(limit, "leaf exceeds its limit of points that cannot be split");
+                }
+            }
+            _ => {}
+        }
+        self.add_recursive(point, data);
+        Ok(())
+    }
+
+    /// Set the number of points a leaf may hold before it is reported in
+    /// [`TreeStats::oversized_leaves`](crate::TreeStats::oversized_leaves), `None` for no limit.
+    ///
+    /// Leaves only grow beyond their capacity when their points cannot be split, usually because
+    /// they are all at the same coordinates. Queries scan all points of such a leaf, so a tree
+    /// holding a lot of duplicates slows down without any other sign. The limit should be well
+    /// above the capacity.
+    pub fn set_leaf_limit(&mut self, limit: Option<usize>) {
+        self.leaf_limit = limit;
+    }
+
+    /// Make [`add`](Self::add) fail with [`ErrorKind::LeafLimitExceeded`] instead of growing a
+    /// leaf beyond the [leaf limit](Self::set_leaf_limit).
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    /// Whether adding `point` would grow a leaf that cannot be split beyond `limit` points, after
+    /// adding `pending` other points at exactly the same coordinates first.
+    pub(crate) fn exceeds_leaf_limit(&self, point: &[A], pending: usize, limit: usize) -> bool {
+        let node = self.leaf_for(point);
+        // points of an unsplittable leaf share their bounds, so only points within them stay
+        (node.size + pending >= limit && node.contains_point(point)) || (pending > 0 && pending >= limit)
+    }
+
+    /// The leaf `point` would be added to.
+    fn leaf_for(&self, point: &[A]) -> &Self {
+        let mut node = self;
+        while !node.is_leaf() {
+            node = if node.belongs_in_left(point) {
+                node.left.as_ref().unwrap()
+            } else {
+                node.right.as_ref().unwrap()
+            };
+        }
+        node
+    }
+
+    /// Like [`add`](Self::add), but skips validating `point` and the capacity of the tree.
+    ///
+    /// Meant for bulk loading from input that has already been validated. The caller must make
+    /// sure that the tree has a non-zero capacity and that `point` has the dimension size of the
+    /// tree and only finite coordinates; this is only checked by debug assertions, adding an
+    /// invalid point leaves the tree in an unspecified (but memory safe) state.
+    pub fn add_unchecked(&mut self, point: U, data: T) {
+        debug_assert_ne!(self.capacity, 0);
+        debug_assert_eq!(self.check_point(point.as_ref()), Ok(()));
+        self.infer_dimensions(point.as_ref());
+        self.add_recursive(point, data);
+    }
+
+    fn infer_dimensions(&mut self, point: &[A]) {
+        if self.dimensions == 0 && self.size == 0 {
+            let changes = std::mem::take(&mut self.changes);
+            let (leaf_limit, strict) = (self.leaf_limit, self.strict);
+            *self = KdTree::with_capacity(point.len(), self.capacity);
+            self.changes = changes;
+            self.leaf_limit = leaf_limit;
+            self.strict = strict;
+        }
     }
 
-    fn add_unchecked(&mut self, point: U, data: T) -> Result<(), ErrorKind> {
+    fn add_recursive(&mut self, point: U, data: T) {
+        self.revision = handle::next_revision();
+        let mut changes = std::mem::take(&mut self.changes);
+        self.add_to_subtree(point, data, &mut changes);
+        self.changes = changes;
+    }
+
+    fn add_to_subtree(&mut self, point: U, data: T, changes: &mut ChangeLog) {
         if self.is_leaf() {
-            self.add_to_bucket(point, data);
-            return Ok(());
+            self.add_to_bucket(point, data, changes);
+            return;
         }
         self.extend(point.as_ref());
         self.size += 1;
@@ -238,61 +887,134 @@ impl<A: Float + Zero + One, T: std::cmp::PartialEq, U: AsRef<[A]> + std::cmp::Pa
         } else {
             self.right.as_mut()
         };
-        next.unwrap().add_unchecked(point, data)
+        next.unwrap().add_to_subtree(point, data, changes)
     }
 
-    fn add_to_bucket(&mut self, point: U, data: T) {
+    fn add_to_bucket(&mut self, point: U, data: T, changes: &mut ChangeLog) {
         self.extend(point.as_ref());
         let mut points = self.points.take().unwrap();
         let mut bucket = self.bucket.take().unwrap();
         points.push(point);
         bucket.push(data);
+        match self.summary.as_mut() {
+            Some(summary) => summary.push(points.last().unwrap().as_ref()),
+            None => self.summary = Some(Box::new(Summary::of_points(self.dimensions, &points))),
+        }
         self.size += 1;
         if self.size > self.capacity {
-            self.split(points, bucket);
+            self.split(points, bucket, changes);
         } else {
             self.points = Some(points);
             self.bucket = Some(bucket);
         }
     }
 
+    /// Remove all entries holding `data` at `point`, returning how many were removed.
+    ///
+    /// Only the leaves whose bounds contain `point` are searched. Like other removals, this
+    /// leaves the structure and bounds of the tree as they are, see [`compact`](Self::compact)
+    /// and [`recompute_bounds`](Self::recompute_bounds).
     pub fn remove(&mut self, point: &U, data: &T) -> Result<usize, ErrorKind> {
-        let mut removed = 0;
         self.check_point(point.as_ref())?;
-        if let (Some(mut points), Some(mut bucket)) = (self.points.take(), self.bucket.take()) {
-            while let Some(p_index) = points.iter().position(|x| x == point) {
-                if &bucket[p_index] == data {
-                    points.remove(p_index);
-                    bucket.remove(p_index);
+        let removed = self.remove_recursive(point, data);
+        if removed > 0 {
+            self.revision = handle::next_revision();
+        }
+        Ok(removed)
+    }
+
+    fn remove_recursive(&mut self, point: &U, data: &T) -> usize {
+        if !self.contains_point(point.as_ref()) {
+            return 0;
+        }
+        let removed = if let (Some(points), Some(bucket)) = (self.points.as_mut(), self.bucket.as_mut()) {
+            let mut removed = 0;
+            let mut i = 0;
+            while i < points.len() {
+                if &points[i] == point && &bucket[i] == data {
+                    points.remove(i);
+                    bucket.remove(i);
                     removed += 1;
-                    self.size -= 1;
+                } else {
+                    i += 1;
                 }
             }
-            self.points = Some(points);
-            self.bucket = Some(bucket);
+            removed
         } else {
+            let mut removed = 0;
             if let Some(right) = self.right.as_mut() {
-                let right_removed = right.remove(point, data)?;
-                if right_removed > 0 {
-                    self.size -= right_removed;
-                    removed += right_removed;
-                }
+                removed += right.remove_recursive(point, data);
             }
             if let Some(left) = self.left.as_mut() {
-                let left_removed = left.remove(point, data)?;
-                if left_removed > 0 {
-                    self.size -= left_removed;
-                    removed += left_removed;
+                removed += left.remove_recursive(point, data);
+            }
+            removed
+        };
+        self.size -= removed;
+        if removed > 0 && self.points.is_some() {
+            self.refresh_summary();
+        }
+        removed
+    }
+
+    /// Remove all entries for which `predicate` returns `true` and yield them, in the order of
+    /// [`iter`](Self::iter).
+    ///
+    /// Unlike [`Vec::retain`], this hands out the removed entries; unlike `Vec::extract_if`, the
+    /// entries are removed right away, whether the returned iterator is consumed or not. Like
+    /// [`remove`](Self::remove), this leaves the structure and bounds of the tree as they are.
+    pub fn extract_if<F>(&mut self, mut predicate: F) -> impl Iterator<Item = (U, T)>
+    where
+        F: FnMut(&U, &mut T) -> bool,
+    {
+        let mut extracted = vec![];
+        self.extract_recursive(&mut predicate, &mut extracted);
+        if !extracted.is_empty() {
+            self.revision = handle::next_revision();
+        }
+        extracted.into_iter()
+    }
+
+    /// Remove all entries and yield them, in the order of [`iter`](Self::iter).
+    ///
+    /// Like [`extract_if`](Self::extract_if), the entries are removed right away and the
+    /// structure of the tree is left as it is, see [`compact`](Self::compact).
+    pub fn drain(&mut self) -> impl Iterator<Item = (U, T)> {
+        self.extract_if(|_, _| true)
+    }
+
+    fn extract_recursive<F>(&mut self, predicate: &mut F, extracted: &mut Vec<(U, T)>)
+    where
+        F: FnMut(&U, &mut T) -> bool,
+    {
+        let before = extracted.len();
+        if let (Some(points), Some(bucket)) = (self.points.as_mut(), self.bucket.as_mut()) {
+            let mut i = 0;
+            while i < points.len() {
+                if predicate(&points[i], &mut bucket[i]) {
+                    extracted.push((points.remove(i), bucket.remove(i)));
+                } else {
+                    i += 1;
                 }
             }
+            if extracted.len() > before {
+                self.refresh_summary();
+            }
+        } else {
+            if let Some(left) = self.left.as_mut() {
+                left.extract_recursive(predicate, extracted);
+            }
+            if let Some(right) = self.right.as_mut() {
+                right.extract_recursive(predicate, extracted);
+            }
         }
-        Ok(removed)
+        self.size -= extracted.len() - before;
     }
 
-    fn split(&mut self, mut points: Vec<U>, mut bucket: Vec<T>) {
-        let mut max = A::zero();
+    fn split(&mut self, mut points: Vec<U>, mut bucket: Vec<T>, changes: &mut ChangeLog) {
+        let mut max = 0.0;
         for dim in 0..self.dimensions {
-            let diff = self.max_bounds[dim] - self.min_bounds[dim];
+            let diff = A::spread(self.min_bounds[dim], self.max_bounds[dim]);
             if !diff.is_nan() && diff > max {
                 max = diff;
                 self.split_dimension = Some(dim);
@@ -300,6 +1022,7 @@ impl<A: Float + Zero + One, T: std::cmp::PartialEq, U: AsRef<[A]> + std::cmp::Pa
         }
         match self.split_dimension {
             None => {
+                self.mark_unsplittable("all points of the leaf are identical, it cannot be split");
                 self.points = Some(points);
                 self.bucket = Some(bucket);
                 return;
@@ -307,25 +1030,60 @@ impl<A: Float + Zero + One, T: std::cmp::PartialEq, U: AsRef<[A]> + std::cmp::Pa
             Some(dim) => {
                 let min = self.min_bounds[dim];
                 let max = self.max_bounds[dim];
-                self.split_value = Some(min + (max - min) / A::from(2.0).unwrap());
+                self.split_value = Some(A::midpoint(min, max));
             }
         };
         let mut left = Box::new(KdTree::with_capacity(self.dimensions, self.capacity));
         let mut right = Box::new(KdTree::with_capacity(self.dimensions, self.capacity));
+        let (left_id, right_id) = changes.split(self.id);
+        left.id = left_id;
+        right.id = right_id;
         while !points.is_empty() {
             let point = points.swap_remove(0);
             let data = bucket.swap_remove(0);
             if self.belongs_in_left(point.as_ref()) {
-                left.add_to_bucket(point, data);
+                left.add_to_bucket(point, data, changes);
             } else {
-                right.add_to_bucket(point, data);
+                right.add_to_bucket(point, data, changes);
             }
         }
+        if left.size == 0 {
+            right.mark_unsplittable("splitting a leaf left one side empty");
+        } else if right.size == 0 {
+            left.mark_unsplittable("splitting a leaf left one side empty");
+        }
+        self.unsplittable = false;
         self.left = Some(left);
         self.right = Some(right);
+        self.summary = None;
+    }
+
+    /// Recompute the running statistics of a leaf after points were removed from it.
+    pub(crate) fn refresh_summary(&mut self) {
+        self.summary = self
+            .points
+            .as_ref()
+            .map(|points| Box::new(Summary::of_points(self.dimensions, points)));
     }
 
-    fn belongs_in_left(&self, point: &[A]) -> bool {
+    /// Flag a leaf that could not be split, warning only the first time.
+    fn mark_unsplittable(&mut self, reason: &str) {
+        if self.unsplittable {
+            return;
+        }
+        self.unsplittable = true;
+        #[cfg(feature = "tracing")]
+        tracing::warn!(
+            size = self.size,
+            capacity = self.capacity,
+            "degenerate split: {}",
+            reason
+        );
+        #[cfg(not(feature = "tracing"))]
+        let _ = reason;
+    }
+
+    pub(crate) fn belongs_in_left(&self, point: &[A]) -> bool {
         if self.min_bounds[self.split_dimension.unwrap()] == self.split_value.unwrap() {
             point[self.split_dimension.unwrap()] <= self.split_value.unwrap()
         } else {
@@ -333,6 +1091,79 @@ impl<A: Float + Zero + One, T: std::cmp::PartialEq, U: AsRef<[A]> + std::cmp::Pa
         }
     }
 
+    /// Shrink the bounds of every node to the points it holds.
+    ///
+    /// Removing points leaves the bounds as they were, so after removing outliers, queries keep
+    /// visiting nodes that only seem to reach far out. Nodes left empty get empty bounds, like
+    /// those of a new tree.
+    pub fn recompute_bounds(&mut self) {
+        let mut min = vec![A::greatest(); self.dimensions];
+        let mut max = vec![A::least(); self.dimensions];
+        if let Some(points) = self.points.as_ref() {
+            for point in points {
+                util::extend_bounds(&mut min, &mut max, point.as_ref(), point.as_ref());
+            }
+        } else {
+            for child in self.left.iter_mut().chain(self.right.iter_mut()) {
+                child.recompute_bounds();
+                util::extend_bounds(&mut min, &mut max, &child.min_bounds, &child.max_bounds);
+            }
+        }
+        self.min_bounds = min.into_boxed_slice();
+        self.max_bounds = max.into_boxed_slice();
+    }
+
+    /// Replace every stem with an empty child by its other child, returning how many stems
+    /// were replaced.
+    ///
+    /// Removing points never changes the structure of the tree, so a tree whose points keep
+    /// moving piles up empty leaves that every query has to step through. Each replacement is
+    /// a [`StructureChange::Collapse`] and a new [`generation`](Self::generation). Bounds are
+    /// [recomputed](Self::recompute_bounds) too, so removed outliers stop inflating them.
+    pub fn compact(&mut self) -> usize {
+        let mut changes = std::mem::take(&mut self.changes);
+        let (revision, leaf_limit, strict) = (self.revision, self.leaf_limit, self.strict);
+        let collapsed = self.compact_recursive(&mut changes);
+        self.changes = changes;
+        self.revision = revision;
+        self.leaf_limit = leaf_limit;
+        self.strict = strict;
+        self.recompute_bounds();
+        collapsed
+    }
+
+    fn compact_recursive(&mut self, changes: &mut ChangeLog) -> usize {
+        let mut collapsed = 0;
+        for child in self.left.iter_mut().chain(self.right.iter_mut()) {
+            collapsed += child.compact_recursive(changes);
+        }
+        let (left, right) = match (self.left.take(), self.right.take()) {
+            (Some(left), Some(right)) => (left, right),
+            (left, right) => {
+                self.left = left;
+                self.right = right;
+                return collapsed;
+            }
+        };
+        if left.size > 0 && right.size > 0 {
+            self.left = Some(left);
+            self.right = Some(right);
+            return collapsed;
+        }
+        let (mut kept, removed) = if left.size > 0 || right.size == 0 {
+            (left, right)
+        } else {
+            (right, left)
+        };
+        changes.collapse(self.id, kept.id, removed.id);
+        if self.id == 0 {
+            // the root keeps its ID
+            kept.id = 0;
+        }
+        *self = *kept;
+        collapsed + 1
+    }
+
     fn extend(&mut self, point: &[A]) {
         let min = self.min_bounds.iter_mut();
         let max = self.max_bounds.iter_mut();
@@ -346,7 +1177,13 @@ impl<A: Float + Zero + One, T: std::cmp::PartialEq, U: AsRef<[A]> + std::cmp::Pa
         }
     }
 
-    fn is_leaf(&self) -> bool {
+    fn contains_point(&self, point: &[A]) -> bool {
+        let min = self.min_bounds.iter();
+        let max = self.max_bounds.iter();
+        min.zip(max).zip(point).all(|((&l, &h), &v)| l <= v && v <= h)
+    }
+
+    pub(crate) fn is_leaf(&self) -> bool {
         self.bucket.is_some()
             && self.points.is_some()
             && self.split_value.is_none()
@@ -355,12 +1192,33 @@ impl<A: Float + Zero + One, T: std::cmp::PartialEq, U: AsRef<[A]> + std::cmp::Pa
             && self.right.is_none()
     }
 
-    fn check_point(&self, point: &[A]) -> Result<(), ErrorKind> {
-        if self.dimensions != point.len() {
+    /// Validate `points` for bulk loading, reporting the first invalid one by its index.
+    pub(crate) fn check_points(&self, points: &[(U, T)]) -> Result<(), ErrorKind> {
+        self.check_each(points.iter().map(|(point, _)| point.as_ref()))
+    }
+
+    /// Validate each of `points`, reporting the first invalid one by its index.
+    pub(crate) fn check_each<'p, I>(&self, points: I) -> Result<(), ErrorKind>
+    where
+        I: IntoIterator<Item = &'p [A]>,
+        A: 'p,
+    {
+        for (index, point) in points.into_iter().enumerate() {
+            self.check_point(point).map_err(|err| match err {
+                ErrorKind::WrongDimension => ErrorKind::WrongDimensionAt(index),
+                _ => ErrorKind::NonFiniteCoordinateAt(index),
+            })?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn check_point(&self, point: &[A]) -> Result<(), ErrorKind> {
+        let still_inferring = self.dimensions == 0 && self.size == 0;
+        if self.dimensions != point.len() && !still_inferring {
             return Err(ErrorKind::WrongDimension);
         }
         for n in point {
-            if !n.is_finite() {
+            if !n.is_regular() {
                 return Err(ErrorKind::NonFiniteCoordinate);
             }
         }
@@ -368,10 +1226,96 @@ impl<A: Float + Zero + One, T: std::cmp::PartialEq, U: AsRef<[A]> + std::cmp::Pa
     }
 }
 
+impl<A: Float, T: std::cmp::PartialEq, U: AsRef<[A]> + std::cmp::PartialEq> KdTree<A, T, U> {
+    /// Like [`nearest`](Self::nearest), but settling for neighbours at most `1 + epsilon` times
+    /// as far as the exact ones.
+    ///
+    /// The search stops as soon as no unvisited node can be nearer than the `num`th neighbour
+    /// found so far divided by `1 + epsilon`, so the `i`th neighbour returned is at most
+    /// `1 + epsilon` times as far as the exact `i`th neighbour. The factor applies to the values
+    /// returned by `distance`, so with [`squared_euclidean`](crate::distance::squared_euclidean)
+    /// the plain distances are off by at most `sqrt(1 + epsilon)`. An `epsilon` of zero gives the
+    /// exact neighbours. Fails with [`ErrorKind::InvalidEpsilon`] unless `epsilon` is zero or
+    /// positive.
+    pub fn approx_nearest<F>(
+        &self,
+        point: &[A],
+        num: usize,
+        distance: &F,
+        epsilon: A,
+    ) -> Result<Vec<(A, &T)>, ErrorKind>
+    where
+        F: Fn(&[A], &[A]) -> A,
+    {
+        self.check_point(point)?;
+        if !(epsilon >= A::zero() && epsilon.is_finite()) {
+            return Err(ErrorKind::InvalidEpsilon);
+        }
+        Ok(self
+            .nearest_within_factor(point, num, A::one() + epsilon, usize::MAX, distance)
+            .0)
+    }
+
+    /// All entries whose coordinates are exactly equal to `point`, visiting only the nodes whose
+    /// bounds contain it.
+    pub(crate) fn exact_matches(&self, point: &[A]) -> Vec<(&U, &T)> {
+        self.matching(point, Tolerance::Exact)
+    }
+}
+
+impl<A: Axis, T: std::cmp::PartialEq + Clone, U: AsRef<[A]> + std::cmp::PartialEq> KdTree<A, T, U> {
+    /// Like [`nearest`](Self::nearest), but returning clones of the payloads, so the results can
+    /// outlive the tree, e.g. one rebuilt every frame.
+    pub fn nearest_cloned<F>(&self, point: &[A], num: usize, distance: &F) -> Result<Vec<(A, T)>, ErrorKind>
+    where
+        F: Fn(&[A], &[A]) -> A,
+    {
+        Ok(Self::cloned(self.nearest(point, num, distance)?))
+    }
+
+    /// Like [`within`](Self::within), but returning clones of the payloads.
+    pub fn within_cloned<F>(&self, point: &[A], radius: A, distance: &F) -> Result<Vec<(A, T)>, ErrorKind>
+    where
+        F: Fn(&[A], &[A]) -> A,
+    {
+        Ok(Self::cloned(self.within(point, radius, distance)?))
+    }
+
+    fn cloned(found: Vec<(A, &T)>) -> Vec<(A, T)> {
+        found.into_iter().map(|(d, data)| (d, data.clone())).collect()
+    }
+}
+
+pub struct Iter<'a, A, T: PartialEq, U: AsRef<[A]> + PartialEq> {
+    pending: Vec<&'a KdTree<A, T, U>>,
+    points: std::slice::Iter<'a, U>,
+    bucket: std::slice::Iter<'a, T>,
+}
+
+impl<'a, A: Axis, T: PartialEq, U: AsRef<[A]> + PartialEq> Iterator for Iter<'a, A, T, U> {
+    type Item = (&'a U, &'a T);
+    fn next(&mut self) -> Option<(&'a U, &'a T)> {
+        loop {
+            if let (Some(point), Some(data)) = (self.points.next(), self.bucket.next()) {
+                return Some((point, data));
+            }
+            let node = self.pending.pop()?;
+            if node.is_leaf() {
+                self.points = node.points.as_ref().unwrap().iter();
+                self.bucket = node.bucket.as_ref().unwrap().iter();
+            } else {
+                self.pending.extend(node.right.as_deref());
+                self.pending.extend(node.left.as_deref());
+            }
+        }
+    }
+}
+
+/// Iterator over stored points, nearest first, as returned by [`KdTree::iter_nearest`].
 pub struct NearestIter<
     'a,
     'b,
-    A: 'a + 'b + Float,
+    A: 'a + 'b + Axis,
     T: 'b + PartialEq,
     U: 'b + AsRef<[A]> + std::cmp::PartialEq,
     F: 'a + Fn(&[A], &[A]) -> A,
@@ -382,7 +1326,7 @@ pub struct NearestIter<
     distance: &'a F,
 }
 
-impl<'a, 'b, A: Float + Zero + One, T: 'b, U: 'b + AsRef<[A]>, F: 'a> Iterator for NearestIter<'a, 'b, A, T, U, F>
+impl<'a, 'b, A: Axis, T: 'b, U: 'b + AsRef<[A]>, F: 'a> Iterator for NearestIter<'a, 'b, A, T, U, F>
 where
     F: Fn(&[A], &[A]) -> A,
     U: PartialEq,
@@ -395,7 +1339,7 @@ where
         let distance = self.distance;
         let point = self.point;
         while !self.pending.is_empty()
-            && (self.evaluated.peek().map_or(A::infinity(), |x| -x.distance) >= -self.pending.peek().unwrap().distance)
+            && (self.evaluated.peek().map_or(A::greatest(), |x| -x.distance) >= -self.pending.peek().unwrap().distance)
         {
             let mut curr = self.pending.pop().unwrap().element;
             while !curr.is_leaf() {
@@ -407,6 +1351,9 @@ where
                     candidate = curr.left.as_ref().unwrap();
                     curr = curr.right.as_ref().unwrap();
                 }
+                if candidate.size == 0 {
+                    continue;
+                }
                 self.pending.push(HeapElement {
                     distance: -distance_to_space(point, &candidate.min_bounds, &candidate.max_bounds, distance),
                     element: &**candidate,
@@ -423,10 +1370,11 @@ where
     }
 }
 
+/// Iterator over stored points, nearest first, as returned by [`KdTree::iter_nearest_mut`].
 pub struct NearestIterMut<
     'a,
     'b,
-    A: 'a + 'b + Float,
+    A: 'a + 'b + Axis,
     T: 'b + PartialEq,
     U: 'b + AsRef<[A]> + PartialEq,
     F: 'a + Fn(&[A], &[A]) -> A,
@@ -437,7 +1385,7 @@ pub struct NearestIterMut<
     distance: &'a F,
 }
 
-impl<'a, 'b, A: Float + Zero + One, T: 'b, U: 'b + AsRef<[A]>, F: 'a> Iterator for NearestIterMut<'a, 'b, A, T, U, F>
+impl<'a, 'b, A: Axis, T: 'b, U: 'b + AsRef<[A]>, F: 'a> Iterator for NearestIterMut<'a, 'b, A, T, U, F>
 where
     F: Fn(&[A], &[A]) -> A,
     U: PartialEq,
@@ -450,7 +1398,7 @@ where
         let distance = self.distance;
         let point = self.point;
         while !self.pending.is_empty()
-            && (self.evaluated.peek().map_or(A::infinity(), |x| -x.distance) >= -self.pending.peek().unwrap().distance)
+            && (self.evaluated.peek().map_or(A::greatest(), |x| -x.distance) >= -self.pending.peek().unwrap().distance)
         {
             let mut curr = &mut *self.pending.pop().unwrap().element;
             while !curr.is_leaf() {
@@ -462,6 +1410,9 @@ where
                     candidate = curr.left.as_mut().unwrap();
                     curr = curr.right.as_mut().unwrap();
                 }
+                if candidate.size == 0 {
+                    continue;
+                }
                 self.pending.push(HeapElement {
                     distance: -distance_to_space(point, &candidate.min_bounds, &candidate.max_bounds, distance),
                     element: &mut **candidate,
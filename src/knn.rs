@@ -0,0 +1,94 @@
+//! k-nearest-neighbour classification and regression.
+
+use num_traits::Float;
+
+use crate::kdtree::{ErrorKind, KdTree};
+
+/// How the votes of the nearest neighbours are weighted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Weighting {
+    /// Every neighbour counts the same.
+    Uniform,
+    /// Neighbours count by the inverse of their distance, as returned by the distance function.
+    /// If any neighbours are at distance zero, only those count.
+    InverseDistance,
+}
+
+impl Weighting {
+    /// Weights of neighbours at the given distances, nearest first.
+    fn weights<A: Float>(self, distances: &[A]) -> Vec<A> {
+        match self {
+            Weighting::Uniform => vec![A::one(); distances.len()],
+            Weighting::InverseDistance if distances.first().map_or(false, |d| d.is_zero()) => distances
+                .iter()
+                .map(|d| if d.is_zero() { A::one() } else { A::zero() })
+                .collect(),
+            Weighting::InverseDistance => distances.iter().map(|d| d.recip()).collect(),
+        }
+    }
+}
+
+impl<A: Float, T: PartialEq, U: AsRef<[A]> + PartialEq> KdTree<A, T, U> {
+    /// The label with the highest (weighted) vote among the `num` nearest neighbours, or `None`
+    /// for an empty tree.
+    ///
+    /// Ties are broken in favour of the label of the nearest neighbour among the tied labels.
+    pub fn knn_classify<F, L, G>(
+        &self,
+        point: &[A],
+        num: usize,
+        distance: &F,
+        weighting: Weighting,
+        label: G,
+    ) -> Result<Option<L>, ErrorKind>
+    where
+        F: Fn(&[A], &[A]) -> A,
+        L: PartialEq,
+        G: Fn(&T) -> L,
+    {
+        let found = self.nearest(point, num, distance)?;
+        let distances: Vec<A> = found.iter().map(|(d, _)| *d).collect();
+        let mut votes: Vec<(L, A)> = vec![];
+        for ((_, data), weight) in found.into_iter().zip(weighting.weights(&distances)) {
+            let l = label(data);
+            match votes.iter_mut().find(|(existing, _)| *existing == l) {
+                Some((_, total)) => *total = *total + weight,
+                None => votes.push((l, weight)),
+            }
+        }
+        let mut best: Option<(L, A)> = None;
+        for (l, total) in votes {
+            if best.as_ref().map_or(true, |(_, best_total)| total > *best_total) {
+                best = Some((l, total));
+            }
+        }
+        Ok(best.map(|(l, _)| l))
+    }
+
+    /// The (weighted) mean value of the `num` nearest neighbours, or `None` for an empty tree.
+    pub fn knn_regress<F, G>(
+        &self,
+        point: &[A],
+        num: usize,
+        distance: &F,
+        weighting: Weighting,
+        value: G,
+    ) -> Result<Option<A>, ErrorKind>
+    where
+        F: Fn(&[A], &[A]) -> A,
+        G: Fn(&T) -> A,
+    {
+        let found = self.nearest(point, num, distance)?;
+        if found.is_empty() {
+            return Ok(None);
+        }
+        let distances: Vec<A> = found.iter().map(|(d, _)| *d).collect();
+        let (sum, total) = found
+            .into_iter()
+            .zip(weighting.weights(&distances))
+            .fold((A::zero(), A::zero()), |(sum, total), ((_, data), weight)| {
+                (sum + weight * value(data), total + weight)
+            });
+        Ok(Some(sum / total))
+    }
+}
@@ -52,16 +52,133 @@
 //!     vec![(0f64, &1), (2f64, &0), (2f64, &2), (8f64, &3)]
 //! );
 //! ```
+//!
+//! ## Iteration and modification
+//!
+//! Iterators like [`KdTree::iter`] and [`KdTree::iter_nearest`] borrow the tree, so the borrow
+//! checker rules out modifying it while they are alive, and each of them sees the tree as it was
+//! when it was created. Indices into the order of [`KdTree::iter`], as used by index based APIs,
+//! stay valid until the tree is modified; an [`EntryHandle`] wraps such an index and reports
+//! [`ErrorKind::StaleHandle`] instead of a wrong entry once they no longer are.
+//!
+//! Removing while visiting is done by [`KdTree::extract_if`] and [`KdTree::drain`], which remove
+//! their entries before returning, so the iterators they return own these entries and do not
+//! borrow the tree.
+//!
+//! To keep reading a tree while it is being modified, share it as an `Arc<KdTree>` and modify
+//! through [`Arc::make_mut`](std::sync::Arc::make_mut): readers holding the `Arc`, including
+//! [`OwnedNeighbor`]s, keep a consistent snapshot while the writer works on a copy.
+//!
+//! ## Unsafe code
+//!
+//! The crate is built with `#![forbid(unsafe_code)]`, whatever features are enabled. Fast paths
+//! are written in safe code, like the `simd` feature, which leaves vectorization to the compiler
+//! rather than calling SIMD intrinsics.
+#![forbid(unsafe_code)]
+
+#[cfg(feature = "half")]
+extern crate half;
+#[cfg(loom)]
+extern crate loom;
 extern crate num_traits;
+#[cfg(feature = "rayon")]
+extern crate rayon;
 extern crate thiserror;
+#[cfg(feature = "tracing")]
+extern crate tracing;
+#[cfg(feature = "uom")]
+extern crate uom;
 
+#[cfg(feature = "serialize")]
+extern crate serde;
 #[cfg(feature = "serialize")]
 #[cfg_attr(feature = "serialize", macro_use)]
 extern crate serde_derive;
 
+pub mod axis;
+pub mod batch;
+pub mod budget;
+pub mod builder;
+mod chain;
+mod columns;
+#[cfg(feature = "serialize")]
+pub mod compat;
+mod cone;
+mod correlation;
 pub mod distance;
+pub mod domain;
+mod ellipsoid;
+mod empty;
+pub mod estimate;
+mod farthest;
+pub mod fixed;
+pub mod flat;
+pub mod frozen;
+#[cfg(feature = "geo")]
+pub mod geo;
+pub mod grid;
+pub mod handle;
 mod heap_element;
+mod hull;
+#[cfg(feature = "hull3d")]
+mod hull3d;
+mod indexed;
+pub mod interned;
+pub mod jitter;
 pub mod kdtree;
+pub mod knn;
+mod linear;
+#[cfg(feature = "metric_tests")]
+pub mod metric_tests;
+mod mst;
+pub mod multimap;
+pub mod owned;
+pub mod partition;
+pub mod periodic;
+pub mod profile;
+pub mod quantized;
+pub mod render;
+mod rerank;
+mod reverse;
+#[cfg(feature = "async")]
+pub mod service;
+pub mod sharded;
+mod skyline;
+pub mod snapshot;
+pub mod sphere;
+pub mod stats;
+pub mod tolerance;
+#[cfg(feature = "uom")]
+pub mod units;
 mod util;
+mod warm;
+pub use crate::axis::Axis;
+pub use crate::batch::ResultOrder;
+pub use crate::budget::BudgetedNearest;
+pub use crate::builder::KdTreeBuilder;
+pub use crate::domain::{DomainKdTree, OutOfDomain};
+pub use crate::estimate::CountEstimate;
+pub use crate::fixed::FixedKdTree;
+pub use crate::flat::{FlatPoint, Layout};
+pub use crate::frozen::FrozenKdTree;
+pub use crate::grid::Grid;
+pub use crate::handle::EntryHandle;
+pub use crate::interned::InternedKdTree;
+pub use crate::jitter::JitteredKdTree;
 pub use crate::kdtree::ErrorKind;
 pub use crate::kdtree::KdTree;
+pub use crate::knn::Weighting;
+pub use crate::multimap::KdMultiMap;
+pub use crate::owned::OwnedNeighbor;
+pub use crate::partition::{Aabb, LeafRef, SplitPlane, StructureChange};
+pub use crate::periodic::PeriodicKdTree;
+pub use crate::profile::ProfileReport;
+pub use crate::quantized::QuantizedKdTree;
+pub use crate::render::{PointStyle, RenderError, RenderOverlay, SvgOptions, TikzOptions};
+pub use crate::sharded::ShardedKdTree;
+pub use crate::snapshot::SnapshotError;
+pub use crate::sphere::SphereKdTree;
+pub use crate::stats::{DimensionStats, TreeStats};
+pub use crate::tolerance::Tolerance;
+#[cfg(feature = "half")]
+pub use half::{bf16, f16};
@@ -0,0 +1,83 @@
+//! Ranking points by a linear scoring function.
+
+use std::collections::BinaryHeap;
+
+use num_traits::Float;
+
+use crate::heap_element::HeapElement;
+use crate::kdtree::{ErrorKind, KdTree};
+
+enum Entry<'a, A, T: PartialEq, U: AsRef<[A]> + PartialEq> {
+    Node(&'a KdTree<A, T, U>),
+    Point(&'a U, &'a T),
+}
+
+impl<A: Float, T: PartialEq, U: AsRef<[A]> + PartialEq> KdTree<A, T, U> {
+    /// The `num` points with the highest score `weights · point`, highest first.
+    ///
+    /// Subtrees are visited best-first by the highest score any point within their bounds could
+    /// reach, so the search stops as soon as no remaining subtree can beat the points found.
+    pub fn top_k_by_linear(&self, weights: &[A], num: usize) -> Result<Vec<(A, &T)>, ErrorKind> {
+        self.check_point(weights)?;
+        Ok(self
+            .top_k_by_linear_points(weights, num)
+            .into_iter()
+            .map(|(score, _, data)| (score, data))
+            .collect())
+    }
+
+    /// Like [`top_k_by_linear`](Self::top_k_by_linear), also returning the points, without
+    /// validating `weights`.
+    pub(crate) fn top_k_by_linear_points(&self, weights: &[A], num: usize) -> Vec<(A, &U, &T)> {
+        let mut found = vec![];
+        let mut pending = BinaryHeap::new();
+        pending.push(HeapElement {
+            distance: A::infinity(),
+            element: Entry::Node(self),
+        });
+        while found.len() < num {
+            let HeapElement { distance, element } = match pending.pop() {
+                Some(next) => next,
+                None => break,
+            };
+            match element {
+                Entry::Point(point, data) => found.push((distance, point, data)),
+                Entry::Node(node) if node.size == 0 => {}
+                Entry::Node(node) if node.is_leaf() => {
+                    let points = node.points.as_ref().unwrap().iter();
+                    let bucket = node.bucket.as_ref().unwrap().iter();
+                    pending.extend(points.zip(bucket).map(|(p, d)| HeapElement {
+                        distance: dot(weights, p.as_ref()),
+                        element: Entry::Point(p, d),
+                    }));
+                }
+                Entry::Node(node) => {
+                    for child in [&node.left, &node.right].iter() {
+                        let child = child.as_ref().unwrap();
+                        if child.size > 0 {
+                            pending.push(HeapElement {
+                                distance: upper_bound(weights, &child.min_bounds, &child.max_bounds),
+                                element: Entry::Node(&**child),
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        found
+    }
+}
+
+fn dot<A: Float>(a: &[A], b: &[A]) -> A {
+    a.iter().zip(b).fold(A::zero(), |acc, (x, y)| acc + *x * *y)
+}
+
+/// Highest score of any point within the given bounds.
+fn upper_bound<A: Float>(weights: &[A], min_bounds: &[A], max_bounds: &[A]) -> A {
+    weights
+        .iter()
+        .zip(min_bounds.iter().zip(max_bounds))
+        .fold(A::zero(), |acc, (w, (min, max))| {
+            acc + *w * if *w >= A::zero() { *max } else { *min }
+        })
+}
@@ -0,0 +1,96 @@
+//! Checking distance functions before trusting query results to them.
+//!
+//! Queries skip every node whose bounds are farther away than the neighbours found so far, with
+//! the distance to a node's bounds being the distance to the nearest point within them. A
+//! distance function for which some point within the bounds is nearer than that nearest point
+//! makes queries silently miss neighbours. [`check_metric`] looks for such points, and for
+//! violations of the usual properties of a metric, on random samples.
+
+use num_traits::Float;
+
+use crate::util;
+
+/// A property a distance function failed, with the points showing it.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MetricViolation<A> {
+    /// The distance from `a` to `b` differs from the distance from `b` to `a`.
+    NotSymmetric { a: Vec<A>, b: Vec<A> },
+    /// Going from `a` to `c` is longer than going from `a` to `b` and on to `c`.
+    ///
+    /// Queries work fine with such distances, [`squared_euclidean`](crate::distance::squared_euclidean)
+    /// being one of them, but results relying on the distances adding up, like clusters of a
+    /// given radius, may not.
+    TriangleInequality { a: Vec<A>, b: Vec<A>, c: Vec<A> },
+    /// `inside` lies within the box from `min` to `max` but is nearer to `point` than the box,
+    /// so queries would skip nodes holding neighbours.
+    BoxLowerBound {
+        point: Vec<A>,
+        min: Vec<A>,
+        max: Vec<A>,
+        inside: Vec<A>,
+    },
+}
+
+/// Check `distance` on `samples` random sets of points of dimension size `dims`, returning the
+/// first violation found of each property, empty if there is none.
+///
+/// Coordinates are drawn from -100 to 100, with a fixed seed so failures are reproducible.
+/// Differences within a small multiple of the machine epsilon of the distances compared are
+/// tolerated.
+pub fn check_metric<A, F>(distance: &F, dims: usize, samples: usize) -> Vec<MetricViolation<A>>
+where
+    A: Float,
+    F: Fn(&[A], &[A]) -> A,
+{
+    let mut state = 0;
+    let mut sample = || {
+        (0..dims)
+            .map(|_| {
+                state += 1;
+                let unit = (util::splitmix64(state) >> 11) as f64 / (1u64 << 53) as f64;
+                A::from(200.0 * unit - 100.0).unwrap()
+            })
+            .collect::<Vec<A>>()
+    };
+    let (mut asymmetric, mut triangle, mut bound) = (None, None, None);
+    for _ in 0..samples {
+        let (a, b, c) = (sample(), sample(), sample());
+        if asymmetric.is_none() && !close(distance(&a, &b), distance(&b, &a)) {
+            asymmetric = Some(MetricViolation::NotSymmetric {
+                a: a.clone(),
+                b: b.clone(),
+            });
+        }
+        let (ab, bc, ac) = (distance(&a, &b), distance(&b, &c), distance(&a, &c));
+        if triangle.is_none() && ac > ab + bc && !close(ac, ab + bc) {
+            triangle = Some(MetricViolation::TriangleInequality {
+                a: a.clone(),
+                b: b.clone(),
+                c: c.clone(),
+            });
+        }
+        // the box spanned by b and c, with a point inside of it
+        let min: Vec<A> = b.iter().zip(&c).map(|(&x, &y)| x.min(y)).collect();
+        let max: Vec<A> = b.iter().zip(&c).map(|(&x, &y)| x.max(y)).collect();
+        let inside = sample()
+            .into_iter()
+            .zip(min.iter().zip(&max))
+            .map(|(x, (&l, &h))| l + (h - l) * (x + A::from(100.0).unwrap()) / A::from(200.0).unwrap())
+            .collect::<Vec<A>>();
+        let to_box = util::distance_to_space(&a, &min, &max, distance);
+        let to_inside = distance(&a, &inside);
+        if bound.is_none() && to_box > to_inside && !close(to_box, to_inside) {
+            bound = Some(MetricViolation::BoxLowerBound {
+                point: a,
+                min,
+                max,
+                inside,
+            });
+        }
+    }
+    vec![asymmetric, triangle, bound].into_iter().flatten().collect()
+}
+
+fn close<A: Float>(x: A, y: A) -> bool {
+    (x - y).abs() <= A::from(64.0).unwrap() * A::epsilon() * x.abs().max(y.abs())
+}
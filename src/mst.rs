@@ -0,0 +1,282 @@
+//! Euclidean minimum spanning trees.
+
+use num_traits::Float;
+
+use crate::distance::squared_euclidean;
+use crate::kdtree::KdTree;
+
+struct UnionFind {
+    parents: Vec<usize>,
+}
+
+impl UnionFind {
+    fn new(size: usize) -> Self {
+        UnionFind {
+            parents: (0..size).collect(),
+        }
+    }
+
+    fn find(&mut self, mut i: usize) -> usize {
+        while self.parents[i] != i {
+            self.parents[i] = self.parents[self.parents[i]];
+            i = self.parents[i];
+        }
+        i
+    }
+
+    fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        self.parents[a] = b;
+        a != b
+    }
+}
+
+/// A node of the tree being spanned, covering the points `start..end` in the order of
+/// [`KdTree::iter`].
+struct Node<'a, A> {
+    min: &'a [A],
+    max: &'a [A],
+    start: usize,
+    end: usize,
+    children: Option<(usize, usize)>,
+}
+
+impl<'a, A: Float> Node<'a, A> {
+    fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// The euclidean distance between the bounds of two nodes.
+    fn distance(&self, other: &Node<'a, A>) -> A {
+        let mut sum = A::zero();
+        for i in 0..self.min.len() {
+            let gap = (self.min[i] - other.max[i])
+                .max(other.min[i] - self.max[i])
+                .max(A::zero());
+            sum = sum + gap * gap;
+        }
+        sum.sqrt()
+    }
+}
+
+/// Append `node` and the nodes below it to `nodes` in depth-first order, returning its index.
+fn flatten<'a, A, T, U>(node: &'a KdTree<A, T, U>, start: usize, nodes: &mut Vec<Node<'a, A>>) -> usize
+where
+    A: Float,
+    T: PartialEq,
+    U: AsRef<[A]> + PartialEq,
+{
+    let index = nodes.len();
+    nodes.push(Node {
+        min: &node.min_bounds,
+        max: &node.max_bounds,
+        start,
+        end: start + node.size,
+        children: None,
+    });
+    if let (Some(left), Some(right)) = (node.left.as_deref(), node.right.as_deref()) {
+        let left_index = flatten(left, start, nodes);
+        let right_index = flatten(right, start + left.size, nodes);
+        nodes[index].children = Some((left_index, right_index));
+    }
+    index
+}
+
+/// One Borůvka round, finding the best edge leaving every component by a dual-tree traversal.
+struct Round<'a, A, W> {
+    nodes: &'a [Node<'a, A>],
+    points: &'a [&'a [A]],
+    /// The component of every point, by its root.
+    roots: &'a [usize],
+    /// The component all points of a node belong to, if they do.
+    components: Vec<Option<usize>>,
+    /// The best edge found leaving each component, by its root.
+    best: Vec<Option<(A, usize, usize)>>,
+    /// An upper bound on the weight of the best edges of the components of a node's points.
+    bounds: Vec<A>,
+    weight: &'a W,
+}
+
+impl<'a, A, W> Round<'a, A, W>
+where
+    A: Float,
+    W: Fn(usize, usize, A) -> A,
+{
+    fn new(nodes: &'a [Node<'a, A>], points: &'a [&'a [A]], roots: &'a [usize], weight: &'a W) -> Self {
+        // children follow their parents, so going backwards sees them first
+        let mut components = vec![None; nodes.len()];
+        for (index, node) in nodes.iter().enumerate().rev() {
+            components[index] = match node.children {
+                Some((left, right)) if nodes[left].is_empty() => components[right],
+                Some((left, right)) if nodes[right].is_empty() => components[left],
+                Some((left, right)) => match (components[left], components[right]) {
+                    (Some(a), Some(b)) if a == b => Some(a),
+                    _ => None,
+                },
+                None => {
+                    let first = roots.get(node.start).copied();
+                    first.filter(|&root| roots[node.start..node.end].iter().all(|&r| r == root))
+                }
+            };
+        }
+        Round {
+            nodes,
+            points,
+            roots,
+            components,
+            best: vec![None; points.len()],
+            bounds: vec![A::infinity(); nodes.len()],
+            weight,
+        }
+    }
+
+    /// Look for edges from the points of `query` to those of `reference`, skipping pairs of
+    /// nodes within a single component or too far apart to improve on the edges found.
+    fn traverse(&mut self, query: usize, reference: usize) {
+        let (q, r) = (&self.nodes[query], &self.nodes[reference]);
+        if q.is_empty() || r.is_empty() {
+            return;
+        }
+        if let (Some(a), Some(b)) = (self.components[query], self.components[reference]) {
+            if a == b {
+                return;
+            }
+        }
+        // weights are never below the distance, which is never below that of the bounds
+        if q.distance(r) > self.bounds[query] {
+            return;
+        }
+        match (q.children, r.children) {
+            (None, None) => self.base_case(query, reference),
+            (Some((left, right)), None) => {
+                self.traverse(left, reference);
+                self.traverse(right, reference);
+                self.bounds[query] = self.bounds[left].max(self.bounds[right]);
+            }
+            (None, Some(_)) => self.traverse_references(query, reference),
+            (Some((left, right)), Some(_)) => {
+                self.traverse_references(left, reference);
+                self.traverse_references(right, reference);
+                self.bounds[query] = self.bounds[left].max(self.bounds[right]);
+            }
+        }
+    }
+
+    /// Traverse the children of `reference`, the one nearer to `query` first.
+    fn traverse_references(&mut self, query: usize, reference: usize) {
+        let (left, right) = self.nodes[reference].children.unwrap();
+        let q = &self.nodes[query];
+        if q.distance(&self.nodes[right]) < q.distance(&self.nodes[left]) {
+            self.traverse(query, right);
+            self.traverse(query, left);
+        } else {
+            self.traverse(query, left);
+            self.traverse(query, right);
+        }
+    }
+
+    fn base_case(&mut self, query: usize, reference: usize) {
+        let (q, r) = (&self.nodes[query], &self.nodes[reference]);
+        let mut bound = A::zero();
+        for i in q.start..q.end {
+            let root = self.roots[i];
+            for j in r.start..r.end {
+                if self.roots[j] == root {
+                    continue;
+                }
+                let d = squared_euclidean(self.points[i], self.points[j]).sqrt();
+                if let Some((w, _, _)) = self.best[root] {
+                    if d > w {
+                        continue;
+                    }
+                }
+                let candidate = ((self.weight)(i, j, d), i.min(j), i.max(j));
+                if self.best[root].map_or(true, |b| edge_cmp(candidate, b) == std::cmp::Ordering::Less) {
+                    self.best[root] = Some(candidate);
+                }
+            }
+            bound = bound.max(self.best[root].map_or(A::infinity(), |(w, _, _)| w));
+        }
+        self.bounds[query] = self.bounds[query].min(bound);
+    }
+}
+
+/// Borůvka's algorithm over the points of `tree`, connecting `i` and `j` at `weight(i, j, d)`,
+/// where `d` is their euclidean distance and `weight(i, j, d) >= d`.
+///
+/// Every round finds the best edge leaving each component with a single dual-tree traversal of
+/// the tree against itself, after March, Ram and Gray: pairs of nodes whose points all belong to
+/// the same component are skipped, as are pairs farther apart than the best edges already found
+/// for the components of the first node's points.
+fn boruvka<A, T, U, W>(tree: &KdTree<A, T, U>, weight: W) -> Vec<(usize, usize, A)>
+where
+    A: Float,
+    T: PartialEq,
+    U: AsRef<[A]> + PartialEq,
+    W: Fn(usize, usize, A) -> A,
+{
+    let points: Vec<&[A]> = tree.iter().map(|(p, _)| p.as_ref()).collect();
+    let mut nodes = vec![];
+    flatten(tree, 0, &mut nodes);
+    let mut components = UnionFind::new(points.len());
+    let mut edges = Vec::with_capacity(points.len().saturating_sub(1));
+    let mut remaining = points.len();
+
+    while remaining > 1 {
+        let roots: Vec<usize> = (0..points.len()).map(|i| components.find(i)).collect();
+        let mut round = Round::new(&nodes, &points, &roots, &weight);
+        round.traverse(0, 0);
+        for (w, i, j) in round.best.into_iter().flatten() {
+            if components.union(i, j) {
+                edges.push((i, j, w));
+                remaining -= 1;
+            }
+        }
+    }
+    edges.sort_by(|a, b| edge_cmp((a.2, a.0, a.1), (b.2, b.0, b.1)));
+    edges
+}
+
+/// Orders edges by weight, breaking ties by their endpoints so Borůvka never forms cycles.
+fn edge_cmp<A: Float>(a: (A, usize, usize), b: (A, usize, usize)) -> std::cmp::Ordering {
+    a.0.partial_cmp(&b.0)
+        .unwrap_or(std::cmp::Ordering::Equal)
+        .then((a.1, a.2).cmp(&(b.1, b.2)))
+}
+
+impl<A: Float, T: PartialEq, U: AsRef<[A]> + PartialEq> KdTree<A, T, U> {
+    /// The euclidean minimum spanning tree over all points, as `(i, j, distance)` edges with
+    /// `i < j`, shortest first.
+    ///
+    /// Indices refer to the order of [`iter`](Self::iter). Computed with the dual-tree variant
+    /// of Borůvka's algorithm, every round traversing the tree against itself while skipping
+    /// pairs of nodes within a single component or too far apart to improve on the edges found.
+    pub fn euclidean_mst(&self) -> Vec<(usize, usize, A)> {
+        boruvka(self, |_, _, d| d)
+    }
+
+    /// The euclidean distance of every point to its `min_samples`-th nearest neighbour, the
+    /// point itself included, in the order of [`iter`](Self::iter).
+    ///
+    /// These are the core distances of HDBSCAN and related density based clustering algorithms.
+    pub fn core_distances(&self, min_samples: usize) -> Vec<A> {
+        self.iter()
+            .map(|(point, _)| {
+                let found = self.nearest_unchecked(point.as_ref(), min_samples, &squared_euclidean);
+                found.last().map_or(A::zero(), |(d, _)| d.sqrt())
+            })
+            .collect()
+    }
+
+    /// The minimum spanning tree under the mutual reachability distance
+    /// `max(core(i), core(j), distance(i, j))`, as `(i, j, mutual reachability)` edges with
+    /// `i < j`, shortest first.
+    ///
+    /// Together with [`core_distances`](Self::core_distances), this is the spatial part of
+    /// HDBSCAN; turning the tree into a cluster hierarchy is left to the caller. Indices refer to
+    /// the order of [`iter`](Self::iter).
+    pub fn mutual_reachability_mst(&self, min_samples: usize) -> Vec<(usize, usize, A)> {
+        let core = self.core_distances(min_samples);
+        boruvka(self, |i, j, d| d.max(core[i]).max(core[j]))
+    }
+}
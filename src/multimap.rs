@@ -0,0 +1,173 @@
+//! A tree mapping each distinct coordinate to all payloads stored at it.
+//!
+//! Event data often has many records at the very same location. Rather than storing one tree
+//! entry per record, [`KdMultiMap`] stores one entry per distinct coordinate and keeps the
+//! records in a `Vec` next to it.
+
+use num_traits::Float;
+
+use crate::kdtree::{ErrorKind, KdTree};
+
+#[derive(Clone, Debug)]
+pub struct KdMultiMap<A, T, U: AsRef<[A]> + PartialEq> {
+    tree: KdTree<A, usize, U>,
+    slots: Vec<Vec<T>>,
+    free_slots: Vec<usize>,
+    size: usize,
+}
+
+impl<A: Float, T, U: AsRef<[A]> + PartialEq> KdMultiMap<A, T, U> {
+    /// Create a new multi map, specifying the dimension size of each point
+    pub fn new(dims: usize) -> Self {
+        KdMultiMap::from_tree(KdTree::new(dims))
+    }
+
+    /// Create a new multi map, specifying the dimension size of each point and the capacity of
+    /// leaf nodes
+    pub fn with_capacity(dimensions: usize, capacity: usize) -> Self {
+        KdMultiMap::from_tree(KdTree::with_capacity(dimensions, capacity))
+    }
+
+    fn from_tree(tree: KdTree<A, usize, U>) -> Self {
+        KdMultiMap {
+            tree,
+            slots: vec![],
+            free_slots: vec![],
+            size: 0,
+        }
+    }
+
+    /// Number of payloads stored, counting every payload at a shared coordinate.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Number of distinct coordinates stored.
+    pub fn coordinates(&self) -> usize {
+        self.tree.size()
+    }
+
+    /// Add `data` at `point`, next to any payloads already stored at exactly these coordinates.
+    pub fn add(&mut self, point: U, data: T) -> Result<(), ErrorKind> {
+        if let Some(slot) = self.slot(point.as_ref()) {
+            self.slots[slot].push(data);
+        } else {
+            let slot = self.free_slots.pop().unwrap_or(self.slots.len());
+            if let Err(err) = self.tree.add(point, slot) {
+                if slot < self.slots.len() {
+                    self.free_slots.push(slot);
+                }
+                return Err(err);
+            }
+            if slot == self.slots.len() {
+                self.slots.push(vec![data]);
+            } else {
+                self.slots[slot].push(data);
+            }
+        }
+        self.size += 1;
+        Ok(())
+    }
+
+    /// All payloads stored at exactly `point`, in insertion order.
+    pub fn get_all(&self, point: &[A]) -> Result<&[T], ErrorKind> {
+        self.tree.check_point(point)?;
+        Ok(match self.slot(point) {
+            Some(slot) => &self.slots[slot],
+            None => &[],
+        })
+    }
+
+    /// Remove the first payload equal to `data` stored at exactly `point`.
+    pub fn remove_one(&mut self, point: &U, data: &T) -> Result<Option<T>, ErrorKind>
+    where
+        T: PartialEq,
+    {
+        self.tree.check_point(point.as_ref())?;
+        let slot = match self.slot(point.as_ref()) {
+            Some(slot) => slot,
+            None => return Ok(None),
+        };
+        let removed = match self.slots[slot].iter().position(|d| d == data) {
+            Some(position) => self.slots[slot].remove(position),
+            None => return Ok(None),
+        };
+        self.size -= 1;
+        if self.slots[slot].is_empty() {
+            self.release(point, slot)?;
+        }
+        Ok(Some(removed))
+    }
+
+    /// Remove all payloads stored at exactly `point`.
+    pub fn remove_all(&mut self, point: &U) -> Result<Vec<T>, ErrorKind> {
+        self.tree.check_point(point.as_ref())?;
+        let slot = match self.slot(point.as_ref()) {
+            Some(slot) => slot,
+            None => return Ok(vec![]),
+        };
+        let removed = std::mem::take(&mut self.slots[slot]);
+        self.size -= removed.len();
+        self.release(point, slot)?;
+        Ok(removed)
+    }
+
+    /// The `num` nearest distinct coordinates, each with all payloads stored at it.
+    pub fn nearest<F>(&self, point: &[A], num: usize, distance: &F) -> Result<Vec<(A, &[T])>, ErrorKind>
+    where
+        F: Fn(&[A], &[A]) -> A,
+    {
+        Ok(self
+            .tree
+            .nearest(point, num, distance)?
+            .into_iter()
+            .map(|(distance, &slot)| (distance, &self.slots[slot][..]))
+            .collect())
+    }
+
+    /// The `num` nearest payloads, nearest first, payloads at the same coordinates in insertion
+    /// order.
+    ///
+    /// Unlike [`nearest`](Self::nearest), this counts payloads rather than coordinates, giving
+    /// the same neighbours a [`KdTree`] holding every payload as a point of its own would. Each
+    /// group of payloads at the same coordinates is looked up once, however large it is.
+    pub fn nearest_payloads<F>(&self, point: &[A], num: usize, distance: &F) -> Result<Vec<(A, &T)>, ErrorKind>
+    where
+        F: Fn(&[A], &[A]) -> A,
+    {
+        let mut found = Vec::with_capacity(num.min(self.size));
+        let mut nearest = self.tree.iter_nearest(point, distance)?;
+        while found.len() < num {
+            let (d, &slot) = match nearest.next() {
+                Some(next) => next,
+                None => break,
+            };
+            let wanted = num - found.len();
+            found.extend(self.slots[slot].iter().take(wanted).map(|data| (d, data)));
+        }
+        Ok(found)
+    }
+
+    /// All distinct coordinates within `radius`, each with all payloads stored at it.
+    pub fn within<F>(&self, point: &[A], radius: A, distance: &F) -> Result<Vec<(A, &[T])>, ErrorKind>
+    where
+        F: Fn(&[A], &[A]) -> A,
+    {
+        Ok(self
+            .tree
+            .within(point, radius, distance)?
+            .into_iter()
+            .map(|(distance, &slot)| (distance, &self.slots[slot][..]))
+            .collect())
+    }
+
+    fn slot(&self, point: &[A]) -> Option<usize> {
+        self.tree.exact_matches(point).first().map(|&(_, &slot)| slot)
+    }
+
+    fn release(&mut self, point: &U, slot: usize) -> Result<(), ErrorKind> {
+        self.tree.remove(point, &slot)?;
+        self.free_slots.push(slot);
+        Ok(())
+    }
+}
@@ -0,0 +1,117 @@
+//! Query results that own a handle on the tree instead of borrowing it.
+
+use std::sync::Arc;
+
+use num_traits::Float;
+
+use crate::kdtree::{ErrorKind, KdTree};
+
+/// A neighbour found by [`KdTree::nearest_owned`] or [`KdTree::within_owned`].
+///
+/// It keeps the tree alive through an `Arc`, so it can be sent to other threads or held across
+/// `.await` points without copying the payload.
+#[derive(Debug)]
+pub struct OwnedNeighbor<A: PartialEq, T: PartialEq, U: AsRef<[A]> + PartialEq> {
+    distance: A,
+    index: usize,
+    tree: Arc<KdTree<A, T, U>>,
+}
+
+impl<A: Float, T: PartialEq, U: AsRef<[A]> + PartialEq> OwnedNeighbor<A, T, U> {
+    pub fn distance(&self) -> A {
+        self.distance
+    }
+
+    /// The index of the point, in the order of [`KdTree::iter`].
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn point(&self) -> &U {
+        self.tree.get_by_index(self.index).unwrap().0
+    }
+
+    pub fn data(&self) -> &T {
+        self.tree.get_by_index(self.index).unwrap().1
+    }
+
+    pub fn tree(&self) -> &Arc<KdTree<A, T, U>> {
+        &self.tree
+    }
+}
+
+impl<A: Clone + PartialEq, T: PartialEq, U: AsRef<[A]> + PartialEq> Clone for OwnedNeighbor<A, T, U> {
+    fn clone(&self) -> Self {
+        OwnedNeighbor {
+            distance: self.distance.clone(),
+            index: self.index,
+            tree: Arc::clone(&self.tree),
+        }
+    }
+}
+
+impl<A: Float, T: PartialEq, U: AsRef<[A]> + PartialEq> KdTree<A, T, U> {
+    /// Like [`nearest`](Self::nearest), but each result holds a clone of the `Arc` instead of a
+    /// borrow of the tree.
+    pub fn nearest_owned<F>(
+        self: &Arc<Self>,
+        point: &[A],
+        num: usize,
+        distance: &F,
+    ) -> Result<Vec<OwnedNeighbor<A, T, U>>, ErrorKind>
+    where
+        F: Fn(&[A], &[A]) -> A,
+    {
+        self.check_point(point)?;
+        let found = self.nearest_indexed(point, num, A::infinity(), distance, |_| true);
+        Ok(self.owned(found.into_iter().map(|(d, i, _)| (d, i))))
+    }
+
+    /// Like [`within`](Self::within), but each result holds a clone of the `Arc` instead of a
+    /// borrow of the tree.
+    pub fn within_owned<F>(
+        self: &Arc<Self>,
+        point: &[A],
+        radius: A,
+        distance: &F,
+    ) -> Result<Vec<OwnedNeighbor<A, T, U>>, ErrorKind>
+    where
+        F: Fn(&[A], &[A]) -> A,
+    {
+        self.check_point(point)?;
+        let found = self.nearest_indexed(point, self.size, radius, distance, |_| true);
+        Ok(self.owned(found.into_iter().map(|(d, i, _)| (d, i))))
+    }
+
+    fn owned<I: Iterator<Item = (A, usize)>>(self: &Arc<Self>, found: I) -> Vec<OwnedNeighbor<A, T, U>> {
+        found
+            .map(|(distance, index)| OwnedNeighbor {
+                distance,
+                index,
+                tree: Arc::clone(self),
+            })
+            .collect()
+    }
+
+    /// The point and data at `index` in the order of [`iter`](Self::iter), descending by the
+    /// sizes of the left subtrees.
+    pub(crate) fn get_by_index(&self, mut index: usize) -> Option<(&U, &T)> {
+        if index >= self.size {
+            return None;
+        }
+        let mut node = self;
+        while !node.is_leaf() {
+            let left = node.left.as_deref().unwrap();
+            if index < left.size {
+                node = left;
+            } else {
+                index -= left.size;
+                node = node.right.as_deref().unwrap();
+            }
+        }
+        Some((
+            &node.points.as_ref().unwrap()[index],
+            &node.bucket.as_ref().unwrap()[index],
+        ))
+    }
+}
@@ -0,0 +1,238 @@
+//! The partitioning of space by a tree, as plain data.
+//!
+//! Every stem splits its cell in two along its split value. The root's cell is the bounding box
+//! of all points, the same cells the [`render`](crate::render) module draws.
+
+use num_traits::Float;
+
+use crate::kdtree::{ErrorKind, KdTree};
+
+/// An axis-aligned box given by its minimum and maximum corner.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Aabb<A> {
+    pub min: Vec<A>,
+    pub max: Vec<A>,
+}
+
+impl<A: Float> Aabb<A> {
+    /// Whether `point` lies within the box, its boundary included.
+    pub fn contains(&self, point: &[A]) -> bool {
+        point
+            .iter()
+            .zip(self.min.iter().zip(&self.max))
+            .all(|(&x, (&min, &max))| min <= x && x <= max)
+    }
+
+    /// The two halves of the box on either side of `value` in `dimension`.
+    fn split(self, dimension: usize, value: A) -> (Self, Self) {
+        let (mut left, mut right) = (self.clone(), self);
+        left.max[dimension] = value;
+        right.min[dimension] = value;
+        (left, right)
+    }
+}
+
+/// A structural change to a tree, as returned by [`KdTree::changes_since`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StructureChange {
+    /// The leaf `id` was split, becoming a stem with two new leaves as children.
+    Split { id: u64, left: u64, right: u64 },
+    /// The stem `id` was replaced by its child `kept`, its other child `removed` being empty.
+    /// When replacing the root, `kept` takes the root's ID 0.
+    Collapse { id: u64, kept: u64, removed: u64 },
+}
+
+/// The generation of a tree and the changes leading up to it.
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ChangeLog {
+    pub(crate) generation: u64,
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    pub(crate) entries: Vec<StructureChange>,
+}
+
+impl ChangeLog {
+    /// Record splitting the leaf `id`, returning the IDs of its new children.
+    pub(crate) fn split(&mut self, id: u64) -> (u64, u64) {
+        self.generation += 1;
+        // two fresh IDs per generation, the root keeps 0
+        let (left, right) = (2 * self.generation - 1, 2 * self.generation);
+        self.entries.push(StructureChange::Split { id, left, right });
+        (left, right)
+    }
+
+    /// Record replacing the stem `id` by its child `kept`.
+    pub(crate) fn collapse(&mut self, id: u64, kept: u64, removed: u64) {
+        self.generation += 1;
+        self.entries.push(StructureChange::Collapse { id, kept, removed });
+    }
+}
+
+/// A leaf with its cell, as returned by [`KdTree::locate_leaf`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct LeafRef<'a, A, T, U> {
+    /// ID of the leaf, see [`KdTree::generation`].
+    pub id: u64,
+    /// The cell of the leaf.
+    pub bounds: Aabb<A>,
+    /// Points stored in the leaf.
+    pub points: &'a [U],
+    /// Data stored in the leaf, in the order of `points`.
+    pub data: &'a [T],
+}
+
+/// The split of a stem, as returned by [`KdTree::split_planes`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct SplitPlane<A> {
+    /// ID of the stem, see [`KdTree::generation`].
+    pub id: u64,
+    /// Dimension the stem splits along.
+    pub dimension: usize,
+    /// Points with a coordinate below this value in `dimension` are stored left, others right.
+    /// Points equal to it are stored left too if it is also the smallest coordinate stored.
+    pub value: A,
+    /// Depth of the stem, the root having depth 0.
+    pub depth: usize,
+    /// The cell split by the stem.
+    pub bounds: Aabb<A>,
+}
+
+impl<A: Float, T: PartialEq, U: AsRef<[A]> + PartialEq> KdTree<A, T, U> {
+    /// The splits of all stems, parents before their children and left before right.
+    pub fn split_planes(&self) -> Vec<SplitPlane<A>> {
+        let mut planes = vec![];
+        let root = Aabb {
+            min: self.min_bounds.to_vec(),
+            max: self.max_bounds.to_vec(),
+        };
+        let mut pending = vec![(self, root, 0)];
+        while let Some((node, bounds, depth)) = pending.pop() {
+            if node.is_leaf() {
+                continue;
+            }
+            let dimension = node.split_dimension.unwrap();
+            let value = node.split_value.unwrap();
+            let (left, right) = bounds.clone().split(dimension, value);
+            pending.extend(node.right.as_deref().map(|node| (node, right, depth + 1)));
+            pending.extend(node.left.as_deref().map(|node| (node, left, depth + 1)));
+            planes.push(SplitPlane {
+                id: node.id,
+                dimension,
+                value,
+                depth,
+                bounds,
+            });
+        }
+        planes
+    }
+    /// The cells of all leaves with the points and data stored in them, in the order of
+    /// [`iter`](Self::iter). Together, the cells cover the bounding box of all points without
+    /// overlapping. An empty tree has no cells.
+    pub fn leaf_cells(&self) -> Vec<(Aabb<A>, &[U], &[T])> {
+        let mut cells = vec![];
+        if self.size == 0 {
+            return cells;
+        }
+        let root = Aabb {
+            min: self.min_bounds.to_vec(),
+            max: self.max_bounds.to_vec(),
+        };
+        let mut pending = vec![(self, root)];
+        while let Some((node, bounds)) = pending.pop() {
+            if node.is_leaf() {
+                let points = node.points.as_deref().unwrap();
+                let bucket = node.bucket.as_deref().unwrap();
+                cells.push((bounds, points, bucket));
+                continue;
+            }
+            let dimension = node.split_dimension.unwrap();
+            let value = node.split_value.unwrap();
+            let (left, right) = bounds.split(dimension, value);
+            pending.extend(node.right.as_deref().map(|node| (node, right)));
+            pending.extend(node.left.as_deref().map(|node| (node, left)));
+        }
+        cells
+    }
+    /// The leaf whose cell `point` falls into, the same way it would be added, no matter how far
+    /// the nearest stored point is.
+    ///
+    /// Cells are those of [`leaf_cells`](Self::leaf_cells), so a point outside of the bounding
+    /// box of all points lies outside of the bounds returned too. For an empty tree, the leaf is
+    /// the root with bounds containing nothing.
+    pub fn locate_leaf(&self, point: &[A]) -> Result<LeafRef<'_, A, T, U>, ErrorKind> {
+        self.check_point(point)?;
+        let mut node = self;
+        let mut bounds = Aabb {
+            min: self.min_bounds.to_vec(),
+            max: self.max_bounds.to_vec(),
+        };
+        while !node.is_leaf() {
+            let (left, right) = bounds.split(node.split_dimension.unwrap(), node.split_value.unwrap());
+            if node.belongs_in_left(point) {
+                node = node.left.as_deref().unwrap();
+                bounds = left;
+            } else {
+                node = node.right.as_deref().unwrap();
+                bounds = right;
+            }
+        }
+        Ok(LeafRef {
+            id: node.id,
+            bounds,
+            points: node.points.as_deref().unwrap(),
+            data: node.bucket.as_deref().unwrap(),
+        })
+    }
+
+    /// The pairs of leaves whose [cells](Self::leaf_cells) share a face, by their IDs, the
+    /// smaller ID first, sorted.
+    ///
+    /// Two cells share a face if they touch along one split plane and their extents overlap in
+    /// every other dimension; cells only touching at an edge or a corner are not adjacent. Empty
+    /// leaves are included, their cells being free space. IDs stay valid until the structure of
+    /// the tree changes, see [`generation`](Self::generation).
+    pub fn leaf_adjacency(&self) -> Vec<(u64, u64)> {
+        let mut edges = vec![];
+        if self.size > 0 {
+            let root = Aabb {
+                min: self.min_bounds.to_vec(),
+                max: self.max_bounds.to_vec(),
+            };
+            self.adjacent_leaves(root, &mut edges);
+        }
+        edges.sort_unstable();
+        edges
+    }
+
+    /// Collect the adjacent leaves below this node into `edges`, returning the cells of all of
+    /// them.
+    fn adjacent_leaves(&self, bounds: Aabb<A>, edges: &mut Vec<(u64, u64)>) -> Vec<(u64, Aabb<A>)> {
+        if self.is_leaf() {
+            return vec![(self.id, bounds)];
+        }
+        let dimension = self.split_dimension.unwrap();
+        let value = self.split_value.unwrap();
+        let (left, right) = bounds.split(dimension, value);
+        let mut left = self.left.as_ref().unwrap().adjacent_leaves(left, edges);
+        let right = self.right.as_ref().unwrap().adjacent_leaves(right, edges);
+        // only leaves touching this stem's plane can be adjacent across it
+        for (a, a_cell) in left.iter().filter(|(_, cell)| cell.max[dimension] == value) {
+            for (b, b_cell) in right.iter().filter(|(_, cell)| cell.min[dimension] == value) {
+                if (0..a_cell.min.len()).all(|d| d == dimension || faces_overlap(a_cell, b_cell, d)) {
+                    edges.push((*a.min(b), *a.max(b)));
+                }
+            }
+        }
+        left.extend(right);
+        left
+    }
+}
+
+/// Whether the extents of `a` and `b` along `dimension` overlap by more than a point, or meet
+/// where one of them has no extent at all.
+fn faces_overlap<A: Float>(a: &Aabb<A>, b: &Aabb<A>, dimension: usize) -> bool {
+    let lo = a.min[dimension].max(b.min[dimension]);
+    let hi = a.max[dimension].min(b.max[dimension]);
+    let flat = a.min[dimension] == a.max[dimension] || b.min[dimension] == b.max[dimension];
+    lo < hi || (lo == hi && flat)
+}
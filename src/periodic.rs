@@ -0,0 +1,136 @@
+//! Nearest neighbours in a periodic box, as in molecular dynamics simulations.
+//!
+//! Every dimension wraps around after its box length, so the nearest image of a point may lie
+//! across the box edge. [`PeriodicKdTree`] stores points wrapped into the box and measures
+//! distances to the nearest image, both between points and when pruning subtrees: a node's
+//! bounds are checked against the query from both sides of the box, so points near the edges
+//! are found from the other side.
+
+use num_traits::Float;
+
+use crate::kdtree::{ErrorKind, KdTree};
+
+/// A tree of points in a box that wraps around in every dimension, answering queries by squared
+/// euclidean distance to the nearest image.
+#[derive(Clone, Debug)]
+pub struct PeriodicKdTree<A: PartialEq, T: PartialEq> {
+    tree: KdTree<A, T, Vec<A>>,
+    lengths: Vec<A>,
+}
+
+impl<A: Float, T: PartialEq> PeriodicKdTree<A, T> {
+    /// Create a new tree for a box of the given length along each dimension.
+    ///
+    /// Fails with [`ErrorKind::InvalidPeriod`] unless all lengths are finite and positive.
+    pub fn new(lengths: Vec<A>) -> Result<Self, ErrorKind> {
+        PeriodicKdTree::with_capacity(lengths, 2_usize.pow(4))
+    }
+
+    /// Like [`new`](Self::new), specifying the capacity of leaf nodes.
+    pub fn with_capacity(lengths: Vec<A>, capacity: usize) -> Result<Self, ErrorKind> {
+        if lengths.is_empty() || !lengths.iter().all(|&l| l > A::zero() && l.is_finite()) {
+            return Err(ErrorKind::InvalidPeriod);
+        }
+        Ok(PeriodicKdTree {
+            tree: KdTree::with_capacity(lengths.len(), capacity),
+            lengths,
+        })
+    }
+
+    pub fn size(&self) -> usize {
+        self.tree.size()
+    }
+
+    /// The length of the box along each dimension.
+    pub fn lengths(&self) -> &[A] {
+        &self.lengths
+    }
+
+    /// Add `data` at `point`, wrapped into the box first.
+    pub fn add(&mut self, point: &[A], data: T) -> Result<(), ErrorKind> {
+        self.tree.check_point(point)?;
+        let wrapped = self.wrap(point);
+        self.tree.add(wrapped, data)
+    }
+
+    /// Iterate over all points, wrapped into the box, and their data.
+    pub fn iter(&self) -> impl Iterator<Item = (&[A], &T)> {
+        self.tree.iter().map(|(point, data)| (&point[..], data))
+    }
+
+    /// The squared euclidean distance between the nearest images of `a` and `b`.
+    pub fn squared_distance(&self, a: &[A], b: &[A]) -> A {
+        a.iter()
+            .zip(b)
+            .zip(&self.lengths)
+            .map(|((&x, &y), &length)| {
+                let d = self.wrap_coordinate(x - y, length).abs();
+                let d = d.min(length - d);
+                d * d
+            })
+            .fold(A::zero(), |sum, d| sum + d)
+    }
+
+    /// The `num` nearest points, nearest first, with their squared distance to the nearest
+    /// image of `point`.
+    pub fn nearest(&self, point: &[A], num: usize) -> Result<Vec<(A, &T)>, ErrorKind> {
+        self.tree.check_point(point)?;
+        Ok(self.search(&self.wrap(point), num, A::infinity()))
+    }
+
+    /// All points whose nearest image is at most `radius` from `point`, nearest first, with
+    /// their squared distance. Like the distances returned, `radius` is a squared distance.
+    pub fn within(&self, point: &[A], radius: A) -> Result<Vec<(A, &T)>, ErrorKind> {
+        self.tree.check_point(point)?;
+        Ok(self.search(&self.wrap(point), self.size(), radius))
+    }
+
+    /// The `num` nearest points to `point`, already wrapped, at most `max_dist` away.
+    fn search(&self, point: &[A], num: usize, max_dist: A) -> Vec<(A, &T)> {
+        let distance = |a: &[A], b: &[A]| self.squared_distance(a, b);
+        let to_box = |point: &[A], min: &[A], max: &[A]| self.distance_to_box(point, min, max);
+        self.tree
+            .nearest_indexed_by(point, num, max_dist, &distance, |_| true, |_, _| true, to_box)
+            .into_iter()
+            .map(|(d, _, data)| (d, data))
+            .collect()
+    }
+
+    /// The squared distance from `point` to the nearest image of the box from `min` to `max`, all
+    /// within the periodic box.
+    fn distance_to_box(&self, point: &[A], min: &[A], max: &[A]) -> A {
+        let mut sum = A::zero();
+        for i in 0..point.len() {
+            let (x, lo, hi, length) = (point[i], min[i], max[i], self.lengths[i]);
+            // the box is left either towards its near side or around the edge to its far side
+            let d = if x < lo {
+                (lo - x).min(length - (hi - x))
+            } else if x > hi {
+                (x - hi).min(length - (x - lo))
+            } else {
+                A::zero()
+            };
+            sum = sum + d * d;
+        }
+        sum
+    }
+
+    fn wrap(&self, point: &[A]) -> Vec<A> {
+        point
+            .iter()
+            .zip(&self.lengths)
+            .map(|(&x, &length)| self.wrap_coordinate(x, length))
+            .collect()
+    }
+
+    /// `x` moved into `0..length` by a multiple of `length`.
+    fn wrap_coordinate(&self, x: A, length: A) -> A {
+        let wrapped = x - (x / length).floor() * length;
+        // rounding may land exactly on the far edge
+        if wrapped >= length {
+            A::zero()
+        } else {
+            wrapped
+        }
+    }
+}
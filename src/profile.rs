@@ -0,0 +1,156 @@
+//! Profiling nearest neighbour queries against a tree, to judge how well it suits a workload.
+
+use std::fmt;
+
+use num_traits::Float;
+
+use crate::kdtree::{ErrorKind, KdTree};
+
+/// Aggregate statistics over a batch of queries, as returned by
+/// [`KdTree::profile_queries`](crate::KdTree::profile_queries).
+///
+/// Latencies are in whatever unit the timer passed to `profile_queries` counts in.
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ProfileReport {
+    /// Number of queries run.
+    pub queries: usize,
+    /// Number of neighbours asked for per query.
+    pub k: usize,
+    /// Number of nodes walked through, stems and leaves, over all queries.
+    pub nodes_visited: usize,
+    /// Number of leaves whose points were compared to the query, over all queries.
+    pub leaves_scanned: usize,
+    /// Number of distance computations against stored points, over all queries.
+    pub points_evaluated: usize,
+    /// Median latency of a single query.
+    pub latency_p50: f64,
+    /// 90th percentile latency of a single query.
+    pub latency_p90: f64,
+    /// 99th percentile latency of a single query.
+    pub latency_p99: f64,
+    /// Slowest single query.
+    pub latency_max: f64,
+}
+
+impl ProfileReport {
+    /// Report as a JSON object, with the same field names as this struct.
+    pub fn to_json_string(&self) -> String {
+        format!(
+            "{{\"queries\":{},\"k\":{},\"nodes_visited\":{},\"leaves_scanned\":{},\"points_evaluated\":{},\
+             \"latency_p50\":{},\"latency_p90\":{},\"latency_p99\":{},\"latency_max\":{}}}",
+            self.queries,
+            self.k,
+            self.nodes_visited,
+            self.leaves_scanned,
+            self.points_evaluated,
+            json_number(self.latency_p50),
+            json_number(self.latency_p90),
+            json_number(self.latency_p99),
+            json_number(self.latency_max),
+        )
+    }
+
+    fn per_query(&self, total: usize) -> f64 {
+        if self.queries == 0 {
+            0.0
+        } else {
+            total as f64 / self.queries as f64
+        }
+    }
+}
+
+/// JSON has no representation for infinities and NaN.
+fn json_number(value: f64) -> String {
+    if value.is_finite() {
+        value.to_string()
+    } else {
+        "null".to_string()
+    }
+}
+
+/// Human readable report, one statistic per line.
+impl fmt::Display for ProfileReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "queries: {} (k = {})", self.queries, self.k)?;
+        writeln!(
+            f,
+            "nodes visited: {} ({:.1} per query)",
+            self.nodes_visited,
+            self.per_query(self.nodes_visited)
+        )?;
+        writeln!(
+            f,
+            "leaves scanned: {} ({:.1} per query)",
+            self.leaves_scanned,
+            self.per_query(self.leaves_scanned)
+        )?;
+        writeln!(
+            f,
+            "points evaluated: {} ({:.1} per query)",
+            self.points_evaluated,
+            self.per_query(self.points_evaluated)
+        )?;
+        write!(
+            f,
+            "latency: p50 {} p90 {} p99 {} max {}",
+            self.latency_p50, self.latency_p90, self.latency_p99, self.latency_max
+        )
+    }
+}
+
+/// The value at the `percent`-th percentile of `sorted`, by nearest rank.
+fn percentile(sorted: &[f64], percent: usize) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = (sorted.len() * percent + 99) / 100;
+    sorted[rank.max(1) - 1]
+}
+
+impl<A: Float, T: PartialEq, U: AsRef<[A]> + PartialEq> KdTree<A, T, U> {
+    /// Run [`nearest`](Self::nearest) for each of `queries`, collecting how much of the tree
+    /// every query had to look at and how long it took.
+    ///
+    /// `timer` is called right before and right after each query and should return a timestamp,
+    /// for example the nanoseconds elapsed since some fixed `Instant`. Passing your own timer keeps
+    /// this usable where `std::time` is not, and lets you pick the resolution. An invalid query is
+    /// reported by its index with [`ErrorKind::WrongDimensionAt`] or
+    /// [`ErrorKind::NonFiniteCoordinateAt`] before any query runs.
+    pub fn profile_queries<Q, F, C>(
+        &self,
+        queries: &[Q],
+        k: usize,
+        distance: &F,
+        mut timer: C,
+    ) -> Result<ProfileReport, ErrorKind>
+    where
+        Q: AsRef<[A]>,
+        F: Fn(&[A], &[A]) -> A,
+        C: FnMut() -> f64,
+    {
+        self.check_each(queries.iter().map(AsRef::as_ref))?;
+        let mut report = ProfileReport {
+            queries: queries.len(),
+            k,
+            ..ProfileReport::default()
+        };
+        let mut latencies = Vec::with_capacity(queries.len());
+        for query in queries {
+            let start = timer();
+            // the very search `nearest` runs, counting its work
+            self.nearest_counted(query.as_ref(), k, A::one(), usize::MAX, distance, |nodes, points| {
+                report.nodes_visited += nodes;
+                report.leaves_scanned += 1;
+                report.points_evaluated += points;
+            });
+            latencies.push(timer() - start);
+        }
+        latencies.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        report.latency_p50 = percentile(&latencies, 50);
+        report.latency_p90 = percentile(&latencies, 90);
+        report.latency_p99 = percentile(&latencies, 99);
+        report.latency_max = latencies.last().copied().unwrap_or(0.0);
+        Ok(report)
+    }
+}
@@ -0,0 +1,256 @@
+//! A read-only tree storing coordinates quantized to 16 bits per leaf.
+//!
+//! Each leaf keeps the bounding box of its points and stores every coordinate as one of 65536
+//! equally sized cells along that box's extent, like many point cloud codecs do. That is two
+//! bytes per coordinate instead of eight for `f64`, at the cost of knowing each point only up to
+//! its cell. Queries stay exact where it matters: cells give conservative lower bounds for
+//! pruning, and [`QuantizedKdTree::nearest_refined`] computes exact distances for the final
+//! candidates only, from coordinates the caller keeps elsewhere (a memory mapped file, a
+//! database, ...).
+
+use std::collections::BinaryHeap;
+
+use num_traits::Float;
+
+use crate::heap_element::HeapElement;
+use crate::kdtree::{ErrorKind, KdTree};
+use crate::util;
+
+const CELLS: u32 = 1 << 16;
+
+#[derive(Clone, Debug)]
+enum Node<A, T> {
+    Stem {
+        left: Box<Node<A, T>>,
+        right: Box<Node<A, T>>,
+        min_bounds: Box<[A]>,
+        max_bounds: Box<[A]>,
+    },
+    Leaf {
+        min_bounds: Box<[A]>,
+        max_bounds: Box<[A]>,
+        /// The cells of all coordinates of all points, point by point.
+        codes: Vec<u16>,
+        bucket: Vec<T>,
+    },
+}
+
+impl<A: Float, T> Node<A, T> {
+    fn bounds(&self) -> (&[A], &[A]) {
+        match self {
+            Node::Stem {
+                min_bounds, max_bounds, ..
+            }
+            | Node::Leaf {
+                min_bounds, max_bounds, ..
+            } => (min_bounds, max_bounds),
+        }
+    }
+}
+
+/// The lowest and highest coordinate of cell `code` along `min..=max`.
+fn cell<A: Float>(min: A, max: A, code: u16) -> (A, A) {
+    let step = (max - min) / A::from(CELLS).unwrap();
+    let lo = if code == 0 {
+        min
+    } else {
+        min + A::from(code).unwrap() * step
+    };
+    let hi = if u32::from(code) == CELLS - 1 {
+        max
+    } else {
+        min + A::from(u32::from(code) + 1).unwrap() * step
+    };
+    (lo, hi)
+}
+
+/// The cell along `min..=max` containing `x`, checked against the cell bounds as computed by
+/// [`cell`] so rounding never puts a coordinate outside its cell.
+fn quantize<A: Float>(min: A, max: A, x: A) -> u16 {
+    if max <= min {
+        return 0;
+    }
+    let scaled = ((x - min) / (max - min) * A::from(CELLS).unwrap()).floor();
+    let mut code = scaled.max(A::zero()).min(A::from(CELLS - 1).unwrap()).to_u16().unwrap();
+    while code > 0 && cell(min, max, code).0 > x {
+        code -= 1;
+    }
+    while u32::from(code) < CELLS - 1 && cell(min, max, code).1 < x {
+        code += 1;
+    }
+    code
+}
+
+enum Entry<'a, A, T> {
+    Node(&'a Node<A, T>),
+    Candidate(&'a T),
+    Exact(&'a T),
+}
+
+/// A read-only tree storing coordinates as 16 bit cells of their leaf's bounding box.
+///
+/// Built from a regular [`KdTree`], keeping its structure and payloads but dropping the exact
+/// coordinates.
+#[derive(Clone, Debug)]
+pub struct QuantizedKdTree<A, T> {
+    root: Node<A, T>,
+    dimensions: usize,
+    size: usize,
+}
+
+impl<A: Float, T: PartialEq> QuantizedKdTree<A, T> {
+    /// Quantize all points of `tree`.
+    pub fn from_tree<U: AsRef<[A]> + PartialEq>(tree: KdTree<A, T, U>) -> Self {
+        QuantizedKdTree {
+            dimensions: tree.dimensions,
+            size: tree.size,
+            root: Self::quantize_node(tree),
+        }
+    }
+
+    fn quantize_node<U: AsRef<[A]> + PartialEq>(node: KdTree<A, T, U>) -> Node<A, T> {
+        if node.is_leaf() {
+            let points = node.points.unwrap_or_default();
+            let mut codes = Vec::with_capacity(points.len() * node.dimensions);
+            for point in &points {
+                for (i, &x) in point.as_ref().iter().enumerate() {
+                    codes.push(quantize(node.min_bounds[i], node.max_bounds[i], x));
+                }
+            }
+            Node::Leaf {
+                min_bounds: node.min_bounds,
+                max_bounds: node.max_bounds,
+                codes,
+                bucket: node.bucket.unwrap_or_default(),
+            }
+        } else {
+            Node::Stem {
+                left: Box::new(Self::quantize_node(*node.left.unwrap())),
+                right: Box::new(Self::quantize_node(*node.right.unwrap())),
+                min_bounds: node.min_bounds,
+                max_bounds: node.max_bounds,
+            }
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    /// The `num` points whose cell centers are nearest to `point`, with the distance to their
+    /// cell center.
+    ///
+    /// This is approximate: a point's cell center is off from the point by at most half a cell,
+    /// a 65536th of its leaf's extent along each dimension.
+    pub fn nearest<F>(&self, point: &[A], num: usize, distance: &F) -> Result<Vec<(A, &T)>, ErrorKind>
+    where
+        F: Fn(&[A], &[A]) -> A,
+    {
+        self.search(point, num, distance, None::<&dyn Fn(&T) -> A>)
+    }
+
+    /// The exact `num` nearest points to `point`, with their exact distance.
+    ///
+    /// `exact` returns the exact coordinates of the point holding a payload. Cells bound the
+    /// distance of every point from below, so `exact` is only called for points whose bound
+    /// beats the `num`-th nearest distance found so far.
+    pub fn nearest_refined<F, E, V>(
+        &self,
+        point: &[A],
+        num: usize,
+        distance: &F,
+        exact: E,
+    ) -> Result<Vec<(A, &T)>, ErrorKind>
+    where
+        F: Fn(&[A], &[A]) -> A,
+        E: Fn(&T) -> V,
+        V: AsRef<[A]>,
+    {
+        let refine = |data: &T| distance(point, exact(data).as_ref());
+        self.search(point, num, distance, Some(&refine))
+    }
+
+    /// Best-first search over nodes, quantized candidates and, when refining, exact distances,
+    /// all keyed by a lower bound of what they can still yield, so entries come out nearest first.
+    fn search<F>(
+        &self,
+        point: &[A],
+        num: usize,
+        distance: &F,
+        refine: Option<&dyn Fn(&T) -> A>,
+    ) -> Result<Vec<(A, &T)>, ErrorKind>
+    where
+        F: Fn(&[A], &[A]) -> A,
+    {
+        if point.len() != self.dimensions {
+            return Err(ErrorKind::WrongDimension);
+        }
+        if point.iter().any(|c| !c.is_finite()) {
+            return Err(ErrorKind::NonFiniteCoordinate);
+        }
+        let mut found = vec![];
+        if self.size == 0 {
+            return Ok(found);
+        }
+        let mut pending = BinaryHeap::new();
+        pending.push(HeapElement {
+            distance: A::zero(),
+            element: Entry::Node(&self.root),
+        });
+        let half = A::from(0.5).unwrap();
+        while found.len() < num {
+            let (key, entry) = match pending.pop() {
+                Some(HeapElement { distance, element }) => (-distance, element),
+                None => break,
+            };
+            match entry {
+                Entry::Node(Node::Stem { left, right, .. }) => {
+                    for child in [left, right].iter() {
+                        let (min, max) = child.bounds();
+                        pending.push(HeapElement {
+                            distance: -util::distance_to_space(point, min, max, distance),
+                            element: Entry::Node(child),
+                        });
+                    }
+                }
+                Entry::Node(Node::Leaf {
+                    min_bounds,
+                    max_bounds,
+                    codes,
+                    bucket,
+                }) => {
+                    for (codes, data) in codes.chunks(self.dimensions).zip(bucket) {
+                        let cells = codes
+                            .iter()
+                            .enumerate()
+                            .map(|(i, &code)| cell(min_bounds[i], max_bounds[i], code));
+                        let key = if refine.is_some() {
+                            let (lo, hi): (Vec<A>, Vec<A>) = cells.unzip();
+                            util::distance_to_space(point, &lo, &hi, distance)
+                        } else {
+                            let center: Vec<A> = cells.map(|(lo, hi)| lo + (hi - lo) * half).collect();
+                            distance(point, &center)
+                        };
+                        pending.push(HeapElement {
+                            distance: -key,
+                            element: Entry::Candidate(data),
+                        });
+                    }
+                }
+                Entry::Candidate(data) => match refine {
+                    Some(refine) => pending.push(HeapElement {
+                        distance: -refine(data),
+                        element: Entry::Exact(data),
+                    }),
+                    None => found.push((key, data)),
+                },
+                Entry::Exact(data) => found.push((key, data)),
+            }
+        }
+        Ok(found)
+    }
+}
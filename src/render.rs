@@ -0,0 +1,709 @@
+//! Drawing 2D trees as figures: their points and the split lines partitioning the plane.
+//!
+//! Every stem splits its cell in two along its split value. The root's cell is the bounding box
+//! of all points, so the figures show the partitioning the way textbooks draw it.
+//!
+//! The structure of trees of any dimension size can be drawn as a Graphviz graph too, see
+//! [`KdTree::display_dot`].
+
+use std::fmt::{self, Write};
+
+use num_traits::Float;
+use thiserror::Error;
+
+use crate::kdtree::KdTree;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum RenderError {
+    #[error("only trees of dimension size 2 can be rendered, not {0}")]
+    WrongDimension(usize),
+    #[error("non-finite coordinate")]
+    NonFiniteCoordinate,
+}
+
+/// Options for [`KdTree::to_tikz_string`] and [`KdTree::display_tikz`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct TikzOptions {
+    /// TikZ units per coordinate unit.
+    pub scale: f64,
+    /// Radius of the dots drawn for points, in TikZ units.
+    pub point_radius: f64,
+    /// Shapes drawn on top of the tree.
+    pub overlays: Vec<RenderOverlay>,
+    /// Draw only the splits of stems at most this deep, the root being at depth 0.
+    pub max_depth: Option<usize>,
+    /// Draw only what lies within this rectangle, given by its minimum and maximum corner.
+    pub clip: Option<([f64; 2], [f64; 2])>,
+    /// Decimal places of the numbers written, trailing zeros removed. `None` writes them in
+    /// full. Numbers are never written in scientific notation.
+    pub precision: Option<usize>,
+    /// Label the lower and left edge of the bounding box with ticks. They are spaced 1, 2 or 5
+    /// times a power of ten apart, about five along the longer side.
+    pub ticks: bool,
+    /// Draw light grid lines at the tick spacing, below everything else.
+    pub grid: bool,
+    /// Draw a labelled bar about a fifth as long as the longer side below the picture.
+    pub scale_bar: bool,
+}
+
+impl Default for TikzOptions {
+    fn default() -> Self {
+        TikzOptions {
+            scale: 1.0,
+            point_radius: 0.05,
+            overlays: vec![],
+            max_depth: None,
+            clip: None,
+            precision: None,
+            ticks: false,
+            grid: false,
+            scale_bar: false,
+        }
+    }
+}
+
+/// Options for [`KdTree::to_svg_string`] and [`KdTree::display_svg`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct SvgOptions {
+    /// Width of the image in pixels.
+    pub width: f64,
+    /// Height of the image in pixels.
+    pub height: f64,
+    /// Space left around the bounding box, in pixels.
+    pub margin: f64,
+    /// Radius of the dots drawn for points, in pixels.
+    pub point_radius: f64,
+    /// Shapes drawn on top of the tree. The image is fit to show them entirely.
+    pub overlays: Vec<RenderOverlay>,
+    /// Draw only the splits of stems at most this deep, the root being at depth 0.
+    pub max_depth: Option<usize>,
+    /// Draw only what lies within this rectangle, given by its minimum and maximum corner. The
+    /// image is fit to the clipped tree instead of the whole one.
+    pub clip: Option<([f64; 2], [f64; 2])>,
+    /// Decimal places of the numbers written, trailing zeros removed. `None` writes them in
+    /// full. Numbers are never written in scientific notation.
+    pub precision: Option<usize>,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        SvgOptions {
+            width: 400.0,
+            height: 400.0,
+            margin: 10.0,
+            point_radius: 3.0,
+            overlays: vec![],
+            max_depth: None,
+            clip: None,
+            precision: None,
+        }
+    }
+}
+
+/// A shape drawn in blue on top of a rendered tree, typically a query region.
+///
+/// Coordinates and radii are in coordinate units, like the points of the tree.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RenderOverlay {
+    /// A circle, e.g. the region searched by `within`. The radius is a plain distance, so take
+    /// the square root of a radius used with `squared_euclidean`.
+    Circle { center: [f64; 2], radius: f64 },
+    /// An axis-aligned rectangle given by its minimum and maximum corner.
+    Rect { min: [f64; 2], max: [f64; 2] },
+    /// A single point, e.g. the query point.
+    Point([f64; 2]),
+}
+
+impl RenderOverlay {
+    pub fn circle(center: [f64; 2], radius: f64) -> Self {
+        RenderOverlay::Circle { center, radius }
+    }
+
+    pub fn rect(min: [f64; 2], max: [f64; 2]) -> Self {
+        RenderOverlay::Rect { min, max }
+    }
+
+    pub fn point(point: [f64; 2]) -> Self {
+        RenderOverlay::Point(point)
+    }
+
+    /// Minimum and maximum corner of the area covered.
+    fn extent(&self) -> ([f64; 2], [f64; 2]) {
+        match *self {
+            RenderOverlay::Circle { center, radius } => (
+                [center[0] - radius, center[1] - radius],
+                [center[0] + radius, center[1] + radius],
+            ),
+            RenderOverlay::Rect { min, max } => (min, max),
+            RenderOverlay::Point(point) => (point, point),
+        }
+    }
+}
+
+fn check_overlays(overlays: &[RenderOverlay]) -> Result<(), RenderError> {
+    overlays.iter().try_for_each(|overlay| check_rect(overlay.extent()))
+}
+
+fn check_rect((min, max): ([f64; 2], [f64; 2])) -> Result<(), RenderError> {
+    if min.iter().chain(&max).all(|x| x.is_finite()) {
+        Ok(())
+    } else {
+        Err(RenderError::NonFiniteCoordinate)
+    }
+}
+
+/// How a point is drawn, as returned by the style callbacks of the `_with` render methods.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PointStyle {
+    /// Color of the dot. Named colors like `red` or `blue` work for both TikZ (with `xcolor`) and
+    /// SVG.
+    pub color: String,
+    /// Text written next to the dot. Passed to TikZ as it is, so it may contain TeX; escaped for
+    /// SVG.
+    pub label: Option<String>,
+}
+
+impl Default for PointStyle {
+    fn default() -> Self {
+        PointStyle {
+            color: "black".to_string(),
+            label: None,
+        }
+    }
+}
+
+/// The shapes making up a figure, in coordinate units.
+struct Figure {
+    /// Minimum and maximum corner of the root's cell, absent for an empty tree.
+    bounds: Option<([f64; 2], [f64; 2])>,
+    splits: Vec<([f64; 2], [f64; 2])>,
+    points: Vec<([f64; 2], PointStyle)>,
+}
+
+impl<A: Float, T: PartialEq, U: AsRef<[A]> + PartialEq> KdTree<A, T, U> {
+    /// Render the tree as a TikZ picture, see the [`render`](crate::render) module.
+    pub fn to_tikz_string(&self, options: &TikzOptions) -> Result<String, RenderError> {
+        self.to_tikz_string_with(options, |_| PointStyle::default())
+    }
+
+    /// Like [`to_tikz_string`](Self::to_tikz_string), drawing each point as `style` returns for
+    /// its data.
+    pub fn to_tikz_string_with<S>(&self, options: &TikzOptions, style: S) -> Result<String, RenderError>
+    where
+        S: Fn(&T) -> PointStyle,
+    {
+        check_overlays(&options.overlays)?;
+        let figure = self.figure(style, options.max_depth, options.clip)?;
+        let mut out = String::new();
+        write_tikz(&mut out, &figure, options).unwrap();
+        Ok(out)
+    }
+
+    /// Render the tree as an SVG image, see the [`render`](crate::render) module.
+    pub fn to_svg_string(&self, options: &SvgOptions) -> Result<String, RenderError> {
+        self.to_svg_string_with(options, |_| PointStyle::default())
+    }
+
+    /// Like [`to_svg_string`](Self::to_svg_string), drawing each point as `style` returns for its
+    /// data.
+    pub fn to_svg_string_with<S>(&self, options: &SvgOptions, style: S) -> Result<String, RenderError>
+    where
+        S: Fn(&T) -> PointStyle,
+    {
+        check_overlays(&options.overlays)?;
+        let figure = self.figure(style, options.max_depth, options.clip)?;
+        let mut out = String::new();
+        write_svg(&mut out, &figure, options).unwrap();
+        Ok(out)
+    }
+
+    /// A wrapper rendering the tree as a TikZ picture when formatted, without building the
+    /// whole string first.
+    ///
+    /// Formatting fails with [`fmt::Error`] where [`to_tikz_string`](Self::to_tikz_string)
+    /// returns a [`RenderError`], so `to_string()` on it panics for a tree that cannot be
+    /// rendered.
+    pub fn display_tikz(&self, options: TikzOptions) -> KdTreeDisplayTikz<'_, A, T, U> {
+        KdTreeDisplayTikz { tree: self, options }
+    }
+
+    /// A wrapper rendering the tree as an SVG image when formatted, like
+    /// [`display_tikz`](Self::display_tikz) does for TikZ.
+    pub fn display_svg(&self, options: SvgOptions) -> KdTreeDisplaySvg<'_, A, T, U> {
+        KdTreeDisplaySvg { tree: self, options }
+    }
+
+    /// A wrapper writing the nodes of the tree as a Graphviz DOT graph when formatted, for
+    /// looking into its balance.
+    ///
+    /// Stems show their split dimension and value, with edges to their left and right child,
+    /// and leaves the points and data stored in them. Nodes are named by their IDs, see
+    /// [`generation`](Self::generation).
+    pub fn display_dot(&self) -> KdTreeDisplayDot<'_, A, T, U>
+    where
+        T: fmt::Debug,
+    {
+        KdTreeDisplayDot { tree: self }
+    }
+
+    fn figure<S: Fn(&T) -> PointStyle>(
+        &self,
+        style: S,
+        max_depth: Option<usize>,
+        clip: Option<([f64; 2], [f64; 2])>,
+    ) -> Result<Figure, RenderError> {
+        let mut figure = Figure {
+            bounds: None,
+            splits: vec![],
+            points: vec![],
+        };
+        if self.size == 0 {
+            return Ok(figure);
+        }
+        if self.dimensions != 2 {
+            return Err(RenderError::WrongDimension(self.dimensions));
+        }
+        if let Some(clip) = clip {
+            check_rect(clip)?;
+        }
+        // the part of a cell that is drawn, none if it lies entirely outside of the clip
+        let visible = |min: [f64; 2], max: [f64; 2]| match clip {
+            Some((clip_min, clip_max)) => {
+                let min = [min[0].max(clip_min[0]), min[1].max(clip_min[1])];
+                let max = [max[0].min(clip_max[0]), max[1].min(clip_max[1])];
+                if min[0] <= max[0] && min[1] <= max[1] {
+                    Some((min, max))
+                } else {
+                    None
+                }
+            }
+            None => Some((min, max)),
+        };
+        let min = [coordinate(self.min_bounds[0])?, coordinate(self.min_bounds[1])?];
+        let max = [coordinate(self.max_bounds[0])?, coordinate(self.max_bounds[1])?];
+        figure.bounds = visible(min, max);
+        let mut pending = vec![(self, min, max, 0)];
+        while let Some((node, min, max, depth)) = pending.pop() {
+            let (shown_min, shown_max) = match visible(min, max) {
+                Some(shown) => shown,
+                None => continue,
+            };
+            if node.is_leaf() {
+                let points = node.points.as_ref().unwrap().iter();
+                for (point, data) in points.zip(node.bucket.as_ref().unwrap()) {
+                    let point = point.as_ref();
+                    let point = [coordinate(point[0])?, coordinate(point[1])?];
+                    if visible(point, point).is_some() {
+                        figure.points.push((point, style(data)));
+                    }
+                }
+                continue;
+            }
+            let dim = node.split_dimension.unwrap();
+            let value = coordinate(node.split_value.unwrap())?;
+            if max_depth.map_or(true, |max_depth| depth <= max_depth)
+                && shown_min[dim] <= value
+                && value <= shown_max[dim]
+            {
+                let (mut from, mut to) = (shown_min, shown_max);
+                from[dim] = value;
+                to[dim] = value;
+                figure.splits.push((from, to));
+            }
+            let (mut left_max, mut right_min) = (max, min);
+            left_max[dim] = value;
+            right_min[dim] = value;
+            pending.extend(node.right.as_deref().map(|right| (right, right_min, max, depth + 1)));
+            pending.extend(node.left.as_deref().map(|left| (left, min, left_max, depth + 1)));
+        }
+        Ok(figure)
+    }
+}
+
+fn coordinate<A: Float>(x: A) -> Result<f64, RenderError> {
+    x.to_f64()
+        .filter(|x| x.is_finite())
+        .ok_or(RenderError::NonFiniteCoordinate)
+}
+
+fn write_tikz<W: Write>(out: &mut W, figure: &Figure, options: &TikzOptions) -> fmt::Result {
+    let n = |v: f64| Number(v, options.precision);
+    writeln!(out, "\\begin{{tikzpicture}}[scale={}]", n(options.scale))?;
+    let spacing = figure
+        .bounds
+        .map(|(min, max)| nice_step((max[0] - min[0]).max(max[1] - min[1]) / 5.0));
+    if let (true, Some((min, max)), Some(step)) = (options.grid, figure.bounds, spacing) {
+        for x in multiples(step, min[0], max[0]) {
+            writeln!(
+                out,
+                "  \\draw[very thin, gray!30] ({},{}) -- ({},{});",
+                n(x),
+                n(min[1]),
+                n(x),
+                n(max[1])
+            )?;
+        }
+        for y in multiples(step, min[1], max[1]) {
+            writeln!(
+                out,
+                "  \\draw[very thin, gray!30] ({},{}) -- ({},{});",
+                n(min[0]),
+                n(y),
+                n(max[0]),
+                n(y)
+            )?;
+        }
+    }
+    if let Some((min, max)) = figure.bounds {
+        writeln!(
+            out,
+            "  \\draw[gray] ({},{}) rectangle ({},{});",
+            n(min[0]),
+            n(min[1]),
+            n(max[0]),
+            n(max[1])
+        )?;
+    }
+    for (from, to) in &figure.splits {
+        writeln!(
+            out,
+            "  \\draw ({},{}) -- ({},{});",
+            n(from[0]),
+            n(from[1]),
+            n(to[0]),
+            n(to[1])
+        )?;
+    }
+    for (point, style) in &figure.points {
+        if style.color == "black" {
+            writeln!(
+                out,
+                "  \\fill ({},{}) circle ({});",
+                n(point[0]),
+                n(point[1]),
+                n(options.point_radius)
+            )?;
+        } else {
+            writeln!(
+                out,
+                "  \\fill[{}] ({},{}) circle ({});",
+                style.color,
+                n(point[0]),
+                n(point[1]),
+                n(options.point_radius)
+            )?;
+        }
+        if let Some(label) = &style.label {
+            writeln!(
+                out,
+                "  \\node[above right] at ({},{}) {{{}}};",
+                n(point[0]),
+                n(point[1]),
+                label
+            )?;
+        }
+    }
+    for overlay in &options.overlays {
+        match overlay {
+            RenderOverlay::Circle { center, radius } => writeln!(
+                out,
+                "  \\draw[blue] ({},{}) circle ({});",
+                n(center[0]),
+                n(center[1]),
+                n(*radius)
+            )?,
+            RenderOverlay::Rect { min, max } => writeln!(
+                out,
+                "  \\draw[blue] ({},{}) rectangle ({},{});",
+                n(min[0]),
+                n(min[1]),
+                n(max[0]),
+                n(max[1])
+            )?,
+            RenderOverlay::Point(point) => writeln!(
+                out,
+                "  \\fill[blue] ({},{}) circle ({});",
+                n(point[0]),
+                n(point[1]),
+                n(options.point_radius)
+            )?,
+        }
+    }
+    if let (true, Some((min, max)), Some(step)) = (options.ticks, figure.bounds, spacing) {
+        // labels get just the decimal places the spacing needs, unless asked for a precision
+        let label = |v: f64| Number(v, options.precision.or(Some(decimals(step))));
+        for x in multiples(step, min[0], max[0]) {
+            writeln!(
+                out,
+                "  \\draw ({},{}) -- ++(0,-2pt) node[below] {{{}}};",
+                n(x),
+                n(min[1]),
+                label(x)
+            )?;
+        }
+        for y in multiples(step, min[1], max[1]) {
+            writeln!(
+                out,
+                "  \\draw ({},{}) -- ++(-2pt,0) node[left] {{{}}};",
+                n(min[0]),
+                n(y),
+                label(y)
+            )?;
+        }
+    }
+    if let (true, Some((min, _)), Some(step)) = (options.scale_bar, figure.bounds, spacing) {
+        let length = Number(step, options.precision.or(Some(decimals(step))));
+        writeln!(
+            out,
+            "  \\draw[|-|] ([yshift=-8mm]{},{}) -- ++({},0) node[midway, below] {{{}}};",
+            n(min[0]),
+            n(min[1]),
+            length,
+            length
+        )?;
+    }
+    writeln!(out, "\\end{{tikzpicture}}")
+}
+
+fn write_svg<W: Write>(out: &mut W, figure: &Figure, options: &SvgOptions) -> fmt::Result {
+    let n = |v: f64| Number(v, options.precision);
+    writeln!(
+        out,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">",
+        w = n(options.width),
+        h = n(options.height)
+    )?;
+    let view = options
+        .overlays
+        .iter()
+        .map(RenderOverlay::extent)
+        .chain(figure.bounds)
+        .reduce(|(a_min, a_max), (b_min, b_max)| {
+            (
+                [a_min[0].min(b_min[0]), a_min[1].min(b_min[1])],
+                [a_max[0].max(b_max[0]), a_max[1].max(b_max[1])],
+            )
+        });
+    if let Some((min, max)) = view {
+        // one scale for both axes keeps the aspect ratio, y grows upwards like in the plane
+        let span = |i: usize| if max[i] > min[i] { max[i] - min[i] } else { 1.0 };
+        let scale =
+            ((options.width - 2.0 * options.margin) / span(0)).min((options.height - 2.0 * options.margin) / span(1));
+        let x = |v: f64| options.margin + (v - min[0]) * scale;
+        let y = |v: f64| options.height - options.margin - (v - min[1]) * scale;
+        if let Some((min, max)) = figure.bounds {
+            writeln!(
+                out,
+                "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"none\" stroke=\"gray\"/>",
+                n(x(min[0])),
+                n(y(max[1])),
+                n(x(max[0]) - x(min[0])),
+                n(y(min[1]) - y(max[1]))
+            )?;
+        }
+        for (from, to) in &figure.splits {
+            writeln!(
+                out,
+                "  <line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"black\"/>",
+                n(x(from[0])),
+                n(y(from[1])),
+                n(x(to[0])),
+                n(y(to[1]))
+            )?;
+        }
+        for (point, style) in &figure.points {
+            writeln!(
+                out,
+                "  <circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"{}\"/>",
+                n(x(point[0])),
+                n(y(point[1])),
+                n(options.point_radius),
+                escape_xml(&style.color)
+            )?;
+            if let Some(label) = &style.label {
+                writeln!(
+                    out,
+                    "  <text x=\"{}\" y=\"{}\" font-size=\"{}\">{}</text>",
+                    n(x(point[0]) + options.point_radius),
+                    n(y(point[1]) - options.point_radius),
+                    n(4.0 * options.point_radius),
+                    escape_xml(label)
+                )?;
+            }
+        }
+        for overlay in &options.overlays {
+            match overlay {
+                RenderOverlay::Circle { center, radius } => writeln!(
+                    out,
+                    "  <circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"none\" stroke=\"blue\"/>",
+                    n(x(center[0])),
+                    n(y(center[1])),
+                    n(radius * scale)
+                )?,
+                RenderOverlay::Rect { min, max } => writeln!(
+                    out,
+                    "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"none\" stroke=\"blue\"/>",
+                    n(x(min[0])),
+                    n(y(max[1])),
+                    n(x(max[0]) - x(min[0])),
+                    n(y(min[1]) - y(max[1]))
+                )?,
+                RenderOverlay::Point(point) => writeln!(
+                    out,
+                    "  <circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"blue\"/>",
+                    n(x(point[0])),
+                    n(y(point[1])),
+                    n(options.point_radius)
+                )?,
+            }
+        }
+    }
+    writeln!(out, "</svg>")
+}
+
+/// A number formatted with a fixed number of decimal places, trailing zeros removed, or in full
+/// when no precision is given. Never in scientific notation, which TikZ cannot parse.
+struct Number(f64, Option<usize>);
+
+impl fmt::Display for Number {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.1 {
+            Some(precision) => {
+                let fixed = format!("{:.*}", precision, self.0);
+                let fixed = if fixed.contains('.') {
+                    fixed.trim_end_matches('0').trim_end_matches('.')
+                } else {
+                    &fixed
+                };
+                // rounding small negative numbers leaves a "-0" behind
+                f.write_str(if fixed == "-0" { "0" } else { fixed })
+            }
+            None => write!(f, "{}", self.0),
+        }
+    }
+}
+
+/// The smallest of 1, 2 and 5 times a power of ten that is at least `rough`.
+fn nice_step(rough: f64) -> f64 {
+    if rough <= 0.0 {
+        return 1.0;
+    }
+    let power = 10f64.powf(rough.log10().floor());
+    [1.0, 2.0, 5.0, 10.0]
+        .iter()
+        .map(|factor| factor * power)
+        .find(|&step| step >= rough)
+        .unwrap_or(10.0 * power)
+}
+
+/// The multiples of a step found by [`nice_step`] from `min` to `max`, both included.
+fn multiples(step: f64, min: f64, max: f64) -> impl Iterator<Item = f64> {
+    let (first, last) = ((min / step).ceil() as i64, (max / step).floor() as i64);
+    // dividing by a power of ten gives 0.3 for the third multiple of 0.1, multiplying doesn't
+    let power = 10f64.powi(decimals(step) as i32);
+    let integral = (step * power).round();
+    (first..=last).map(move |i| i as f64 * integral / power)
+}
+
+/// Decimal places needed to write multiples of a step found by [`nice_step`].
+fn decimals(step: f64) -> usize {
+    (-step.log10().floor()).max(0.0) as usize
+}
+
+/// Escape `text` for a double-quoted DOT string, ending lines left-aligned.
+fn escape_dot(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\l") + "\\l"
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Renders a tree as a TikZ picture when formatted, see [`KdTree::display_tikz`].
+pub struct KdTreeDisplayTikz<'a, A, T: PartialEq, U: AsRef<[A]> + PartialEq> {
+    tree: &'a KdTree<A, T, U>,
+    options: TikzOptions,
+}
+
+impl<'a, A: Float, T: PartialEq, U: AsRef<[A]> + PartialEq> fmt::Display for KdTreeDisplayTikz<'a, A, T, U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        check_overlays(&self.options.overlays).map_err(|_| fmt::Error)?;
+        let figure = self
+            .tree
+            .figure(|_| PointStyle::default(), self.options.max_depth, self.options.clip)
+            .map_err(|_| fmt::Error)?;
+        write_tikz(f, &figure, &self.options)
+    }
+}
+
+/// Renders a tree as an SVG image when formatted, see [`KdTree::display_svg`].
+pub struct KdTreeDisplaySvg<'a, A, T: PartialEq, U: AsRef<[A]> + PartialEq> {
+    tree: &'a KdTree<A, T, U>,
+    options: SvgOptions,
+}
+
+impl<'a, A: Float, T: PartialEq, U: AsRef<[A]> + PartialEq> fmt::Display for KdTreeDisplaySvg<'a, A, T, U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        check_overlays(&self.options.overlays).map_err(|_| fmt::Error)?;
+        let figure = self
+            .tree
+            .figure(|_| PointStyle::default(), self.options.max_depth, self.options.clip)
+            .map_err(|_| fmt::Error)?;
+        write_svg(f, &figure, &self.options)
+    }
+}
+
+/// Writes the nodes of a tree as a Graphviz DOT graph when formatted, see
+/// [`KdTree::display_dot`].
+pub struct KdTreeDisplayDot<'a, A, T: PartialEq, U: AsRef<[A]> + PartialEq> {
+    tree: &'a KdTree<A, T, U>,
+}
+
+impl<'a, A: Float, T: PartialEq + fmt::Debug, U: AsRef<[A]> + PartialEq> fmt::Display
+    for KdTreeDisplayDot<'a, A, T, U>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let n = |v: A| Number(v.to_f64().unwrap_or(f64::NAN), None);
+        writeln!(f, "digraph kdtree {{")?;
+        writeln!(f, "  node [shape=box, fontname=monospace];")?;
+        let mut pending = vec![self.tree];
+        while let Some(node) = pending.pop() {
+            let mut label = String::new();
+            if node.is_leaf() {
+                write!(label, "leaf {}, size {}", node.id, node.size)?;
+                let points = node.points.as_ref().unwrap().iter();
+                for (point, data) in points.zip(node.bucket.as_ref().unwrap()) {
+                    label.push_str("\n(");
+                    for (i, &x) in point.as_ref().iter().enumerate() {
+                        if i > 0 {
+                            label.push_str(", ");
+                        }
+                        write!(label, "{}", n(x))?;
+                    }
+                    write!(label, ") {:?}", data)?;
+                }
+            } else {
+                write!(
+                    label,
+                    "stem {}, size {}\nsplit dimension {} at {}",
+                    node.id,
+                    node.size,
+                    node.split_dimension.unwrap(),
+                    n(node.split_value.unwrap())
+                )?;
+            }
+            writeln!(f, "  n{} [label=\"{}\"];", node.id, escape_dot(&label))?;
+            for (side, child) in [("left", &node.left), ("right", &node.right)].iter() {
+                if let Some(child) = child.as_deref() {
+                    writeln!(f, "  n{} -> n{} [label={}];", node.id, child.id, side)?;
+                }
+            }
+            pending.extend(node.right.as_deref());
+            pending.extend(node.left.as_deref());
+        }
+        writeln!(f, "}}")
+    }
+}
@@ -0,0 +1,46 @@
+//! Two-stage nearest neighbour searches: gathering candidates with a cheap distance and ranking
+//! them with an expensive one.
+
+use num_traits::Float;
+
+use crate::kdtree::{ErrorKind, KdTree};
+
+impl<A: Float, T: PartialEq, U: AsRef<[A]> + PartialEq> KdTree<A, T, U> {
+    /// The `num` points nearest to `point` by `rerank_with`, among the `candidates` nearest by
+    /// `distance`, nearest first, with their distance by `rerank_with`.
+    ///
+    /// The tree is only searched with `distance`, which has to meet the requirements of
+    /// [`nearest`](Self::nearest), so `rerank_with` can be anything, like a learned or
+    /// non-metric similarity, and is called `candidates` times only. The results are exact when
+    /// every one of the true `num` nearest by `rerank_with` is among the candidates, which holds
+    /// for any number of candidates if both distances order points the same way. Otherwise more
+    /// candidates trade speed for recall. Fewer than `num` candidates count as `num`, and
+    /// candidates tied by `rerank_with` stay in their order by `distance`.
+    pub fn nearest_reranked<F, R>(
+        &self,
+        point: &[A],
+        num: usize,
+        candidates: usize,
+        distance: &F,
+        rerank_with: &R,
+    ) -> Result<Vec<(A, &T)>, ErrorKind>
+    where
+        F: Fn(&[A], &[A]) -> A,
+        R: Fn(&[A], &[A]) -> A,
+    {
+        self.check_point(point)?;
+        let candidates = std::cmp::max(candidates, num);
+        let mut found: Vec<(A, &T)> = self
+            .nearest_indexed(point, candidates, A::infinity(), distance, |_| true)
+            .into_iter()
+            .map(|(_, index, data)| {
+                let (stored, _) = self.get_by_index(index).unwrap();
+                (rerank_with(point, stored.as_ref()), data)
+            })
+            .collect();
+        // stable, so ties keep the order of `distance`
+        found.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+        found.truncate(num);
+        Ok(found)
+    }
+}
@@ -0,0 +1,87 @@
+//! Reverse nearest neighbour queries.
+
+use num_traits::Float;
+
+use crate::kdtree::{ErrorKind, KdTree};
+use crate::util::distance_to_space;
+
+impl<A: Float, T: PartialEq, U: AsRef<[A]> + PartialEq> KdTree<A, T, U> {
+    /// All stored points that would have `point` among their `num` nearest neighbours, nearest
+    /// to `point` first.
+    ///
+    /// A stored point qualifies if fewer than `num` other stored points are strictly closer to it
+    /// than `point`. The distance has to be symmetric and meet the requirements of
+    /// [`nearest`](Self::nearest).
+    ///
+    /// A subtree holding more than `num` points gives each of them at least `num` others no
+    /// farther than the distance between the corners of its bounds, so subtrees of it farther
+    /// than that from `point` are skipped without looking at their points. The points left are
+    /// checked by counting their closer points, stopping as soon as `num` are found and skipping
+    /// every subtree whose bounds are not closer than `point`.
+    pub fn reverse_nearest<F>(&self, point: &[A], num: usize, distance: &F) -> Result<Vec<(A, &T)>, ErrorKind>
+    where
+        F: Fn(&[A], &[A]) -> A,
+    {
+        self.check_point(point)?;
+        if num == 0 {
+            return Ok(vec![]);
+        }
+        let mut found: Vec<(A, &T)> = vec![];
+        // the smallest diameter of a node holding more than `num` points, this one or above
+        let mut pending = vec![(self, A::infinity())];
+        while let Some((node, witness)) = pending.pop() {
+            if node.size == 0 {
+                continue;
+            }
+            let witness = if node.size > num {
+                witness.min(distance(&node.min_bounds, &node.max_bounds))
+            } else {
+                witness
+            };
+            if witness < distance_to_space(point, &node.min_bounds, &node.max_bounds, distance) {
+                continue;
+            }
+            if !node.is_leaf() {
+                pending.extend(node.right.as_deref().map(|right| (right, witness)));
+                pending.extend(node.left.as_deref().map(|left| (left, witness)));
+                continue;
+            }
+            let points = node.points.as_ref().unwrap();
+            for (p, data) in points.iter().zip(node.bucket.as_ref().unwrap()) {
+                let radius = distance(p.as_ref(), point);
+                // the stored point itself is among the closer ones, unless it is at `point`
+                let limit = if radius > A::zero() { num + 1 } else { num };
+                if self.count_closer(p.as_ref(), radius, limit, distance) < limit {
+                    found.push((radius, data));
+                }
+            }
+        }
+        found.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+        Ok(found)
+    }
+
+    /// Number of points strictly closer than `radius` to `point`, counting at most up to `limit`.
+    fn count_closer<F>(&self, point: &[A], radius: A, limit: usize, distance: &F) -> usize
+    where
+        F: Fn(&[A], &[A]) -> A,
+    {
+        let mut count = 0;
+        let mut pending = vec![self];
+        while let Some(node) = pending.pop() {
+            if node.size == 0 || distance_to_space(point, &node.min_bounds, &node.max_bounds, distance) >= radius {
+                continue;
+            }
+            if node.is_leaf() {
+                let points = node.points.as_ref().unwrap();
+                count += points.iter().filter(|p| distance(point, p.as_ref()) < radius).count();
+                if count >= limit {
+                    return count;
+                }
+            } else {
+                pending.extend(node.left.as_deref());
+                pending.extend(node.right.as_deref());
+            }
+        }
+        count
+    }
+}
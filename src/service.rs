@@ -0,0 +1,273 @@
+//! A tree behind a pool of worker threads, answering queries as futures.
+//!
+//! Services embedding a tree usually want two things from it: queries that do not block the
+//! async runtime, and a way to swap in a rebuilt tree without stopping traffic. [`KdTreeService`]
+//! owns the current tree as an `Arc` snapshot and queues incoming queries. Each worker takes all
+//! queued queries at once, up to a batch size, and answers them against the same snapshot.
+//! Queries already running keep their snapshot alive when a new one is swapped in. A query
+//! panicking, in the distance function for example, resolves to [`ErrorKind::QueryPanicked`]
+//! and leaves its worker running.
+//!
+//! The futures are plain [`Future`] implementations and work with any executor.
+
+use std::collections::VecDeque;
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+use std::sync::{Arc, Condvar, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::thread::{self, JoinHandle};
+
+#[cfg(loom)]
+use loom::sync::RwLock;
+use num_traits::Float;
+#[cfg(not(loom))]
+use std::sync::RwLock;
+
+use crate::kdtree::{ErrorKind, KdTree};
+use crate::owned::OwnedNeighbor;
+
+type Answer<A, T, U> = Result<Vec<OwnedNeighbor<A, T, U>>, ErrorKind>;
+
+/// The value currently published, replaced as a whole while readers keep the one they loaded.
+struct Snapshot<V> {
+    current: RwLock<Arc<V>>,
+}
+
+impl<V> Snapshot<V> {
+    fn new(value: V) -> Self {
+        Snapshot {
+            current: RwLock::new(Arc::new(value)),
+        }
+    }
+
+    fn load(&self) -> Arc<V> {
+        Arc::clone(&self.current.read().unwrap())
+    }
+
+    fn swap(&self, value: V) -> Arc<V> {
+        std::mem::replace(&mut *self.current.write().unwrap(), Arc::new(value))
+    }
+}
+
+struct Slot<R> {
+    result: Option<R>,
+    waker: Option<Waker>,
+}
+
+/// The result of a query to a [`KdTreeService`], ready once a worker got to it.
+pub struct QueryFuture<A: PartialEq, T: PartialEq, U: AsRef<[A]> + PartialEq> {
+    slot: Arc<Mutex<Slot<Answer<A, T, U>>>>,
+}
+
+impl<A: PartialEq, T: PartialEq, U: AsRef<[A]> + PartialEq> Future for QueryFuture<A, T, U> {
+    type Output = Answer<A, T, U>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut slot = self.slot.lock().unwrap();
+        match slot.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                slot.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+enum Query<A> {
+    Nearest(usize),
+    Within(A),
+}
+
+struct Request<A: PartialEq, T: PartialEq, U: AsRef<[A]> + PartialEq> {
+    point: Vec<A>,
+    query: Query<A>,
+    slot: Arc<Mutex<Slot<Answer<A, T, U>>>>,
+}
+
+struct Queue<A: PartialEq, T: PartialEq, U: AsRef<[A]> + PartialEq> {
+    requests: VecDeque<Request<A, T, U>>,
+    shutdown: bool,
+}
+
+struct Shared<A: PartialEq, T: PartialEq, U: AsRef<[A]> + PartialEq, F> {
+    tree: Snapshot<KdTree<A, T, U>>,
+    queue: Mutex<Queue<A, T, U>>,
+    available: Condvar,
+    distance: F,
+    batch_size: usize,
+}
+
+/// A tree answering queries on a pool of worker threads, with snapshot swapping.
+pub struct KdTreeService<A, T, U, F>
+where
+    A: Float + Send + Sync + 'static,
+    T: PartialEq + Send + Sync + 'static,
+    U: AsRef<[A]> + PartialEq + Send + Sync + 'static,
+    F: Fn(&[A], &[A]) -> A + Send + Sync + 'static,
+{
+    shared: Arc<Shared<A, T, U, F>>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl<A, T, U, F> KdTreeService<A, T, U, F>
+where
+    A: Float + Send + Sync + 'static,
+    T: PartialEq + Send + Sync + 'static,
+    U: AsRef<[A]> + PartialEq + Send + Sync + 'static,
+    F: Fn(&[A], &[A]) -> A + Send + Sync + 'static,
+{
+    /// Serve `tree` on `workers` threads, measuring with `distance`. Each worker answers up to
+    /// `batch_size` queued queries per snapshot it takes.
+    pub fn new(tree: KdTree<A, T, U>, distance: F, workers: usize, batch_size: usize) -> Self {
+        let shared = Arc::new(Shared {
+            tree: Snapshot::new(tree),
+            queue: Mutex::new(Queue {
+                requests: VecDeque::new(),
+                shutdown: false,
+            }),
+            available: Condvar::new(),
+            distance,
+            batch_size: batch_size.max(1),
+        });
+        let workers = (0..workers.max(1))
+            .map(|_| {
+                let shared = Arc::clone(&shared);
+                thread::spawn(move || work(&shared))
+            })
+            .collect();
+        KdTreeService { shared, workers }
+    }
+
+    /// The snapshot currently answering queries.
+    pub fn tree(&self) -> Arc<KdTree<A, T, U>> {
+        self.shared.tree.load()
+    }
+
+    /// Answer all queries from now on with `tree`, returning the previous snapshot.
+    pub fn swap(&self, tree: KdTree<A, T, U>) -> Arc<KdTree<A, T, U>> {
+        self.shared.tree.swap(tree)
+    }
+
+    /// The `num` points nearest to `point`, as [`KdTree::nearest_owned`].
+    pub fn nearest(&self, point: Vec<A>, num: usize) -> QueryFuture<A, T, U> {
+        self.submit(point, Query::Nearest(num))
+    }
+
+    /// All points within `radius` of `point`, as [`KdTree::within_owned`].
+    pub fn within(&self, point: Vec<A>, radius: A) -> QueryFuture<A, T, U> {
+        self.submit(point, Query::Within(radius))
+    }
+
+    fn submit(&self, point: Vec<A>, query: Query<A>) -> QueryFuture<A, T, U> {
+        let slot = Arc::new(Mutex::new(Slot {
+            result: None,
+            waker: None,
+        }));
+        let request = Request {
+            point,
+            query,
+            slot: Arc::clone(&slot),
+        };
+        self.shared.queue.lock().unwrap().requests.push_back(request);
+        self.shared.available.notify_one();
+        QueryFuture { slot }
+    }
+}
+
+fn work<A, T, U, F>(shared: &Shared<A, T, U, F>)
+where
+    A: Float,
+    T: PartialEq,
+    U: AsRef<[A]> + PartialEq,
+    F: Fn(&[A], &[A]) -> A,
+{
+    loop {
+        let batch: Vec<Request<A, T, U>> = {
+            let mut queue = shared.queue.lock().unwrap();
+            while queue.requests.is_empty() && !queue.shutdown {
+                queue = shared.available.wait(queue).unwrap();
+            }
+            if queue.requests.is_empty() {
+                return;
+            }
+            let len = queue.requests.len().min(shared.batch_size);
+            queue.requests.drain(..len).collect()
+        };
+        let tree = shared.tree.load();
+        for request in batch {
+            let answer = panic::catch_unwind(AssertUnwindSafe(|| match request.query {
+                Query::Nearest(num) => tree.nearest_owned(&request.point, num, &shared.distance),
+                Query::Within(radius) => tree.within_owned(&request.point, radius, &shared.distance),
+            }))
+            .unwrap_or(Err(ErrorKind::QueryPanicked));
+            let mut slot = request.slot.lock().unwrap();
+            slot.result = Some(answer);
+            if let Some(waker) = slot.waker.take() {
+                waker.wake();
+            }
+        }
+    }
+}
+
+/// Lets the workers finish all queued queries, then joins them.
+impl<A, T, U, F> Drop for KdTreeService<A, T, U, F>
+where
+    A: Float + Send + Sync + 'static,
+    T: PartialEq + Send + Sync + 'static,
+    U: AsRef<[A]> + PartialEq + Send + Sync + 'static,
+    F: Fn(&[A], &[A]) -> A + Send + Sync + 'static,
+{
+    fn drop(&mut self) {
+        self.shared.queue.lock().unwrap().shutdown = true;
+        self.shared.available.notify_all();
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+#[cfg(all(test, loom))]
+mod tests {
+    use loom::thread;
+
+    use super::Snapshot;
+    use crate::distance::squared_euclidean;
+    use crate::KdTree;
+
+    fn pair(label: &'static str) -> KdTree<f64, &'static str, [f64; 1]> {
+        let mut kdtree = KdTree::with_capacity(1, 1);
+        kdtree.add([0.0], label).unwrap();
+        kdtree.add([1.0], label).unwrap();
+        kdtree
+    }
+
+    #[test]
+    fn readers_see_either_the_old_or_the_new_tree() {
+        loom::model(|| {
+            let snapshot = loom::sync::Arc::new(Snapshot::new(pair("old")));
+            let readers: Vec<_> = (0..2)
+                .map(|reader| {
+                    let snapshot = loom::sync::Arc::clone(&snapshot);
+                    thread::spawn(move || {
+                        let tree = snapshot.load();
+                        let found = if reader == 0 {
+                            tree.nearest(&[0.0], 2, &squared_euclidean).unwrap()
+                        } else {
+                            tree.within(&[0.0], 1.0, &squared_euclidean).unwrap()
+                        };
+                        let labels: Vec<&str> = found.iter().map(|&(_, &label)| label).collect();
+                        assert!(labels == ["old", "old"] || labels == ["new", "new"], "{:?}", labels);
+                    })
+                })
+                .collect();
+            let previous = snapshot.swap(pair("new"));
+            assert!(previous.iter().all(|(_, &label)| label == "old"));
+            for reader in readers {
+                reader.join().unwrap();
+            }
+            assert!(snapshot.load().iter().all(|(_, &label)| label == "new"));
+        });
+    }
+}
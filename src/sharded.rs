@@ -0,0 +1,146 @@
+//! One tree per category, queried one category at a time or all at once.
+//!
+//! Map layers, object types and tenants often get a tree each, so queries can be restricted to
+//! one of them. [`ShardedKdTree`] keeps these trees by key and merges the results of queries
+//! across all of them.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use num_traits::Float;
+
+use crate::kdtree::{ErrorKind, KdTree};
+
+#[derive(Clone, Debug)]
+pub struct ShardedKdTree<K, A, T: PartialEq, U: AsRef<[A]> + PartialEq> {
+    dimensions: usize,
+    capacity: usize,
+    shards: HashMap<K, KdTree<A, T, U>>,
+}
+
+impl<K: Eq + Hash, A: Float, T: PartialEq, U: AsRef<[A]> + PartialEq> ShardedKdTree<K, A, T, U> {
+    /// Create a new sharded tree, specifying the dimension size of each point
+    pub fn new(dims: usize) -> Self {
+        ShardedKdTree::with_capacity(dims, 2_usize.pow(4))
+    }
+
+    /// Create a new sharded tree, specifying the dimension size of each point and the capacity of
+    /// leaf nodes of every shard
+    pub fn with_capacity(dimensions: usize, capacity: usize) -> Self {
+        ShardedKdTree {
+            dimensions,
+            capacity,
+            shards: HashMap::new(),
+        }
+    }
+
+    /// Number of points stored across all shards.
+    pub fn size(&self) -> usize {
+        self.shards.values().map(KdTree::size).sum()
+    }
+
+    /// The keys of all shards, in no particular order.
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.shards.keys()
+    }
+
+    /// The tree of the shard `key`, if anything was added to it.
+    pub fn shard(&self, key: &K) -> Option<&KdTree<A, T, U>> {
+        self.shards.get(key)
+    }
+
+    /// Add `data` at `point` to the shard `key`, creating the shard if needed.
+    pub fn add(&mut self, key: K, point: U, data: T) -> Result<(), ErrorKind> {
+        self.check_point(point.as_ref())?;
+        let (dimensions, capacity) = (self.dimensions, self.capacity);
+        self.shards
+            .entry(key)
+            .or_insert_with(|| KdTree::with_capacity(dimensions, capacity))
+            .add(point, data)
+    }
+
+    /// Remove all entries holding `data` at `point` from the shard `key`, returning how many were
+    /// removed. A shard left empty is dropped.
+    pub fn remove(&mut self, key: &K, point: &U, data: &T) -> Result<usize, ErrorKind> {
+        self.check_point(point.as_ref())?;
+        let shard = match self.shards.get_mut(key) {
+            Some(shard) => shard,
+            None => return Ok(0),
+        };
+        let removed = shard.remove(point, data)?;
+        if shard.size() == 0 {
+            self.shards.remove(key);
+        }
+        Ok(removed)
+    }
+
+    /// The `num` nearest neighbours of `point` in the shard `key`, nearest first.
+    pub fn nearest_in<F>(&self, key: &K, point: &[A], num: usize, distance: &F) -> Result<Vec<(A, &T)>, ErrorKind>
+    where
+        F: Fn(&[A], &[A]) -> A,
+    {
+        self.check_point(point)?;
+        match self.shards.get(key) {
+            Some(shard) => shard.nearest(point, num, distance),
+            None => Ok(vec![]),
+        }
+    }
+
+    /// The `num` nearest neighbours of `point` across all shards with the key of their shard,
+    /// nearest first.
+    pub fn nearest_any<F>(&self, point: &[A], num: usize, distance: &F) -> Result<Vec<(A, &K, &T)>, ErrorKind>
+    where
+        F: Fn(&[A], &[A]) -> A,
+    {
+        self.check_point(point)?;
+        let mut found = vec![];
+        for (key, shard) in &self.shards {
+            let nearest = shard.nearest(point, num, distance)?;
+            found.extend(nearest.into_iter().map(|(d, data)| (d, key, data)));
+        }
+        found.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+        found.truncate(num);
+        Ok(found)
+    }
+
+    /// All points of the shard `key` within `radius` of `point`, nearest first.
+    pub fn within_in<F>(&self, key: &K, point: &[A], radius: A, distance: &F) -> Result<Vec<(A, &T)>, ErrorKind>
+    where
+        F: Fn(&[A], &[A]) -> A,
+    {
+        self.check_point(point)?;
+        match self.shards.get(key) {
+            Some(shard) => shard.within(point, radius, distance),
+            None => Ok(vec![]),
+        }
+    }
+
+    /// All points within `radius` of `point` across all shards with the key of their shard,
+    /// nearest first.
+    pub fn within_any<F>(&self, point: &[A], radius: A, distance: &F) -> Result<Vec<(A, &K, &T)>, ErrorKind>
+    where
+        F: Fn(&[A], &[A]) -> A,
+    {
+        self.check_point(point)?;
+        let mut found = vec![];
+        for (key, shard) in &self.shards {
+            let within = shard.within(point, radius, distance)?;
+            found.extend(within.into_iter().map(|(d, data)| (d, key, data)));
+        }
+        found.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+        Ok(found)
+    }
+
+    /// Validate `point` the same way every shard would, so that missing shards don't hide
+    /// invalid queries.
+    fn check_point(&self, point: &[A]) -> Result<(), ErrorKind> {
+        if point.len() != self.dimensions {
+            return Err(ErrorKind::WrongDimension);
+        }
+        if point.iter().any(|x| !x.is_finite()) {
+            return Err(ErrorKind::NonFiniteCoordinate);
+        }
+        Ok(())
+    }
+}
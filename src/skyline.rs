@@ -0,0 +1,72 @@
+//! Skyline (Pareto front) extraction.
+
+use std::collections::BinaryHeap;
+
+use num_traits::Float;
+
+use crate::heap_element::HeapElement;
+use crate::kdtree::KdTree;
+
+enum Entry<'a, A, T: PartialEq, U: AsRef<[A]> + PartialEq> {
+    Node(&'a KdTree<A, T, U>),
+    Point(&'a U, &'a T),
+}
+
+/// Whether `a` dominates `b`: `a` is no greater in every dimension and less in at least one.
+fn dominates<A: Float>(a: &[A], b: &[A]) -> bool {
+    a.iter().zip(b).all(|(x, y)| x <= y) && a.iter().zip(b).any(|(x, y)| x < y)
+}
+
+impl<A: Float, T: PartialEq, U: AsRef<[A]> + PartialEq> KdTree<A, T, U> {
+    /// All points not dominated by any other point, smaller coordinates being better.
+    ///
+    /// A point dominates another one if it is no greater in every dimension and strictly less in
+    /// at least one. Identical points do not dominate each other, so all of them are returned.
+    /// Subtrees whose lower bound corner is already dominated are skipped without being visited.
+    /// Points are returned by increasing sum of their coordinates.
+    pub fn skyline(&self) -> Vec<(&U, &T)> {
+        let mut skyline: Vec<(&U, &T)> = vec![];
+        let mut pending = BinaryHeap::new();
+        pending.push(HeapElement {
+            distance: A::zero(),
+            element: Entry::Node(self),
+        });
+        let is_dominated =
+            |skyline: &[(&U, &T)], corner: &[A]| skyline.iter().any(|(p, _)| dominates(p.as_ref(), corner));
+        while let Some(HeapElement { element, .. }) = pending.pop() {
+            match element {
+                Entry::Point(point, data) => {
+                    if !is_dominated(&skyline, point.as_ref()) {
+                        skyline.push((point, data));
+                    }
+                }
+                Entry::Node(node) => {
+                    if node.size == 0 || is_dominated(&skyline, &node.min_bounds) {
+                        continue;
+                    }
+                    if node.is_leaf() {
+                        let points = node.points.as_ref().unwrap().iter();
+                        let bucket = node.bucket.as_ref().unwrap().iter();
+                        pending.extend(points.zip(bucket).map(|(p, d)| HeapElement {
+                            distance: -sum(p.as_ref()),
+                            element: Entry::Point(p, d),
+                        }));
+                    } else {
+                        pending.extend([&node.left, &node.right].iter().map(|child| {
+                            let child = child.as_ref().unwrap();
+                            HeapElement {
+                                distance: -sum(&child.min_bounds),
+                                element: Entry::Node(&**child),
+                            }
+                        }));
+                    }
+                }
+            }
+        }
+        skyline
+    }
+}
+
+fn sum<A: Float>(point: &[A]) -> A {
+    point.iter().fold(A::zero(), |acc, x| acc + *x)
+}
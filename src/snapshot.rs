@@ -0,0 +1,319 @@
+//! A stable, human readable textual snapshot of a tree.
+//!
+//! Unlike the `Debug` output, which may change with the internal layout of the tree, the snapshot
+//! format is versioned and kept stable, so it can be checked into golden files. A snapshot records
+//! the node structure exactly, so `from_snapshot_str(to_snapshot_string())` yields a tree with the
+//! same splits, bounds and buckets.
+//!
+//! ```text
+//! kdtree snapshot v1 dimensions=1 capacity=2
+//! stem split_dimension=0 split_value=50 size=4 min=0 max=100
+//!   leaf size=2 min=0 max=45
+//!     - 0 => 1
+//!     - 45 => 3
+//!   leaf size=2 min=55 max=100
+//!     - 100 => 2
+//!     - 55 => 4
+//! ```
+//!
+//! The header also carries the [leaf limit](KdTree::set_leaf_limit) as `leaf_limit=<n>` and
+//! [strictness](KdTree::set_strict) as `strict=true`, only if they are set, so a restored tree
+//! keeps treating oversized leaves like the original.
+//!
+//! Node IDs are not part of the format. A restored tree numbers its nodes afresh, as a tree just
+//! [built](KdTree::build) with the same structure, so its [generation](KdTree::generation) is its
+//! number of stems.
+//!
+//! Every node is one line, children are indented by two spaces below their parent, the left child
+//! first. Leaf entries are listed as `- <coordinates> => <payload>`, coordinates being separated by
+//! commas. Coordinates and payloads are written with `Display` and read back with `FromStr`, so
+//! payloads must not contain line breaks.
+
+use std::convert::TryFrom;
+use std::fmt::{Display, Write};
+use std::str::FromStr;
+
+use num_traits::Float;
+use thiserror::Error;
+
+use crate::kdtree::KdTree;
+
+const HEADER: &str = "kdtree snapshot v1";
+const INDENT: usize = 2;
+
+/// Error returned when a snapshot cannot be parsed.
+#[derive(Error, Debug, PartialEq, Eq)]
+#[error("invalid snapshot at line {line}: {message}")]
+pub struct SnapshotError {
+    /// 1-based line number the error was detected at.
+    pub line: usize,
+    pub message: String,
+}
+
+impl<A: Float + Display, T: PartialEq + Display, U: AsRef<[A]> + PartialEq> KdTree<A, T, U> {
+    /// Render the tree in the stable snapshot format described in the [`snapshot`](crate::snapshot)
+    /// module.
+    pub fn to_snapshot_string(&self) -> String {
+        let mut out = String::new();
+        write!(
+            out,
+            "{} dimensions={} capacity={}",
+            HEADER, self.dimensions, self.capacity
+        )
+        .unwrap();
+        if let Some(limit) = self.leaf_limit {
+            write!(out, " leaf_limit={}", limit).unwrap();
+        }
+        if self.strict {
+            out.push_str(" strict=true");
+        }
+        out.push('\n');
+        self.write_snapshot_node(&mut out, 0);
+        out
+    }
+
+    fn write_snapshot_node(&self, out: &mut String, depth: usize) {
+        let indent = depth * INDENT;
+        if self.is_leaf() {
+            writeln!(
+                out,
+                "{:indent$}leaf size={} min={} max={}",
+                "",
+                self.size,
+                join(&self.min_bounds),
+                join(&self.max_bounds),
+                indent = indent
+            )
+            .unwrap();
+            let points = self.points.as_ref().unwrap();
+            let bucket = self.bucket.as_ref().unwrap();
+            for (point, data) in points.iter().zip(bucket) {
+                writeln!(
+                    out,
+                    "{:indent$}- {} => {}",
+                    "",
+                    join(point.as_ref()),
+                    data,
+                    indent = indent + INDENT
+                )
+                .unwrap();
+            }
+        } else {
+            writeln!(
+                out,
+                "{:indent$}stem split_dimension={} split_value={} size={} min={} max={}",
+                "",
+                self.split_dimension.unwrap(),
+                self.split_value.unwrap(),
+                self.size,
+                join(&self.min_bounds),
+                join(&self.max_bounds),
+                indent = indent
+            )
+            .unwrap();
+            self.left.as_ref().unwrap().write_snapshot_node(out, depth + 1);
+            self.right.as_ref().unwrap().write_snapshot_node(out, depth + 1);
+        }
+    }
+}
+
+impl<A: Float + FromStr, T: PartialEq + FromStr, U: AsRef<[A]> + PartialEq + TryFrom<Vec<A>>> KdTree<A, T, U> {
+    /// Restore a tree from the snapshot format described in the [`snapshot`](crate::snapshot)
+    /// module.
+    pub fn from_snapshot_str(snapshot: &str) -> Result<Self, SnapshotError> {
+        let mut lines = snapshot
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| Line {
+                number: i + 1,
+                indent: line.len() - line.trim_start().len(),
+                content: line.trim(),
+            })
+            .peekable();
+
+        let header = lines.next().ok_or_else(|| error(1, "empty snapshot"))?;
+        let rest = header
+            .content
+            .strip_prefix(HEADER)
+            .ok_or_else(|| error(header.number, "missing `kdtree snapshot v1` header"))?;
+        let mut fields = Fields::parse(header.number, rest)?;
+        let dimensions = fields.take("dimensions")?;
+        let capacity = fields.take("capacity")?;
+        let leaf_limit = fields.take_optional("leaf_limit")?;
+        let strict = fields.take_optional("strict")?.unwrap_or(false);
+
+        let mut nodes: Vec<Line> = lines.collect();
+        nodes.reverse();
+        let mut tree: KdTree<A, T, U> = parse_node(&mut nodes, 0, dimensions, capacity)?;
+        tree.leaf_limit = leaf_limit;
+        tree.strict = strict;
+        tree.reset_ids();
+        match nodes.pop() {
+            Some(line) => Err(error(line.number, "unexpected trailing line")),
+            None => Ok(tree),
+        }
+    }
+}
+
+struct Line<'a> {
+    number: usize,
+    indent: usize,
+    content: &'a str,
+}
+
+/// `key=value` pairs of a single node line.
+struct Fields<'a> {
+    line: usize,
+    pairs: Vec<(&'a str, &'a str)>,
+}
+
+impl<'a> Fields<'a> {
+    fn parse(line: usize, content: &'a str) -> Result<Self, SnapshotError> {
+        let pairs = content
+            .split_whitespace()
+            .map(|pair| {
+                let mut split = pair.splitn(2, '=');
+                match (split.next(), split.next()) {
+                    (Some(key), Some(value)) => Ok((key, value)),
+                    _ => Err(error(line, format!("expected `key=value`, found `{}`", pair))),
+                }
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Fields { line, pairs })
+    }
+
+    fn raw(&mut self, key: &str) -> Result<&'a str, SnapshotError> {
+        let position = self
+            .pairs
+            .iter()
+            .position(|(k, _)| *k == key)
+            .ok_or_else(|| error(self.line, format!("missing `{}`", key)))?;
+        Ok(self.pairs.remove(position).1)
+    }
+
+    fn take<V: FromStr>(&mut self, key: &str) -> Result<V, SnapshotError> {
+        let raw = self.raw(key)?;
+        raw.parse()
+            .map_err(|_| error(self.line, format!("invalid `{}` value `{}`", key, raw)))
+    }
+
+    fn take_optional<V: FromStr>(&mut self, key: &str) -> Result<Option<V>, SnapshotError> {
+        if self.pairs.iter().any(|(k, _)| *k == key) {
+            self.take(key).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn take_coordinates<A: FromStr>(&mut self, key: &str, dimensions: usize) -> Result<Box<[A]>, SnapshotError> {
+        let raw = self.raw(key)?;
+        parse_coordinates(self.line, raw, dimensions).map(Vec::into_boxed_slice)
+    }
+}
+
+fn parse_node<A, T, U>(
+    lines: &mut Vec<Line>,
+    indent: usize,
+    dimensions: usize,
+    capacity: usize,
+) -> Result<KdTree<A, T, U>, SnapshotError>
+where
+    A: Float + FromStr,
+    T: PartialEq + FromStr,
+    U: AsRef<[A]> + PartialEq + TryFrom<Vec<A>>,
+{
+    let line = lines.pop().ok_or_else(|| error(0, "missing node"))?;
+    if line.indent != indent {
+        return Err(error(line.number, "unexpected indentation"));
+    }
+    let mut tree = KdTree::with_capacity(dimensions, capacity);
+    let mut words = line.content.splitn(2, ' ');
+    let kind = words.next().unwrap_or("");
+    let mut fields = Fields::parse(line.number, words.next().unwrap_or(""))?;
+    tree.size = fields.take("size")?;
+    tree.min_bounds = fields.take_coordinates("min", dimensions)?;
+    tree.max_bounds = fields.take_coordinates("max", dimensions)?;
+    match kind {
+        "leaf" => {
+            let mut points = vec![];
+            let mut bucket = vec![];
+            while lines.last().map_or(false, |next| next.content.starts_with('-')) {
+                let entry = lines.pop().unwrap();
+                if entry.indent != indent + INDENT {
+                    return Err(error(entry.number, "unexpected indentation"));
+                }
+                let mut split = entry.content[1..].splitn(2, "=>");
+                let (coordinates, data) = match (split.next(), split.next()) {
+                    (Some(coordinates), Some(data)) => (coordinates.trim(), data.trim()),
+                    _ => return Err(error(entry.number, "expected `- <coordinates> => <payload>`")),
+                };
+                let point = U::try_from(parse_coordinates(entry.number, coordinates, dimensions)?)
+                    .map_err(|_| error(entry.number, "coordinates do not fit the point type"))?;
+                let data = data
+                    .parse()
+                    .map_err(|_| error(entry.number, format!("invalid payload `{}`", data)))?;
+                points.push(point);
+                bucket.push(data);
+            }
+            if points.len() != tree.size {
+                return Err(error(line.number, "leaf size does not match its entries"));
+            }
+            tree.points = Some(points);
+            tree.bucket = Some(bucket);
+            tree.refresh_summary();
+        }
+        "stem" => {
+            let split_dimension = fields.take("split_dimension")?;
+            if split_dimension >= dimensions {
+                return Err(error(line.number, "split dimension out of range"));
+            }
+            tree.split_dimension = Some(split_dimension);
+            tree.split_value = Some(fields.take("split_value")?);
+            let left: KdTree<A, T, U> = parse_node(lines, indent + INDENT, dimensions, capacity)?;
+            let right: KdTree<A, T, U> = parse_node(lines, indent + INDENT, dimensions, capacity)?;
+            if left.size + right.size != tree.size {
+                return Err(error(line.number, "stem size does not match its children"));
+            }
+            tree.left = Some(Box::new(left));
+            tree.right = Some(Box::new(right));
+            tree.points = None;
+            tree.bucket = None;
+        }
+        other => return Err(error(line.number, format!("unknown node kind `{}`", other))),
+    }
+    Ok(tree)
+}
+
+fn parse_coordinates<A: FromStr>(line: usize, raw: &str, dimensions: usize) -> Result<Vec<A>, SnapshotError> {
+    let coordinates = raw
+        .split(',')
+        .map(|x| {
+            x.trim()
+                .parse()
+                .map_err(|_| error(line, format!("invalid coordinate `{}`", x)))
+        })
+        .collect::<Result<Vec<A>, _>>()?;
+    if coordinates.len() != dimensions {
+        return Err(error(line, "wrong dimension"));
+    }
+    Ok(coordinates)
+}
+
+fn join<A: Display>(values: &[A]) -> String {
+    let mut out = String::new();
+    for (i, value) in values.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        write!(out, "{}", value).unwrap();
+    }
+    out
+}
+
+fn error<M: Into<String>>(line: usize, message: M) -> SnapshotError {
+    SnapshotError {
+        line,
+        message: message.into(),
+    }
+}
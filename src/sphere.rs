@@ -0,0 +1,91 @@
+//! Nearest neighbours on the surface of a sphere.
+//!
+//! Latitude/longitude points are stored as 3D unit vectors. The chord (straight line) distance
+//! between two unit vectors grows monotonically with the angle between them, `chord = 2 sin(θ/2)`,
+//! so euclidean k-NN over the unit vectors is exactly spherical k-NN, with the regular pruning of
+//! the tree. Unlike euclidean distance on raw latitude/longitude, this stays correct near the poles
+//! and across the antimeridian.
+
+use num_traits::Float;
+
+use crate::distance::squared_euclidean;
+use crate::kdtree::{ErrorKind, KdTree};
+
+/// Convert latitude and longitude, both in degrees, to a point on the unit sphere.
+pub fn unit_vector<A: Float>(lat: A, lon: A) -> [A; 3] {
+    let (lat, lon) = (lat.to_radians(), lon.to_radians());
+    [lat.cos() * lon.cos(), lat.cos() * lon.sin(), lat.sin()]
+}
+
+/// Central angle in radians corresponding to a squared chord length between unit vectors.
+fn angle_from_squared_chord<A: Float>(squared_chord: A) -> A {
+    let two = A::one() + A::one();
+    let half_chord = squared_chord.sqrt() / two;
+    two * half_chord.min(A::one()).asin()
+}
+
+/// Squared chord length between unit vectors corresponding to a central angle in radians.
+fn squared_chord_from_angle<A: Float>(angle: A) -> A {
+    let two = A::one() + A::one();
+    let chord = two * (angle.min(A::from(std::f64::consts::PI).unwrap()) / two).sin();
+    chord * chord
+}
+
+/// A tree of latitude/longitude points answering queries by central angle.
+#[derive(Clone, Debug)]
+pub struct SphereKdTree<A: PartialEq, T: PartialEq> {
+    tree: KdTree<A, T, [A; 3]>,
+}
+
+impl<A: Float, T: PartialEq> SphereKdTree<A, T> {
+    pub fn new() -> Self {
+        SphereKdTree { tree: KdTree::new(3) }
+    }
+
+    /// Create a new sphere tree, specifying the capacity of leaf nodes
+    pub fn with_capacity(capacity: usize) -> Self {
+        SphereKdTree {
+            tree: KdTree::with_capacity(3, capacity),
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.tree.size()
+    }
+
+    /// Add a point given by latitude and longitude in degrees.
+    pub fn add(&mut self, lat: A, lon: A, data: T) -> Result<(), ErrorKind> {
+        self.tree.add(unit_vector(lat, lon), data)
+    }
+
+    /// The `num` points nearest to the given latitude and longitude, with their central angle
+    /// to it in radians. Multiply by the sphere radius to get great-circle distances.
+    pub fn nearest_angular(&self, lat: A, lon: A, num: usize) -> Result<Vec<(A, &T)>, ErrorKind> {
+        let found = self.tree.nearest(&unit_vector(lat, lon), num, &squared_euclidean)?;
+        Ok(found
+            .into_iter()
+            .map(|(d, data)| (angle_from_squared_chord(d), data))
+            .collect())
+    }
+
+    /// All points whose central angle to the given latitude and longitude is at most `angle`
+    /// radians, nearest first. A negative or non-finite `angle` fails with
+    /// [`ErrorKind::InvalidAngle`].
+    pub fn within_angle(&self, lat: A, lon: A, angle: A) -> Result<Vec<(A, &T)>, ErrorKind> {
+        if !angle.is_finite() || angle < A::zero() {
+            return Err(ErrorKind::InvalidAngle);
+        }
+        let radius = squared_chord_from_angle(angle);
+        let found = self.tree.within(&unit_vector(lat, lon), radius, &squared_euclidean)?;
+        Ok(found
+            .into_iter()
+            .map(|(d, data)| (angle_from_squared_chord(d), data))
+            .collect())
+    }
+}
+
+impl<A: Float, T: PartialEq> Default for SphereKdTree<A, T> {
+    fn default() -> Self {
+        SphereKdTree::new()
+    }
+}
@@ -0,0 +1,185 @@
+//! Structural statistics about a tree, useful for spotting unbalanced or degenerate trees.
+
+use num_traits::Float;
+
+use crate::axis::Axis;
+use crate::kdtree::KdTree;
+use crate::util;
+
+/// Summary of the shape of a [`KdTree`](crate::KdTree), as returned by
+/// [`KdTree::stats`](crate::KdTree::stats).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TreeStats {
+    /// Number of points stored in the tree.
+    pub size: usize,
+    /// Dimension size of each point.
+    pub dimensions: usize,
+    /// Capacity of leaf nodes before they are split.
+    pub capacity: usize,
+    /// Length of the longest path from the root to a leaf, the root alone having depth 0.
+    pub depth: usize,
+    /// Number of inner nodes.
+    pub stems: usize,
+    /// Number of leaf nodes.
+    pub leaves: usize,
+    /// Number of leaf nodes not holding any point.
+    pub empty_leaves: usize,
+    /// Number of points in the fullest leaf.
+    pub max_leaf_size: usize,
+    /// Number of leaves that could not be split when full, because all of their points were
+    /// identical or all of them ended up on one side. Such leaves grow beyond their capacity and
+    /// make queries scan more points than they should.
+    pub unsplittable_leaves: usize,
+    /// The number of points a leaf may hold, see [`KdTree::set_leaf_limit`](crate::KdTree::set_leaf_limit).
+    pub leaf_limit: Option<usize>,
+    /// Number of leaves holding more points than the leaf limit. Deduplicating the points or
+    /// jittering their coordinates keeps leaves from growing like this.
+    pub oversized_leaves: usize,
+}
+
+impl<A: Float, T: PartialEq, U: AsRef<[A]> + PartialEq> KdTree<A, T, U> {
+    /// Minimum, maximum, mean and variance of every coordinate over all points, empty for an
+    /// empty tree.
+    ///
+    /// Leaves keep running statistics of their points, so this only visits every node once
+    /// instead of every point.
+    pub fn coordinate_stats(&self) -> Vec<DimensionStats<A>> {
+        if self.size == 0 {
+            return vec![];
+        }
+        let mut total = Summary::empty(self.dimensions);
+        let mut pending = vec![self];
+        while let Some(node) = pending.pop() {
+            if let Some(points) = node.points.as_ref() {
+                match node.summary.as_ref() {
+                    Some(summary) => total.merge(summary),
+                    None => total.merge(&Summary::of_points(self.dimensions, points)),
+                }
+            } else {
+                pending.extend(node.left.as_deref());
+                pending.extend(node.right.as_deref());
+            }
+        }
+        let count = total.count as f64;
+        (0..self.dimensions)
+            .map(|i| DimensionStats {
+                min: total.min[i],
+                max: total.max[i],
+                mean: A::from(total.mean[i]).unwrap(),
+                variance: A::from(total.m2[i] / count).unwrap(),
+            })
+            .collect()
+    }
+
+    /// Walk the whole tree and collect structural statistics.
+    pub fn stats(&self) -> TreeStats {
+        let mut stats = TreeStats {
+            dimensions: self.dimensions,
+            capacity: self.capacity,
+            size: self.size,
+            leaf_limit: self.leaf_limit,
+            ..TreeStats::default()
+        };
+        let mut pending = vec![(self, 0)];
+        while let Some((node, depth)) = pending.pop() {
+            stats.depth = stats.depth.max(depth);
+            if node.is_leaf() {
+                let len = node.bucket.as_ref().map_or(0, Vec::len);
+                stats.leaves += 1;
+                if node.unsplittable {
+                    stats.unsplittable_leaves += 1;
+                }
+                if len == 0 {
+                    stats.empty_leaves += 1;
+                }
+                stats.max_leaf_size = stats.max_leaf_size.max(len);
+                if self.leaf_limit.map_or(false, |limit| len > limit) {
+                    stats.oversized_leaves += 1;
+                }
+            } else {
+                stats.stems += 1;
+                pending.extend(node.left.as_deref().map(|left| (left, depth + 1)));
+                pending.extend(node.right.as_deref().map(|right| (right, depth + 1)));
+            }
+        }
+        stats
+    }
+}
+
+/// Summary statistics of one coordinate over all points of a tree, as returned by
+/// [`KdTree::coordinate_stats`](crate::KdTree::coordinate_stats).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DimensionStats<A> {
+    pub min: A,
+    pub max: A,
+    pub mean: A,
+    /// Population variance, dividing by the number of points.
+    pub variance: A,
+}
+
+/// Running statistics of the points of a leaf, kept up to date as points are added, and
+/// recomputed from scratch when points are removed. Means and variances are accumulated in `f64`,
+/// so they do not overflow or get truncated for integer coordinates.
+#[derive(Clone, Debug)]
+pub(crate) struct Summary<A> {
+    count: usize,
+    min: Vec<A>,
+    max: Vec<A>,
+    mean: Vec<f64>,
+    /// Sum of squared differences from the mean, see Welford's algorithm.
+    m2: Vec<f64>,
+}
+
+impl<A: Axis> Summary<A> {
+    fn empty(dimensions: usize) -> Self {
+        Summary {
+            count: 0,
+            min: vec![A::greatest(); dimensions],
+            max: vec![A::least(); dimensions],
+            mean: vec![0.0; dimensions],
+            m2: vec![0.0; dimensions],
+        }
+    }
+
+    pub(crate) fn of_points<U: AsRef<[A]>>(dimensions: usize, points: &[U]) -> Self {
+        let mut summary = Summary::empty(dimensions);
+        for point in points {
+            summary.push(point.as_ref());
+        }
+        summary
+    }
+
+    pub(crate) fn push(&mut self, point: &[A]) {
+        self.count += 1;
+        let count = self.count as f64;
+        for (i, &x) in point.iter().enumerate() {
+            self.min[i] = util::min(self.min[i], x);
+            if x > self.max[i] {
+                self.max[i] = x;
+            }
+            let x = x.to_f64().unwrap_or(f64::NAN);
+            let delta = x - self.mean[i];
+            self.mean[i] += delta / count;
+            self.m2[i] += delta * (x - self.mean[i]);
+        }
+    }
+
+    /// Combine with the statistics of disjoint points, see Chan et al.
+    fn merge(&mut self, other: &Summary<A>) {
+        if other.count == 0 {
+            return;
+        }
+        let (a, b) = (self.count as f64, other.count as f64);
+        let total = a + b;
+        for i in 0..self.mean.len() {
+            self.min[i] = util::min(self.min[i], other.min[i]);
+            if other.max[i] > self.max[i] {
+                self.max[i] = other.max[i];
+            }
+            let delta = other.mean[i] - self.mean[i];
+            self.mean[i] += delta * b / total;
+            self.m2[i] += other.m2[i] + delta * delta * a * b / total;
+        }
+        self.count += other.count;
+    }
+}
@@ -0,0 +1,144 @@
+//! Comparing coordinates up to a tolerance.
+//!
+//! Points that went through any computation rarely compare bit-equal to the points stored, so the
+//! operations matching stored points by their coordinates have variants taking a [`Tolerance`].
+
+use num_traits::Float;
+
+use crate::handle;
+use crate::kdtree::{ErrorKind, KdTree};
+
+/// How far apart coordinates may be and still be considered equal.
+///
+/// Coordinates are compared one by one, so points match when all of their coordinates do.
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Tolerance<A> {
+    /// Coordinates must be exactly equal.
+    Exact,
+    /// Coordinates may differ by at most this much.
+    Absolute(A),
+    /// Coordinates may differ by at most this fraction of the larger of their magnitudes.
+    Relative(A),
+}
+
+impl<A: Float> Tolerance<A> {
+    /// Whether the coordinates `a` and `b` are equal up to this tolerance.
+    pub fn matches(&self, a: A, b: A) -> bool {
+        match *self {
+            Tolerance::Exact => a == b,
+            Tolerance::Absolute(epsilon) => (a - b).abs() <= epsilon,
+            Tolerance::Relative(epsilon) => (a - b).abs() <= epsilon * a.abs().max(b.abs()),
+        }
+    }
+
+    /// Whether all coordinates of `a` and `b` are equal up to this tolerance.
+    pub fn matches_points(&self, a: &[A], b: &[A]) -> bool {
+        a.len() == b.len() && a.iter().zip(b).all(|(&a, &b)| self.matches(a, b))
+    }
+
+    /// How far outside of a node's bounds a coordinate `x` may lie and still match a point in it.
+    fn slack(&self, x: A) -> A {
+        match *self {
+            Tolerance::Exact => A::zero(),
+            Tolerance::Absolute(epsilon) => epsilon,
+            // |x - y| <= e * max(|x|, |y|) <= e * (|x| + |x - y|)
+            Tolerance::Relative(epsilon) if epsilon < A::one() => epsilon * x.abs() / (A::one() - epsilon),
+            Tolerance::Relative(_) => A::infinity(),
+        }
+    }
+}
+
+impl<A: Float, T: PartialEq, U: AsRef<[A]> + PartialEq> KdTree<A, T, U> {
+    /// Whether any point equal to `point` up to `tolerance` is stored.
+    pub fn contains(&self, point: &[A], tolerance: Tolerance<A>) -> Result<bool, ErrorKind> {
+        self.check_point(point)?;
+        Ok(!self.matching(point, tolerance).is_empty())
+    }
+
+    /// All entries whose coordinates equal `point` up to `tolerance`.
+    pub fn get_matching(&self, point: &[A], tolerance: Tolerance<A>) -> Result<Vec<(&U, &T)>, ErrorKind> {
+        self.check_point(point)?;
+        Ok(self.matching(point, tolerance))
+    }
+
+    /// Remove all entries holding `data` whose coordinates equal `point` up to `tolerance`,
+    /// returning how many were removed. With [`Tolerance::Exact`], this is
+    /// [`remove`](Self::remove).
+    pub fn remove_matching(&mut self, point: &[A], data: &T, tolerance: Tolerance<A>) -> Result<usize, ErrorKind> {
+        self.check_point(point)?;
+        let removed = self.remove_matching_recursive(point, data, tolerance);
+        if removed > 0 {
+            self.revision = handle::next_revision();
+        }
+        Ok(removed)
+    }
+
+    fn remove_matching_recursive(&mut self, point: &[A], data: &T, tolerance: Tolerance<A>) -> usize {
+        if !self.may_match(point, tolerance) {
+            return 0;
+        }
+        let removed = if let (Some(points), Some(bucket)) = (self.points.as_mut(), self.bucket.as_mut()) {
+            let mut removed = 0;
+            let mut i = 0;
+            while i < points.len() {
+                if &bucket[i] == data && tolerance.matches_points(points[i].as_ref(), point) {
+                    points.remove(i);
+                    bucket.remove(i);
+                    removed += 1;
+                } else {
+                    i += 1;
+                }
+            }
+            removed
+        } else {
+            let mut removed = 0;
+            if let Some(left) = self.left.as_mut() {
+                removed += left.remove_matching_recursive(point, data, tolerance);
+            }
+            if let Some(right) = self.right.as_mut() {
+                removed += right.remove_matching_recursive(point, data, tolerance);
+            }
+            removed
+        };
+        self.size -= removed;
+        if removed > 0 && self.points.is_some() {
+            self.refresh_summary();
+        }
+        removed
+    }
+
+    /// All entries whose coordinates equal `point` up to `tolerance`, visiting only the nodes
+    /// whose bounds may hold such a point.
+    pub(crate) fn matching(&self, point: &[A], tolerance: Tolerance<A>) -> Vec<(&U, &T)> {
+        let mut found = vec![];
+        let mut pending = vec![self];
+        while let Some(node) = pending.pop() {
+            if !node.may_match(point, tolerance) {
+                continue;
+            }
+            if node.is_leaf() {
+                let points = node.points.as_ref().unwrap().iter();
+                let bucket = node.bucket.as_ref().unwrap().iter();
+                found.extend(
+                    points
+                        .zip(bucket)
+                        .filter(|(p, _)| tolerance.matches_points(p.as_ref(), point)),
+                );
+            } else {
+                pending.extend(node.left.as_deref());
+                pending.extend(node.right.as_deref());
+            }
+        }
+        found
+    }
+
+    fn may_match(&self, point: &[A], tolerance: Tolerance<A>) -> bool {
+        let min = self.min_bounds.iter();
+        let max = self.max_bounds.iter();
+        min.zip(max).zip(point).all(|((&l, &h), &v)| {
+            let slack = tolerance.slack(v);
+            l - slack <= v && v <= h + slack
+        })
+    }
+}
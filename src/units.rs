@@ -0,0 +1,83 @@
+//! Coordinates carrying units of measure, through the `uom` crate.
+//!
+//! [`LengthKdTree`] accepts points as [`Length`] quantities and returns squared distances as
+//! [`Area`], so mixing meters and kilometers is handled by the type system instead of silently
+//! producing wrong neighbours. Internally coordinates are stored in meters.
+//!
+//! This only partly covers units of measure: lengths only, stored as `f64` and measured by
+//! squared euclidean distance. The distance and bounds code is not generic over quantities, so
+//! other quantities, `f32` storage and custom distance functions are not supported, and need a
+//! tree of plain floats converted by hand like here.
+
+use uom::si::area::square_meter;
+use uom::si::f64::{Area, Length};
+use uom::si::length::meter;
+
+use crate::distance::squared_euclidean;
+use crate::kdtree::{ErrorKind, KdTree};
+
+/// A tree of points whose coordinates are lengths.
+#[derive(Clone, Debug)]
+pub struct LengthKdTree<T: PartialEq> {
+    tree: KdTree<f64, T, Vec<f64>>,
+}
+
+impl<T: PartialEq> LengthKdTree<T> {
+    /// Create a new tree, specifying the dimension size of each point
+    pub fn new(dims: usize) -> Self {
+        LengthKdTree {
+            tree: KdTree::new(dims),
+        }
+    }
+
+    /// Create a new tree, specifying the dimension size of each point and the capacity of leaf
+    /// nodes
+    pub fn with_capacity(dimensions: usize, capacity: usize) -> Self {
+        LengthKdTree {
+            tree: KdTree::with_capacity(dimensions, capacity),
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.tree.size()
+    }
+
+    pub fn add(&mut self, point: &[Length], data: T) -> Result<(), ErrorKind> {
+        self.tree.add(meters(point), data)
+    }
+
+    /// Remove all entries holding `data` at `point`, returning how many were removed.
+    pub fn remove(&mut self, point: &[Length], data: &T) -> Result<usize, ErrorKind> {
+        self.tree.remove(&meters(point), data)
+    }
+
+    /// The `num` nearest points with their squared euclidean distance.
+    pub fn nearest(&self, point: &[Length], num: usize) -> Result<Vec<(Area, &T)>, ErrorKind> {
+        let found = self.tree.nearest(&meters(point), num, &squared_euclidean)?;
+        Ok(found
+            .into_iter()
+            .map(|(d, data)| (Area::new::<square_meter>(d), data))
+            .collect())
+    }
+
+    /// All points at most `radius` away, with their squared euclidean distance. A negative or
+    /// non-finite `radius` fails with [`ErrorKind::InvalidRadius`].
+    pub fn within(&self, point: &[Length], radius: Length) -> Result<Vec<(Area, &T)>, ErrorKind> {
+        let meters_radius = radius.get::<meter>();
+        if !meters_radius.is_finite() || meters_radius < 0.0 {
+            return Err(ErrorKind::InvalidRadius);
+        }
+        let radius = radius * radius;
+        let found = self
+            .tree
+            .within(&meters(point), radius.get::<square_meter>(), &squared_euclidean)?;
+        Ok(found
+            .into_iter()
+            .map(|(d, data)| (Area::new::<square_meter>(d), data))
+            .collect())
+    }
+}
+
+fn meters(point: &[Length]) -> Vec<f64> {
+    point.iter().map(|x| x.get::<meter>()).collect()
+}
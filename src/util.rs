@@ -1,23 +1,59 @@
-use num_traits::Float;
-
 pub fn distance_to_space<F, T>(p1: &[T], min_bounds: &[T], max_bounds: &[T], distance: &F) -> T
 where
     F: Fn(&[T], &[T]) -> T,
-    T: Float,
+    T: Copy + PartialOrd,
 {
-    let mut p2 = vec![T::nan(); p1.len()];
+    let mut p2 = p1.to_vec();
     for i in 0..p1.len() {
         if p1[i] > max_bounds[i] {
             p2[i] = max_bounds[i];
         } else if p1[i] < min_bounds[i] {
             p2[i] = min_bounds[i];
-        } else {
-            p2[i] = p1[i];
         }
     }
     distance(p1, &p2[..])
 }
 
+/// Grow the box `min`..`max` to include the box `other_min`..`other_max`.
+pub fn extend_bounds<T: Copy + PartialOrd>(min: &mut [T], max: &mut [T], other_min: &[T], other_max: &[T]) {
+    for (l, &v) in min.iter_mut().zip(other_min) {
+        *l = self::min(*l, v);
+    }
+    for (h, &v) in max.iter_mut().zip(other_max) {
+        if v > *h {
+            *h = v;
+        }
+    }
+}
+
+/// The smaller of `a` and `b`, `a` if they are not comparable.
+pub fn min<T: PartialOrd>(a: T, b: T) -> T {
+    if b < a {
+        b
+    } else {
+        a
+    }
+}
+
+/// Whether the boxes `min`..`max` and `other_min`..`other_max` share any point.
+pub fn boxes_overlap<T: PartialOrd>(min: &[T], max: &[T], other_min: &[T], other_max: &[T]) -> bool {
+    (0..min.len()).all(|i| min[i] <= other_max[i] && other_min[i] <= max[i])
+}
+
+/// Whether the box `min`..`max` contains all of the box `other_min`..`other_max`.
+pub fn box_contains<T: PartialOrd>(min: &[T], max: &[T], other_min: &[T], other_max: &[T]) -> bool {
+    (0..min.len()).all(|i| min[i] <= other_min[i] && other_max[i] <= max[i])
+}
+
+/// The next output of the splitmix64 generator for the state `z`, a cheap way to derive well
+/// spread bits from a seed and a counter.
+pub fn splitmix64(mut z: u64) -> u64 {
+    z = z.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
 #[cfg(test)]
 mod tests {
     use super::distance_to_space;
@@ -0,0 +1,55 @@
+//! Touching all of a tree's memory ahead of the first queries.
+//!
+//! A freshly loaded tree, or one whose pages were swapped out, pays page faults on its first
+//! queries, which shows up as tail latency. Warming it up moves that cost to a point of your
+//! choosing, typically right after loading and before taking traffic.
+
+use num_traits::Float;
+#[cfg(feature = "rayon")]
+use rayon::join;
+
+use crate::kdtree::KdTree;
+
+impl<A: Float, T: PartialEq, U: AsRef<[A]> + PartialEq> KdTree<A, T, U> {
+    /// Read every node's bounds and every stored coordinate, in the order queries descend the
+    /// tree, so their memory is resident afterwards.
+    ///
+    /// Returns how many finite values were read, bounds included, mostly so the reads cannot be
+    /// optimized away. Payloads are not touched, as they may own memory elsewhere.
+    pub fn warm_up(&self) -> usize {
+        let mut touched = 0;
+        let mut pending = vec![self];
+        while let Some(node) = pending.pop() {
+            touched += node.touch();
+            pending.extend(node.right.as_deref());
+            pending.extend(node.left.as_deref());
+        }
+        touched
+    }
+
+    /// As [`warm_up`](Self::warm_up), warming up the subtrees of each stem in parallel.
+    #[cfg(feature = "rayon")]
+    pub fn par_warm_up(&self) -> usize
+    where
+        A: Sync,
+        T: Sync,
+        U: Sync,
+    {
+        let own = self.touch();
+        match (self.left.as_deref(), self.right.as_deref()) {
+            (Some(left), Some(right)) => {
+                let (left, right) = join(|| left.par_warm_up(), || right.par_warm_up());
+                own + left + right
+            }
+            _ => own,
+        }
+    }
+
+    /// Read the bounds and the coordinates stored in this node only, counting finite ones.
+    fn touch(&self) -> usize {
+        let bounds = self.min_bounds.iter().chain(self.max_bounds.iter());
+        let points = self.points.as_deref().unwrap_or(&[]);
+        let coordinates = points.iter().flat_map(|p| p.as_ref().iter());
+        bounds.chain(coordinates).filter(|x| x.is_finite()).count()
+    }
+}
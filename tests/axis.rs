@@ -0,0 +1,184 @@
+extern crate kdtree;
+extern crate num_traits;
+
+use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
+
+use kdtree::distance::{manhattan, squared_euclidean};
+use kdtree::{ErrorKind, KdTree};
+use num_traits::{Num, NumCast, One, ToPrimitive, Zero};
+
+#[test]
+fn it_stores_integer_coordinates() {
+    let mut kdtree: KdTree<i32, (i32, i32), [i32; 2]> = KdTree::with_capacity(2, 2);
+    for x in -5i32..5 {
+        for y in -5i32..5 {
+            kdtree.add([x * 1000, y * 1000], (x, y)).unwrap();
+        }
+    }
+    assert_eq!(kdtree.size(), 100);
+
+    let nearest = kdtree.nearest(&[2100, -2900], 2, &squared_euclidean).unwrap();
+    assert_eq!(nearest[0], (100 * 100 + 100 * 100, &(2, -3)));
+    let within = kdtree.within(&[0, 0], 1000, &manhattan).unwrap();
+    assert_eq!(within.len(), 5);
+    assert_eq!(within[0], (0, &(0, 0)));
+
+    let mut found = kdtree.within_box(&[-1000, 0], &[0, 1000]).unwrap();
+    found.sort_by_key(|entry| *entry.1);
+    assert_eq!(
+        found,
+        vec![
+            (&[-1000, 0], &(-1, 0)),
+            (&[-1000, 1000], &(-1, 1)),
+            (&[0, 0], &(0, 0)),
+            (&[0, 1000], &(0, 1)),
+        ]
+    );
+    assert!(kdtree.iter().all(|(point, &(x, y))| *point == [x * 1000, y * 1000]));
+    let ordered: Vec<i32> = kdtree
+        .iter_nearest(&[0, 0], &squared_euclidean)
+        .unwrap()
+        .map(|(distance, _)| distance)
+        .take(5)
+        .collect();
+    assert_eq!(ordered, vec![0, 1_000_000, 1_000_000, 1_000_000, 1_000_000]);
+
+    assert_eq!(kdtree.remove(&[0, 0], &(0, 0)), Ok(1));
+    assert_eq!(kdtree.nearest(&[0, 0], 1, &squared_euclidean).unwrap()[0].0, 1_000_000);
+}
+
+#[test]
+fn it_keeps_extreme_values() {
+    let mut kdtree: KdTree<i32, char, [i32; 1]> = KdTree::with_capacity(1, 1);
+    kdtree.add([i32::MIN], 'a').unwrap();
+    kdtree.add([i32::MAX], 'b').unwrap();
+    kdtree.add([i32::MAX - 1], 'c').unwrap();
+    let points: Vec<i32> = kdtree.iter().map(|(point, _)| point[0]).collect();
+    assert!(points.contains(&i32::MIN));
+    assert!(points.contains(&i32::MAX));
+    let mut found = kdtree.within_box(&[0], &[i32::MAX]).unwrap();
+    found.sort_by_key(|entry| *entry.1);
+    assert_eq!(found, vec![(&[i32::MAX], &'b'), (&[i32::MAX - 1], &'c')]);
+}
+
+#[test]
+fn it_checks_integer_points() {
+    let mut kdtree: KdTree<i64, u8, Vec<i64>> = KdTree::new_infer();
+    kdtree.add(vec![1, 2, 3], 0).unwrap();
+    assert_eq!(kdtree.add(vec![1, 2], 1), Err(ErrorKind::WrongDimension));
+    assert_eq!(
+        kdtree.nearest(&[1, 2], 1, &squared_euclidean),
+        Err(ErrorKind::WrongDimension)
+    );
+}
+
+/// Hundredths, stored as an integer.
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+struct Centi(i64);
+
+impl Add for Centi {
+    type Output = Centi;
+    fn add(self, other: Centi) -> Centi {
+        Centi(self.0 + other.0)
+    }
+}
+
+impl Sub for Centi {
+    type Output = Centi;
+    fn sub(self, other: Centi) -> Centi {
+        Centi(self.0 - other.0)
+    }
+}
+
+impl Mul for Centi {
+    type Output = Centi;
+    fn mul(self, other: Centi) -> Centi {
+        Centi(self.0 * other.0 / 100)
+    }
+}
+
+impl Div for Centi {
+    type Output = Centi;
+    fn div(self, other: Centi) -> Centi {
+        Centi(self.0 * 100 / other.0)
+    }
+}
+
+impl Rem for Centi {
+    type Output = Centi;
+    fn rem(self, other: Centi) -> Centi {
+        Centi(self.0 % other.0)
+    }
+}
+
+impl Neg for Centi {
+    type Output = Centi;
+    fn neg(self) -> Centi {
+        Centi(-self.0)
+    }
+}
+
+impl Zero for Centi {
+    fn zero() -> Centi {
+        Centi(0)
+    }
+    fn is_zero(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl One for Centi {
+    fn one() -> Centi {
+        Centi(100)
+    }
+}
+
+impl Num for Centi {
+    type FromStrRadixErr = ();
+    fn from_str_radix(_: &str, _: u32) -> Result<Centi, ()> {
+        Err(())
+    }
+}
+
+impl ToPrimitive for Centi {
+    fn to_i64(&self) -> Option<i64> {
+        Some(self.0 / 100)
+    }
+    fn to_u64(&self) -> Option<u64> {
+        self.to_i64().and_then(|x| x.to_u64())
+    }
+    fn to_f64(&self) -> Option<f64> {
+        Some(self.0 as f64 / 100.0)
+    }
+}
+
+impl NumCast for Centi {
+    fn from<N: ToPrimitive>(n: N) -> Option<Centi> {
+        let x = n.to_f64()? * 100.0;
+        if x.is_finite() && x.abs() < i64::MAX as f64 {
+            Some(Centi(x.round() as i64))
+        } else {
+            None
+        }
+    }
+}
+
+#[test]
+fn it_accepts_fixed_point_coordinates() {
+    let mut kdtree: KdTree<Centi, &str, [Centi; 2]> = KdTree::with_capacity(2, 1);
+    kdtree.add([Centi(150), Centi(-25)], "a").unwrap();
+    kdtree.add([Centi(300), Centi(300)], "b").unwrap();
+    kdtree.add([Centi(-300), Centi(300)], "c").unwrap();
+    assert_eq!(
+        kdtree
+            .nearest(&[Centi(100), Centi(-25)], 1, &squared_euclidean)
+            .unwrap(),
+        vec![(Centi(25), &"a")]
+    );
+    assert_eq!(
+        kdtree
+            .within_box(&[Centi(0), Centi(-100)], &[Centi(200), Centi(0)])
+            .unwrap(),
+        vec![(&[Centi(150), Centi(-25)], &"a")]
+    );
+}
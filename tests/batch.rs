@@ -0,0 +1,104 @@
+extern crate kdtree;
+
+use kdtree::distance::squared_euclidean;
+#[cfg(feature = "rayon")]
+use kdtree::ResultOrder;
+use kdtree::{ErrorKind, KdTree};
+
+fn line() -> KdTree<f64, usize, [f64; 2]> {
+    let mut kdtree = KdTree::with_capacity(2, 2);
+    for i in 0..20 {
+        kdtree.add([i as f64, 0.0], i).unwrap();
+    }
+    kdtree
+}
+
+#[test]
+fn it_answers_queries_in_order() {
+    let kdtree = line();
+    let queries = [[3.2, 1.0], [15.9, 0.0], [-4.0, 0.0]];
+
+    let found = kdtree.nearest_batch(&queries, 2, &squared_euclidean).unwrap();
+    assert_eq!(found.len(), 3);
+    for (query, nearest) in queries.iter().zip(&found) {
+        assert_eq!(nearest, &kdtree.nearest(query, 2, &squared_euclidean).unwrap());
+    }
+    assert_eq!(*found[1][0].1, 16);
+}
+
+#[test]
+fn it_rejects_batches_with_invalid_queries() {
+    let kdtree = line();
+    let queries = vec![vec![0.0, 0.0], vec![1.0]];
+    assert_eq!(
+        kdtree.nearest_batch(&queries, 1, &squared_euclidean),
+        Err(ErrorKind::WrongDimension)
+    );
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn it_hands_out_parallel_results_in_the_requested_order() {
+    let kdtree = line();
+    let queries: Vec<[f64; 2]> = (0..100).map(|i| [i as f64 * 0.2, 0.0]).collect();
+    let expected = kdtree.nearest_batch(&queries, 3, &squared_euclidean).unwrap();
+
+    let mut preserved = vec![];
+    kdtree
+        .par_nearest_batch(&queries, 3, &squared_euclidean, ResultOrder::Preserved, |i, nearest| {
+            preserved.push((i, nearest))
+        })
+        .unwrap();
+    assert_eq!(
+        preserved.iter().map(|r| r.0).collect::<Vec<_>>(),
+        (0..100).collect::<Vec<_>>()
+    );
+    assert_eq!(preserved.into_iter().map(|r| r.1).collect::<Vec<_>>(), expected);
+
+    let mut unordered = vec![];
+    kdtree
+        .par_nearest_batch(&queries, 3, &squared_euclidean, ResultOrder::Unordered, |i, nearest| {
+            unordered.push((i, nearest))
+        })
+        .unwrap();
+    unordered.sort_by_key(|r| r.0);
+    assert_eq!(unordered.into_iter().map(|r| r.1).collect::<Vec<_>>(), expected);
+}
+
+#[test]
+fn it_answers_radius_queries_in_order() {
+    let kdtree = line();
+    let queries = [[3.0, 0.0], [30.0, 0.0], [18.5, 0.0]];
+
+    let found = kdtree.within_batch(&queries, 1.0, &squared_euclidean).unwrap();
+    let data: Vec<Vec<usize>> = found
+        .iter()
+        .map(|points| {
+            let mut data: Vec<usize> = points.iter().map(|&(_, &i)| i).collect();
+            data.sort_unstable();
+            data
+        })
+        .collect();
+    assert_eq!(data, vec![vec![2, 3, 4], vec![], vec![18, 19]]);
+    assert_eq!(found[0][0], (0.0, &3));
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn it_answers_radius_queries_in_parallel() {
+    let kdtree = line();
+    let queries: Vec<[f64; 2]> = (0..50).map(|i| [i as f64 * 0.4, 0.5]).collect();
+    let expected = kdtree.within_batch(&queries, 2.0, &squared_euclidean).unwrap();
+
+    let mut found = vec![];
+    kdtree
+        .par_within_batch(
+            &queries,
+            2.0,
+            &squared_euclidean,
+            ResultOrder::Preserved,
+            |i, points| found.push((i, points)),
+        )
+        .unwrap();
+    assert_eq!(found.into_iter().map(|r| r.1).collect::<Vec<_>>(), expected);
+}
@@ -0,0 +1,59 @@
+extern crate kdtree;
+
+use kdtree::distance::squared_euclidean;
+use kdtree::{ErrorKind, KdTree, KdTreeBuilder};
+
+#[test]
+fn it_builds_trees_of_fixed_dimensions() {
+    let kdtree: KdTree<f64, usize, [f64; 3]> = KdTreeBuilder::new().capacity(2).dimensions::<3>().build().unwrap();
+    assert_eq!(kdtree.dimensions(), 3);
+    assert_eq!(kdtree.stats().capacity, 2);
+
+    let kdtree = KdTreeBuilder::new()
+        .dimensions::<2>()
+        .bulk_load(vec![([0.0, 0.0], 'a'), ([1.0, 1.0], 'b')])
+        .unwrap();
+    assert_eq!(
+        kdtree.nearest(&[0.75, 0.75], 1, &squared_euclidean).unwrap(),
+        vec![(0.125, &'b')]
+    );
+}
+
+#[test]
+fn it_rejects_invalid_configurations() {
+    let zero_capacity = KdTreeBuilder::new().dimensions::<2>().capacity(0).build::<f64, usize>();
+    assert_eq!(zero_capacity.unwrap_err(), ErrorKind::ZeroCapacity);
+    let zero_dimensions = KdTreeBuilder::new().dimensions::<0>().build::<f64, usize>();
+    assert_eq!(zero_dimensions.unwrap_err(), ErrorKind::WrongDimension);
+}
+
+#[test]
+fn it_configures_the_leaf_limit() {
+    let mut kdtree = KdTreeBuilder::new()
+        .dimensions::<1>()
+        .capacity(1)
+        .leaf_limit(2)
+        .strict(true)
+        .build()
+        .unwrap();
+    kdtree.add([0.0], 'a').unwrap();
+    kdtree.add([0.0], 'b').unwrap();
+    assert_eq!(kdtree.add([0.0], 'c'), Err(ErrorKind::LeafLimitExceeded(2)));
+    assert_eq!(kdtree.stats().leaf_limit, Some(2));
+}
+
+#[test]
+fn it_configures_the_leaf_limit_of_jittered_trees() {
+    // without jitter, duplicates stay in one leaf
+    let mut kdtree = KdTreeBuilder::new()
+        .dimensions::<1>()
+        .capacity(1)
+        .leaf_limit(2)
+        .strict(true)
+        .jittered(0.0, 7)
+        .unwrap();
+    kdtree.add([0.0], 'a').unwrap();
+    kdtree.add([0.0], 'b').unwrap();
+    assert_eq!(kdtree.add([0.0], 'c'), Err(ErrorKind::LeafLimitExceeded(2)));
+    assert_eq!(kdtree.tree().stats().leaf_limit, Some(2));
+}
@@ -0,0 +1,47 @@
+extern crate kdtree;
+extern crate rand;
+
+use kdtree::distance::squared_euclidean;
+use kdtree::{ErrorKind, KdTree};
+
+#[test]
+fn it_matches_a_brute_force_chain() {
+    let points: Vec<[f64; 2]> = (0..400).map(|_| rand::random()).collect();
+    let mut kdtree = KdTree::with_capacity(2, 4);
+    for (i, point) in points.iter().enumerate() {
+        kdtree.add(*point, i).unwrap();
+    }
+    let indexed: Vec<[f64; 2]> = kdtree.iter().map(|(point, _)| *point).collect();
+
+    let chain = kdtree.nearest_chain(&[0.5, 0.5], 400, &squared_euclidean).unwrap();
+    assert_eq!(chain.len(), 400);
+
+    let mut visited = vec![false; 400];
+    let mut current = [0.5, 0.5];
+    for &(d, index) in &chain {
+        let nearest = (0..400)
+            .filter(|&i| !visited[i])
+            .map(|i| squared_euclidean(&current, &indexed[i]))
+            .fold(f64::INFINITY, f64::min);
+        assert_eq!(d, nearest);
+        assert!(!visited[index]);
+        visited[index] = true;
+        current = indexed[index];
+    }
+}
+
+#[test]
+fn it_stops_after_the_requested_hops() {
+    let mut kdtree = KdTree::new(1);
+    for x in 0..10 {
+        kdtree.add([(x * x) as f64], x).unwrap();
+    }
+    let chain = kdtree.nearest_chain(&[3.2], 3, &squared_euclidean).unwrap();
+    let data: Vec<i32> = chain.iter().map(|&(_, i)| *kdtree.iter().nth(i).unwrap().1).collect();
+    assert_eq!(data, vec![2, 1, 0]);
+    assert_eq!(kdtree.nearest_chain(&[0.0], 50, &squared_euclidean).unwrap().len(), 10);
+    assert_eq!(
+        kdtree.nearest_chain(&[0.0, 0.0], 1, &squared_euclidean),
+        Err(ErrorKind::WrongDimension)
+    );
+}
@@ -0,0 +1,43 @@
+extern crate kdtree;
+
+use kdtree::distance::squared_euclidean;
+use kdtree::{ErrorKind, KdTree};
+
+fn tree() -> KdTree<f64, String, [f64; 2]> {
+    let mut kdtree = KdTree::with_capacity(2, 2);
+    for i in 0..30 {
+        kdtree
+            .add([(i * 7 % 11) as f64, (i * 3 % 13) as f64], i.to_string())
+            .unwrap();
+    }
+    kdtree
+}
+
+#[test]
+fn it_splits_into_aligned_columns() {
+    let kdtree = tree();
+    let (points, payloads) = kdtree.to_columns();
+    assert_eq!(points.len(), 30);
+    let entries: Vec<(&[f64; 2], &String)> = points.iter().zip(&payloads).collect();
+    assert!(entries.into_iter().eq(kdtree.iter()));
+    assert_eq!(kdtree.clone().into_columns(), (points, payloads));
+}
+
+#[test]
+fn it_builds_from_columns() {
+    let kdtree = tree();
+    let (points, payloads) = kdtree.to_columns();
+    let rebuilt = KdTree::from_columns(points.clone(), payloads.clone()).unwrap();
+    assert_eq!(rebuilt.size(), 30);
+    // ties may come in another order
+    let distances = |kdtree: &KdTree<f64, String, [f64; 2]>| -> Vec<f64> {
+        let found = kdtree.nearest(&[4.0, 4.0], 5, &squared_euclidean).unwrap();
+        found.into_iter().map(|(d, _)| d).collect()
+    };
+    assert_eq!(distances(&rebuilt), distances(&kdtree));
+
+    assert_eq!(
+        KdTree::from_columns(points, payloads[1..].to_vec()).map(|tree| tree.size()),
+        Err(ErrorKind::ColumnLengthMismatch)
+    );
+}
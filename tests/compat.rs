@@ -0,0 +1,61 @@
+#![cfg(feature = "serialize")]
+
+extern crate kdtree;
+extern crate serde_json;
+
+use kdtree::compat::from_upstream;
+use kdtree::distance::squared_euclidean;
+use kdtree::KdTree;
+use serde_json::Value;
+
+/// Serialize `kdtree` in the representation of the upstream crate, which lacks the node IDs,
+/// change log and limits.
+fn upstream_json(kdtree: &KdTree<f64, usize, [f64; 2]>) -> String {
+    fn strip(node: &mut Value) {
+        if let Value::Object(fields) = node {
+            for field in &["unsplittable", "id", "changes", "leaf_limit", "strict"] {
+                fields.remove(*field);
+            }
+            for child in &["left", "right"] {
+                strip(fields.get_mut(*child).unwrap());
+            }
+        }
+    }
+    let mut value = serde_json::to_value(kdtree).unwrap();
+    strip(&mut value);
+    value.to_string()
+}
+
+#[test]
+fn it_loads_upstream_trees() {
+    let mut kdtree = KdTree::with_capacity(2, 2);
+    for i in 0..40 {
+        kdtree.add([(i * 7 % 11) as f64, (i * 3 % 13) as f64], i).unwrap();
+    }
+    let json = upstream_json(&kdtree);
+    assert!(!json.contains("\"id\""));
+
+    let mut deserializer = serde_json::Deserializer::from_str(&json);
+    let mut restored: KdTree<f64, usize, [f64; 2]> = from_upstream(&mut deserializer).unwrap();
+    assert!(restored.iter().eq(kdtree.iter()));
+    // same splits, with node IDs given depth first
+    let planes = |kdtree: &KdTree<f64, usize, [f64; 2]>| {
+        let mut planes = kdtree.split_planes();
+        for plane in &mut planes {
+            plane.id = 0;
+        }
+        planes
+    };
+    assert_eq!(planes(&restored), planes(&kdtree));
+    assert_eq!(restored.generation(), kdtree.split_planes().len() as u64);
+    assert_eq!(
+        restored.nearest(&[5.0, 5.0], 4, &squared_euclidean).unwrap(),
+        kdtree.nearest(&[5.0, 5.0], 4, &squared_euclidean).unwrap()
+    );
+
+    for i in 40..60 {
+        kdtree.add([i as f64, 0.5], i).unwrap();
+        restored.add([i as f64, 0.5], i).unwrap();
+    }
+    assert_eq!(restored.stats(), kdtree.stats());
+}
@@ -0,0 +1,59 @@
+extern crate kdtree;
+extern crate rand;
+
+use kdtree::distance::squared_euclidean;
+use kdtree::{ErrorKind, KdTree};
+
+fn angle(a: &[f64], b: &[f64]) -> f64 {
+    let dot: f64 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let length = |v: &[f64]| v.iter().map(|x| x * x).sum::<f64>().sqrt();
+    (dot / (length(a) * length(b))).clamp(-1.0, 1.0).acos()
+}
+
+#[test]
+fn it_matches_brute_force() {
+    let points: Vec<([f64; 3], usize)> = (0..600).map(|i| (rand::random(), i)).collect();
+    let kdtree = KdTree::bulk_load(3, 4, points.clone()).unwrap();
+    let origin = [0.5, 0.5, 0.5];
+    for &(direction, half_angle) in &[
+        ([1.0, 0.0, 0.0], 0.3),
+        ([-1.0, 2.0, 0.5], 0.1),
+        ([0.0, 0.0, -3.0], 1.2),
+        ([1.0, 1.0, 1.0], 3.0),
+    ] {
+        let mut expected: Vec<f64> = points
+            .iter()
+            .filter(|(p, _)| {
+                let offset: Vec<f64> = p.iter().zip(&origin).map(|(x, o)| x - o).collect();
+                angle(&offset, &direction) <= half_angle
+            })
+            .map(|(p, _)| squared_euclidean(&origin, p))
+            .collect();
+        expected.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        expected.truncate(6);
+        let found = kdtree.nearest_in_cone(&origin, &direction, half_angle, 6).unwrap();
+        assert_eq!(found.iter().map(|r| r.0).collect::<Vec<_>>(), expected);
+    }
+}
+
+#[test]
+fn it_looks_along_the_direction_only() {
+    let mut kdtree = KdTree::new(2);
+    kdtree.add([1.0, 0.0], "ahead").unwrap();
+    kdtree.add([-0.5, 0.0], "behind").unwrap();
+    kdtree.add([2.0, 1.9], "aside").unwrap();
+    kdtree.add([0.0, 0.0], "here").unwrap();
+    let quarter = std::f64::consts::FRAC_PI_4;
+    assert_eq!(
+        kdtree.nearest_in_cone(&[0.0, 0.0], &[1.0, 0.0], quarter, 5).unwrap(),
+        vec![(1.0, &"ahead"), (4.0 + 1.9 * 1.9, &"aside")]
+    );
+    assert_eq!(
+        kdtree.nearest_in_cone(&[0.0, 0.0], &[0.0, 0.0], quarter, 1),
+        Err(ErrorKind::InvalidCone)
+    );
+    assert_eq!(
+        kdtree.nearest_in_cone(&[0.0, 0.0], &[1.0, 0.0], -0.1, 1),
+        Err(ErrorKind::InvalidCone)
+    );
+}
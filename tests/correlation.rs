@@ -0,0 +1,80 @@
+extern crate kdtree;
+extern crate rand;
+
+use kdtree::{ErrorKind, KdTree};
+
+fn random_tree(size: usize) -> KdTree<f64, usize, [f64; 2]> {
+    let points = (0..size)
+        .map(|i| ([rand::random::<f64>(), rand::random::<f64>()], i))
+        .collect();
+    KdTree::bulk_load(2, 4, points).unwrap()
+}
+
+fn brute_force(a: &[[f64; 2]], b: &[[f64; 2]], edges: &[f64], distinct: bool) -> Vec<usize> {
+    let mut counts = vec![0; edges.len() - 1];
+    for (i, p) in a.iter().enumerate() {
+        for (j, q) in b.iter().enumerate() {
+            if distinct && j <= i {
+                continue;
+            }
+            let d = ((p[0] - q[0]).powi(2) + (p[1] - q[1]).powi(2)).sqrt();
+            if let Some(k) = (0..counts.len()).find(|&k| edges[k] <= d && d < edges[k + 1]) {
+                counts[k] += 1;
+            }
+        }
+    }
+    counts
+}
+
+#[test]
+fn it_counts_pairs_per_bin() {
+    let edges = [0.0, 0.05, 0.1, 0.2, 0.4, 0.8];
+    let a = random_tree(300);
+    let b = random_tree(200);
+    let a_points: Vec<[f64; 2]> = a.iter().map(|(p, _)| *p).collect();
+    let b_points: Vec<[f64; 2]> = b.iter().map(|(p, _)| *p).collect();
+
+    assert_eq!(
+        a.distance_histogram(&edges).unwrap(),
+        brute_force(&a_points, &a_points, &edges, true)
+    );
+    assert_eq!(
+        a.cross_distance_histogram(&b, &edges).unwrap(),
+        brute_force(&a_points, &b_points, &edges, false)
+    );
+}
+
+#[test]
+fn it_counts_duplicates_at_distance_zero() {
+    let mut kdtree = KdTree::new(1);
+    for data in 0..3 {
+        kdtree.add([1f64], data).unwrap();
+    }
+    kdtree.add([3f64], 3).unwrap();
+
+    assert_eq!(kdtree.distance_histogram(&[0.0, 1.0, 2.0, 3.0]), Ok(vec![3, 0, 3]));
+    assert_eq!(kdtree.distance_histogram(&[0.5, 2.5]), Ok(vec![3]));
+
+    let other: KdTree<f64, i32, [f64; 2]> = KdTree::new(2);
+    assert_eq!(kdtree.cross_distance_histogram(&other, &[0.0, 1.0]), Ok(vec![0]));
+    let mut other = other;
+    other.add([0.0, 0.0], 0).unwrap();
+    assert!(kdtree.cross_distance_histogram(&other, &[0.0, 1.0]).is_err());
+}
+
+#[test]
+fn it_rejects_invalid_bin_edges() {
+    let mut kdtree = KdTree::new(1);
+    kdtree.add([1f64], 0).unwrap();
+
+    assert_eq!(kdtree.distance_histogram(&[1.0, 0.5]), Err(ErrorKind::InvalidBinEdges));
+    assert_eq!(kdtree.distance_histogram(&[-1.0, 0.5]), Err(ErrorKind::InvalidBinEdges));
+    assert_eq!(
+        kdtree.distance_histogram(&[0.0, f64::NAN]),
+        Err(ErrorKind::InvalidBinEdges)
+    );
+    assert_eq!(
+        kdtree.cross_distance_histogram(&kdtree, &[1.0, 0.5]),
+        Err(ErrorKind::InvalidBinEdges)
+    );
+}
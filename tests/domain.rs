@@ -0,0 +1,61 @@
+extern crate kdtree;
+
+use kdtree::distance::squared_euclidean;
+use kdtree::{Aabb, DomainKdTree, ErrorKind, OutOfDomain};
+
+fn lon_lat() -> Aabb<f64> {
+    Aabb {
+        min: vec![-180.0, -90.0],
+        max: vec![180.0, 90.0],
+    }
+}
+
+#[test]
+fn it_rejects_points_outside_of_the_domain() {
+    let mut tree = DomainKdTree::new(lon_lat(), OutOfDomain::Reject).unwrap();
+    tree.add([13.4, 52.5], "berlin").unwrap();
+    assert_eq!(tree.add([13.4, 952.5], "glitch"), Err(ErrorKind::OutOfDomain));
+    assert_eq!(tree.add([f64::NAN, 0.0], "nan"), Err(ErrorKind::NonFiniteCoordinate));
+    assert_eq!(tree.size(), 1);
+    assert_eq!(tree.tree().iter().next().unwrap().0, &[13.4, 52.5]);
+    assert_eq!(tree.remove(&[13.4, 952.5], &"glitch"), Ok(0));
+}
+
+#[test]
+fn it_clamps_points_and_optionally_queries() {
+    let mut tree = DomainKdTree::new(lon_lat(), OutOfDomain::Clamp).unwrap();
+    tree.add([13.4, 52.5], "berlin").unwrap();
+    tree.add([200.0, -100.0], "glitch").unwrap();
+    assert_eq!(tree.add([f64::NAN, 0.0], "nan"), Err(ErrorKind::NonFiniteCoordinate));
+    assert_eq!(
+        tree.nearest(&[180.0, -90.0], 1, &squared_euclidean).unwrap(),
+        vec![(0.0, &"glitch")]
+    );
+    assert_eq!(tree.within(&[190.0, -90.0], 1.0, &squared_euclidean).unwrap(), vec![]);
+
+    let tree = tree.clamp_queries(true);
+    assert_eq!(
+        tree.within(&[190.0, -90.0], 1.0, &squared_euclidean).unwrap(),
+        vec![(0.0, &"glitch")]
+    );
+    let mut tree = tree;
+    assert_eq!(tree.remove(&[200.0, -100.0], &"glitch"), Ok(1));
+    assert_eq!(tree.size(), 1);
+}
+
+#[test]
+fn it_validates_the_domain() {
+    let inverted = Aabb {
+        min: vec![1.0],
+        max: vec![0.0],
+    };
+    assert!(DomainKdTree::<f64, (), [f64; 1]>::new(inverted, OutOfDomain::Reject).is_err());
+    let mismatched = Aabb {
+        min: vec![0.0, 0.0],
+        max: vec![1.0],
+    };
+    assert_eq!(
+        DomainKdTree::<f64, (), Vec<f64>>::new(mismatched, OutOfDomain::Clamp).err(),
+        Some(ErrorKind::InvalidDomain)
+    );
+}
@@ -0,0 +1,95 @@
+extern crate kdtree;
+extern crate rand;
+
+use kdtree::{ErrorKind, KdTree};
+
+type Points = Vec<([f64; 2], usize)>;
+
+fn tree() -> (Points, KdTree<f64, usize, [f64; 2]>) {
+    let points: Points = (0..800).map(|i| (rand::random(), i)).collect();
+    let kdtree = KdTree::bulk_load(2, 4, points.clone()).unwrap();
+    (points, kdtree)
+}
+
+fn sorted(mut found: Vec<usize>) -> Vec<usize> {
+    found.sort_unstable();
+    found
+}
+
+#[test]
+fn it_finds_points_in_axis_aligned_ellipsoids() {
+    let (points, kdtree) = tree();
+    let center = [0.4, 0.6];
+    let radii = [0.3, 0.05];
+    let inside = |p: &[f64; 2]| ((p[0] - center[0]) / radii[0]).powi(2) + ((p[1] - center[1]) / radii[1]).powi(2);
+    let found = kdtree.within_ellipsoid(&center, &radii).unwrap();
+    assert!(found.windows(2).all(|w| w[0].0 <= w[1].0));
+    assert!(found.iter().all(|&(d, &i)| (d - inside(&points[i].0)).abs() < 1e-12));
+    assert_eq!(
+        sorted(found.iter().map(|&(_, &i)| i).collect()),
+        points
+            .iter()
+            .filter(|(p, _)| inside(p) <= 1.0)
+            .map(|&(_, i)| i)
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        kdtree.within_ellipsoid(&center, &[0.3, 0.0]),
+        Err(ErrorKind::InvalidEllipsoid)
+    );
+    assert_eq!(
+        kdtree.within_ellipsoid(&center, &[0.3, f64::INFINITY]),
+        Err(ErrorKind::InvalidEllipsoid)
+    );
+    assert_eq!(
+        kdtree.within_ellipsoid(&center, &[0.3, f64::NAN]),
+        Err(ErrorKind::InvalidEllipsoid)
+    );
+}
+
+#[test]
+fn it_finds_points_in_rotated_ellipsoids() {
+    let (points, kdtree) = tree();
+    let center = [0.5, 0.5];
+    // elongated along the diagonal
+    let covariance = [0.02, 0.018, 0.018, 0.02];
+    let gate = 2.0;
+    let mahalanobis = |p: &[f64; 2]| {
+        let (x, y) = (p[0] - center[0], p[1] - center[1]);
+        let det = 0.02 * 0.02 - 0.018 * 0.018;
+        (0.02 * x * x - 2.0 * 0.018 * x * y + 0.02 * y * y) / det
+    };
+    let found = kdtree.within_ellipsoid_matrix(&center, &covariance, gate).unwrap();
+    assert!(found.windows(2).all(|w| w[0].0 <= w[1].0));
+    assert!(found
+        .iter()
+        .all(|&(d, &i)| (d - mahalanobis(&points[i].0)).abs() < 1e-9));
+    assert_eq!(
+        sorted(found.iter().map(|&(_, &i)| i).collect()),
+        points
+            .iter()
+            .filter(|(p, _)| mahalanobis(p) <= gate)
+            .map(|&(_, i)| i)
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn it_rejects_invalid_matrices() {
+    let (_, kdtree) = tree();
+    let center = [0.5, 0.5];
+    assert_eq!(
+        kdtree.within_ellipsoid_matrix(&center, &[1.0, 0.0, 0.0], 1.0),
+        Err(ErrorKind::WrongDimension)
+    );
+    for covariance in &[[1.0, 2.0, 2.0, 1.0], [1.0, 0.5, 0.0, 1.0], [0.0, 0.0, 0.0, 1.0]] {
+        assert_eq!(
+            kdtree.within_ellipsoid_matrix(&center, covariance, 1.0),
+            Err(ErrorKind::InvalidEllipsoid)
+        );
+    }
+    assert_eq!(
+        kdtree.within_ellipsoid_matrix(&center, &[1.0, 0.0, 0.0, 1.0], -1.0),
+        Err(ErrorKind::InvalidEllipsoid)
+    );
+}
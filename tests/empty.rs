@@ -0,0 +1,120 @@
+extern crate kdtree;
+extern crate rand;
+
+use kdtree::distance::squared_euclidean;
+use kdtree::{ErrorKind, KdTree};
+
+#[test]
+fn it_measures_the_gap_around_a_point() {
+    let mut kdtree = KdTree::new(2);
+    assert_eq!(kdtree.largest_empty_radius(&[0.0, 0.0], &squared_euclidean), Ok(None));
+    kdtree.add([3.0, 4.0], 'a').unwrap();
+    kdtree.add([10.0, 0.0], 'b').unwrap();
+    assert_eq!(
+        kdtree.largest_empty_radius(&[0.0, 0.0], &squared_euclidean),
+        Ok(Some(25.0))
+    );
+}
+
+#[test]
+fn it_finds_a_large_empty_ball() {
+    // a grid with its upper right quarter missing
+    let mut kdtree = KdTree::with_capacity(2, 4);
+    for x in 0..=10 {
+        for y in 0..=10 {
+            if x <= 5 || y <= 5 {
+                kdtree.add([x as f64, y as f64], (x, y)).unwrap();
+            }
+        }
+    }
+    let (center, radius) = kdtree
+        .largest_empty_ball_within(&[0.0, 0.0], &[10.0, 10.0], &squared_euclidean)
+        .unwrap()
+        .unwrap();
+    assert_eq!(center, vec![10.0, 10.0]);
+    assert_eq!(radius, 25.0);
+    assert_eq!(
+        kdtree.largest_empty_radius(&center, &squared_euclidean),
+        Ok(Some(radius))
+    );
+
+    // the center stays within the box
+    let (center, _) = kdtree
+        .largest_empty_ball_within(&[0.0, 0.0], &[4.0, 4.0], &squared_euclidean)
+        .unwrap()
+        .unwrap();
+    assert!(center.iter().all(|&x| (0.0..=4.0).contains(&x)));
+}
+
+#[test]
+fn it_finds_an_empty_ball_in_random_points() {
+    let points: Vec<([f64; 2], usize)> = (0..300).map(|i| (rand::random(), i)).collect();
+    let kdtree = KdTree::bulk_load(2, 8, points.clone()).unwrap();
+    let (center, radius) = kdtree
+        .largest_empty_ball_within(&[0.0, 0.0], &[1.0, 1.0], &squared_euclidean)
+        .unwrap()
+        .unwrap();
+    assert!(points.iter().all(|(p, _)| squared_euclidean(&center, p) >= radius));
+    assert!(radius > 0.0);
+
+    let none = kdtree.largest_empty_ball_within(&[1.0, 0.0], &[0.0, 1.0], &squared_euclidean);
+    assert_eq!(none, Ok(None));
+}
+
+#[test]
+fn it_maps_coverage_gaps() {
+    // sensors along the left and bottom edge of a 16 by 16 square
+    let mut kdtree = KdTree::with_capacity(2, 4);
+    for i in 0..=16 {
+        kdtree.add([0.0, i as f64], i).unwrap();
+        kdtree.add([i as f64, 0.0], i + 100).unwrap();
+    }
+    let radius = 4.0 * 4.0;
+    let gaps = kdtree
+        .coverage_gaps(&[0.0, 0.0], &[16.0, 16.0], 4, radius, &squared_euclidean)
+        .unwrap();
+
+    // brute force over the 16 by 16 grid of unit cells
+    let mut expected = vec![];
+    for x in 0..16 {
+        for y in 0..16 {
+            let center = [x as f64 + 0.5, y as f64 + 0.5];
+            let nearest = kdtree.nearest(&center, 1, &squared_euclidean).unwrap()[0].0;
+            if nearest > radius {
+                expected.push((x, y));
+            }
+        }
+    }
+    let mut found: Vec<(i32, i32)> = gaps
+        .iter()
+        .map(|cell| {
+            assert_eq!((cell.max[0] - cell.min[0], cell.max[1] - cell.min[1]), (1.0, 1.0));
+            (cell.min[0] as i32, cell.min[1] as i32)
+        })
+        .collect();
+    found.sort_unstable();
+    assert_eq!(found, expected);
+    assert!(!found.contains(&(3, 10)));
+    assert!(found.contains(&(4, 10)));
+
+    let empty: KdTree<f64, usize, [f64; 2]> = KdTree::new(2);
+    let all = empty
+        .coverage_gaps(&[0.0, 0.0], &[1.0, 1.0], 2, 1.0, &squared_euclidean)
+        .unwrap();
+    assert_eq!(all.len(), 16);
+}
+
+#[test]
+fn it_rejects_too_many_dimensions() {
+    let mut kdtree = KdTree::new(64);
+    kdtree.add(vec![0.0; 64], ()).unwrap();
+    let (min, max) = (vec![0.0; 64], vec![1.0; 64]);
+    assert_eq!(
+        kdtree.largest_empty_ball_within(&min, &max, &squared_euclidean),
+        Err(ErrorKind::TooManyDimensions(16))
+    );
+    assert_eq!(
+        kdtree.coverage_gaps(&min, &max, 2, 0.5, &squared_euclidean),
+        Err(ErrorKind::TooManyDimensions(16))
+    );
+}
@@ -0,0 +1,33 @@
+extern crate kdtree;
+
+use kdtree::distance::squared_euclidean;
+use kdtree::{ErrorKind, KdTree};
+
+#[test]
+fn it_estimates_counts_within_guaranteed_bounds() {
+    let mut kdtree = KdTree::with_capacity(2, 4);
+    for x in 0..20 {
+        for y in 0..20 {
+            kdtree.add([f64::from(x), f64::from(y)], (x, y)).unwrap();
+        }
+    }
+    for &(center, radius) in &[([10.0, 10.0], 5.0), ([0.0, 0.0], 3.5), ([19.5, 7.25], 8.0)] {
+        let exact = kdtree
+            .within(&center, radius * radius, &squared_euclidean)
+            .unwrap()
+            .len();
+        let estimate = kdtree.estimate_count_within(&center, radius).unwrap();
+        assert!(estimate.min <= exact && exact <= estimate.max);
+        assert!(estimate.min as f64 <= estimate.estimate && estimate.estimate <= estimate.max as f64);
+        assert!((estimate.estimate - exact as f64).abs() < 0.25 * exact as f64);
+    }
+
+    let everything = kdtree.estimate_count_within(&[10.0, 10.0], 100.0).unwrap();
+    assert_eq!((everything.estimate, everything.min, everything.max), (400.0, 400, 400));
+    let nothing = kdtree.estimate_count_within(&[100.0, 100.0], 1.0).unwrap();
+    assert_eq!((nothing.estimate, nothing.min, nothing.max), (0.0, 0, 0));
+    assert_eq!(
+        kdtree.estimate_count_within(&[0.0], 1.0),
+        Err(ErrorKind::WrongDimension)
+    );
+}
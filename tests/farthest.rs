@@ -0,0 +1,35 @@
+extern crate kdtree;
+extern crate rand;
+
+use kdtree::distance::{manhattan, squared_euclidean};
+use kdtree::{ErrorKind, KdTree};
+
+#[test]
+fn it_matches_brute_force() {
+    let points: Vec<([f64; 3], usize)> = (0..500).map(|i| (rand::random(), i)).collect();
+    let kdtree = KdTree::bulk_load(3, 4, points.clone()).unwrap();
+    for query in &[[0.5, 0.5, 0.5], [0.0, 0.0, 0.0], [3.0, -1.0, 0.2]] {
+        for distance in &[squared_euclidean as fn(&[f64], &[f64]) -> f64, manhattan] {
+            let mut expected: Vec<f64> = points.iter().map(|(p, _)| distance(query, p)).collect();
+            expected.sort_by(|a, b| b.partial_cmp(a).unwrap());
+            let found = kdtree.farthest(query, 7, distance).unwrap();
+            assert_eq!(found.iter().map(|r| r.0).collect::<Vec<_>>(), expected[..7].to_vec());
+        }
+    }
+}
+
+#[test]
+fn it_handles_small_trees() {
+    let mut kdtree = KdTree::new(1);
+    assert!(kdtree.farthest(&[0.0], 3, &squared_euclidean).unwrap().is_empty());
+    kdtree.add([1.0], 'a').unwrap();
+    kdtree.add([-3.0], 'b').unwrap();
+    assert_eq!(
+        kdtree.farthest(&[0.0], 3, &squared_euclidean).unwrap(),
+        vec![(9.0, &'b'), (1.0, &'a')]
+    );
+    assert_eq!(
+        kdtree.farthest(&[0.0, 0.0], 1, &squared_euclidean),
+        Err(ErrorKind::WrongDimension)
+    );
+}
@@ -0,0 +1,98 @@
+extern crate kdtree;
+
+use kdtree::distance;
+use kdtree::fixed::squared_euclidean;
+use kdtree::{ErrorKind, FixedKdTree, KdTree, KdTreeBuilder};
+
+fn points() -> Vec<([f64; 3], usize)> {
+    (0..100)
+        .map(|i| {
+            let x = i as f64;
+            ([x % 7.0, x % 11.0, x % 13.0], i)
+        })
+        .collect()
+}
+
+#[test]
+fn it_finds_what_the_dynamic_tree_finds() {
+    let mut fixed = FixedKdTree::with_capacity(4);
+    let mut dynamic = KdTree::with_capacity(3, 4);
+    for (point, i) in points() {
+        fixed.add(point, i).unwrap();
+        dynamic.add(point, i).unwrap();
+    }
+    assert_eq!(fixed.size(), 100);
+
+    let query = [3.5, 5.0, 6.5];
+    let nearest = fixed.nearest(&query, 5, &squared_euclidean).unwrap();
+    let expected = dynamic.nearest(&query, 5, &distance::squared_euclidean).unwrap();
+    assert_eq!(
+        nearest.iter().map(|r| r.0).collect::<Vec<_>>(),
+        expected.iter().map(|r| r.0).collect::<Vec<_>>()
+    );
+
+    let mut within: Vec<usize> = fixed
+        .within(&query, 4.0, &squared_euclidean)
+        .unwrap()
+        .into_iter()
+        .map(|(_, &i)| i)
+        .collect();
+    let mut expected: Vec<usize> = dynamic
+        .within(&query, 4.0, &distance::squared_euclidean)
+        .unwrap()
+        .into_iter()
+        .map(|(_, &i)| i)
+        .collect();
+    within.sort_unstable();
+    expected.sort_unstable();
+    assert_eq!(within, expected);
+}
+
+#[test]
+fn it_rejects_non_finite_coordinates() {
+    let mut kdtree: FixedKdTree<f64, usize, 2> = FixedKdTree::new();
+    assert_eq!(kdtree.add([f64::NAN, 0.0], 0), Err(ErrorKind::NonFiniteCoordinate));
+    assert_eq!(
+        kdtree.nearest(&[0.0, f64::INFINITY], 1, &squared_euclidean),
+        Err(ErrorKind::NonFiniteCoordinate)
+    );
+    assert_eq!(
+        FixedKdTree::<f64, usize, 2>::with_capacity(0).add([0.0, 0.0], 0),
+        Err(ErrorKind::ZeroCapacity)
+    );
+    assert_eq!(kdtree.size(), 0);
+}
+
+#[test]
+fn it_removes_points() {
+    let mut kdtree = FixedKdTree::new();
+    for (point, i) in points() {
+        kdtree.add(point, i).unwrap();
+    }
+    assert_eq!(kdtree.remove(&[0.0, 0.0, 0.0], &0), Ok(1));
+    assert_eq!(kdtree.size(), 99);
+    assert_eq!(kdtree.iter().count(), 99);
+    assert_eq!(kdtree.into_inner().size(), 99);
+}
+
+#[test]
+fn it_wraps_trees_of_the_right_dimension_size() {
+    let mut kdtree = KdTreeBuilder::new()
+        .dimensions::<2>()
+        .capacity(2)
+        .leaf_limit(3)
+        .strict(true)
+        .build_fixed()
+        .unwrap();
+    for i in 0..3 {
+        kdtree.add([1.0, 1.0], i).unwrap();
+    }
+    assert_eq!(kdtree.add([1.0, 1.0], 3), Err(ErrorKind::LeafLimitExceeded(3)));
+    assert_eq!(kdtree.nearest(&[0.0, 1.0], 1, &squared_euclidean).unwrap()[0].0, 1.0);
+
+    let wrong: KdTree<f64, usize, [f64; 2]> = KdTree::new(3);
+    assert_eq!(
+        FixedKdTree::from_tree(wrong).map(|tree| tree.size()),
+        Err(ErrorKind::WrongDimension)
+    );
+}
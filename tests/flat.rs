@@ -0,0 +1,145 @@
+extern crate kdtree;
+
+use kdtree::distance::squared_euclidean;
+use kdtree::{ErrorKind, FlatPoint, KdTree, Layout};
+
+#[test]
+fn it_builds_from_row_and_column_major_buffers() {
+    let row_major = [0.0, 0.0, 1.0, 1.0, 2.0, 2.0, 3.0, 3.0];
+    let column_major = [0.0, 1.0, 2.0, 3.0, 0.0, 1.0, 2.0, 3.0];
+    let payloads = vec!['a', 'b', 'c', 'd'];
+
+    for &(coords, layout) in &[(&row_major, Layout::RowMajor), (&column_major, Layout::ColumnMajor)] {
+        let kdtree = KdTree::build_from_flat(coords, layout, 4, 2, 2, payloads.clone()).unwrap();
+        assert_eq!(kdtree.size(), 4);
+        assert_eq!(
+            kdtree.nearest(&[1.0, 1.0], 2, &squared_euclidean).unwrap()[0],
+            (0.0, &'b')
+        );
+        let points: Vec<(Vec<f64>, char)> = kdtree.iter().map(|(p, &d)| (p.as_ref().to_vec(), d)).collect();
+        assert!(points.contains(&(vec![3.0, 3.0], 'd')));
+    }
+}
+
+#[test]
+fn it_rejects_mismatched_buffers() {
+    let coords = [0.0, 0.0, 1.0];
+    assert_eq!(
+        KdTree::build_from_flat(&coords, Layout::RowMajor, 2, 2, 2, vec![0, 1]).unwrap_err(),
+        ErrorKind::WrongDimension
+    );
+    assert_eq!(
+        KdTree::build_from_flat(&coords[..2], Layout::RowMajor, 1, 2, 2, vec![0, 1]).unwrap_err(),
+        ErrorKind::ColumnLengthMismatch
+    );
+    assert_eq!(
+        KdTree::build_from_flat(&[0.0, f64::NAN], Layout::ColumnMajor, 2, 1, 2, vec![0, 1]).unwrap_err(),
+        ErrorKind::NonFiniteCoordinateAt(1)
+    );
+}
+
+#[test]
+fn it_writes_knn_results_into_flat_buffers() {
+    let row_major = [0.0, 0.0, 1.0, 1.0, 2.0, 2.0];
+    let kdtree = KdTree::build_from_flat(&row_major, Layout::RowMajor, 3, 2, 1, vec!['a', 'b', 'c']).unwrap();
+    let index_of = |data: char| kdtree.iter().position(|(_, &d)| d == data).unwrap() as u32;
+
+    let queries = [0.0, 0.0, 2.0, 2.5];
+    let mut distances = [0.0; 4];
+    let mut indices = [0; 4];
+    kdtree
+        .knn_into(&queries, 2, &squared_euclidean, &mut distances, &mut indices)
+        .unwrap();
+    assert_eq!(distances, [0.0, 2.0, 0.25, 3.25]);
+    assert_eq!(indices, [index_of('a'), index_of('b'), index_of('c'), index_of('b')]);
+
+    let mut distances = [0.0; 8];
+    let mut indices = [0; 8];
+    kdtree
+        .knn_into(&queries, 4, &squared_euclidean, &mut distances, &mut indices)
+        .unwrap();
+    assert_eq!(distances[3], f64::INFINITY);
+    assert_eq!(indices[3], u32::MAX);
+
+    let mut short = [0.0; 3];
+    assert_eq!(
+        kdtree.knn_into(&queries, 2, &squared_euclidean, &mut short, &mut [0; 4]),
+        Err(ErrorKind::WrongOutputLength)
+    );
+    assert_eq!(
+        kdtree.knn_into(&queries[..3], 1, &squared_euclidean, &mut [0.0; 1], &mut [0; 1]),
+        Err(ErrorKind::WrongDimension)
+    );
+}
+
+#[test]
+fn it_adds_points_from_components() {
+    let x = [0.0, 1.0, 2.0, 3.0];
+    let y = [0.0, 1.0, 2.0, 3.0];
+    let mut kdtree = KdTree::with_capacity(2, 2);
+    kdtree.add_from_components(&[&x, &y], vec!['a', 'b', 'c', 'd']).unwrap();
+    kdtree.add_from_components(&[&[1.5], &[1.5]], vec!['e']).unwrap();
+    assert_eq!(kdtree.size(), 5);
+    let nearest = kdtree.nearest(&[1.5, 1.75], 2, &squared_euclidean).unwrap();
+    assert_eq!(nearest, vec![(0.0625, &'e'), (0.3125, &'c')]);
+
+    let mut inferred = KdTree::new_infer();
+    inferred.add_from_components(&[&x, &y, &y], vec![0, 1, 2, 3]).unwrap();
+    assert_eq!(inferred.dimensions(), 3);
+}
+
+#[test]
+fn it_rejects_mismatched_components() {
+    let mut kdtree: KdTree<f64, char, Vec<f64>> = KdTree::new(2);
+    let x = [0.0, 1.0];
+    assert_eq!(
+        kdtree.add_from_components(&[&x, &x[..1]], vec!['a', 'b']),
+        Err(ErrorKind::WrongDimension)
+    );
+    assert_eq!(
+        kdtree.add_from_components(&[&x, &x, &x], vec!['a', 'b']),
+        Err(ErrorKind::WrongDimension)
+    );
+    assert_eq!(
+        kdtree.add_from_components(&[&x, &[0.0, f64::NAN]], vec!['a', 'b']),
+        Err(ErrorKind::NonFiniteCoordinateAt(1))
+    );
+    assert_eq!(kdtree.size(), 0);
+}
+
+#[test]
+fn it_adds_no_points_if_one_exceeds_a_strict_leaf_limit() {
+    let mut kdtree = KdTree::with_capacity(2, 2);
+    kdtree.set_leaf_limit(Some(3));
+    kdtree.set_strict(true);
+    let x = [0.0, 1.0, 1.0, 1.0, 1.0, 2.0];
+    assert_eq!(
+        kdtree.add_from_components(&[&x, &x], vec![0, 1, 2, 3, 4, 5]),
+        Err(ErrorKind::LeafLimitExceeded(3))
+    );
+    assert_eq!(kdtree.size(), 0);
+
+    // duplicates already stored count too
+    kdtree.add(vec![1.0, 1.0], 0).unwrap();
+    let x = [1.0, 2.0, 1.0, 1.0];
+    assert_eq!(
+        kdtree.add_from_components(&[&x, &x], vec![1, 2, 3, 4]),
+        Err(ErrorKind::LeafLimitExceeded(3))
+    );
+    assert_eq!(kdtree.size(), 1);
+    kdtree.add_from_components(&[&x[..3], &x[..3]], vec![1, 2, 3]).unwrap();
+    assert_eq!(kdtree.size(), 4);
+}
+
+#[test]
+fn it_builds_balanced_trees_from_flat_buffers() {
+    let coords: Vec<f64> = (0..64).map(f64::from).collect();
+    let kdtree = KdTree::build_from_flat(&coords, Layout::RowMajor, 64, 1, 2, (0..64).collect()).unwrap();
+    assert_eq!(kdtree.stats().depth, 5);
+
+    let mut empty: KdTree<f64, usize, FlatPoint<f64>> =
+        KdTree::build_from_flat(&[], Layout::ColumnMajor, 0, 3, 2, vec![]).unwrap();
+    assert_eq!(empty.dimensions(), 3);
+    empty.add(FlatPoint::new(vec![1.0, 2.0, 3.0]), 0).unwrap();
+    assert_eq!(empty.remove(&FlatPoint::new(vec![1.0, 2.0, 3.0]), &0), Ok(1));
+}
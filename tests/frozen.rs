@@ -0,0 +1,183 @@
+extern crate kdtree;
+extern crate rand;
+
+use kdtree::distance::squared_euclidean;
+use kdtree::{ErrorKind, FrozenKdTree, KdTree};
+
+fn random_tree() -> KdTree<f64, usize, [f64; 3]> {
+    let mut kdtree = KdTree::with_capacity(3, 4);
+    for i in 0..300 {
+        let point = [rand::random(), rand::random(), rand::random()];
+        kdtree.add(point, i).unwrap();
+    }
+    kdtree
+}
+
+#[test]
+fn it_answers_queries_like_the_mutable_tree() {
+    let kdtree = random_tree();
+    let frozen = kdtree.clone().freeze();
+    assert_eq!(frozen.size(), kdtree.size());
+    assert_eq!(frozen.dimensions(), 3);
+
+    for _ in 0..20 {
+        let query = [rand::random(), rand::random(), rand::random()];
+        assert_eq!(
+            frozen.nearest(&query, 7, &squared_euclidean).unwrap(),
+            kdtree.nearest(&query, 7, &squared_euclidean).unwrap()
+        );
+        assert_eq!(
+            frozen.within(&query, 0.05, &squared_euclidean).unwrap(),
+            kdtree.within(&query, 0.05, &squared_euclidean).unwrap()
+        );
+    }
+    assert!(frozen.nearest(&[0.0, 0.0], 1, &squared_euclidean).is_err());
+}
+
+#[test]
+fn it_answers_the_other_queries_like_the_mutable_tree() {
+    let kdtree = random_tree();
+    let frozen = kdtree.clone().freeze();
+    let sorted = |mut found: Vec<usize>| {
+        found.sort_unstable();
+        found
+    };
+
+    for _ in 0..20 {
+        let query = [rand::random(), rand::random(), rand::random()];
+        let exact = kdtree.nearest(&query, 10, &squared_euclidean).unwrap();
+        assert_eq!(frozen.nearest_unchecked(&query, 10, &squared_euclidean), exact);
+        assert_eq!(
+            frozen.nearest_multi_k(&query, &[3, 10], &squared_euclidean).unwrap(),
+            vec![exact[..3].to_vec(), exact.clone()]
+        );
+        assert_eq!(
+            frozen.nearest_within(&query, 10, 0.02, &squared_euclidean).unwrap(),
+            kdtree.nearest_within(&query, 10, 0.02, &squared_euclidean).unwrap()
+        );
+        let even = |data: &usize| data % 2 == 0;
+        assert_eq!(
+            frozen.nearest_filtered(&query, 5, &squared_euclidean, even).unwrap(),
+            kdtree.nearest_filtered(&query, 5, &squared_euclidean, even).unwrap()
+        );
+        assert_eq!(
+            frozen
+                .nearest_at_least(&query, 5, 0.001, 2.0, &squared_euclidean)
+                .unwrap(),
+            kdtree
+                .nearest_at_least(&query, 5, 0.001, 2.0, &squared_euclidean)
+                .unwrap()
+        );
+        assert!(frozen
+            .iter_nearest(&query, &squared_euclidean)
+            .unwrap()
+            .eq(kdtree.iter_nearest(&query, &squared_euclidean).unwrap()));
+        assert_eq!(
+            frozen.within_cloned(&query, 0.05, &squared_euclidean).unwrap(),
+            kdtree.within_cloned(&query, 0.05, &squared_euclidean).unwrap()
+        );
+
+        let approx = frozen.approx_nearest(&query, 10, &squared_euclidean, 0.5).unwrap();
+        assert_eq!(approx.len(), 10);
+        for (found, exact) in approx.iter().zip(&exact) {
+            assert!(found.0 <= exact.0 * 1.5);
+        }
+
+        let (min, max) = ([query[0] - 0.2, 0.0, 0.3], [query[0] + 0.2, 0.5, 0.9]);
+        assert_eq!(
+            sorted(
+                frozen
+                    .within_box(&min, &max)
+                    .unwrap()
+                    .into_iter()
+                    .map(|(_, &d)| d)
+                    .collect()
+            ),
+            sorted(
+                kdtree
+                    .within_box(&min, &max)
+                    .unwrap()
+                    .into_iter()
+                    .map(|(_, &d)| d)
+                    .collect()
+            )
+        );
+    }
+    assert_eq!(
+        frozen.approx_nearest(&[0.0; 3], 1, &squared_euclidean, -1.0),
+        Err(ErrorKind::InvalidEpsilon)
+    );
+}
+
+#[test]
+fn it_unfreezes_into_the_same_tree() {
+    let kdtree = random_tree();
+    let frozen = kdtree.clone().freeze();
+    let original: Vec<(&[f64], &usize)> = kdtree.iter().map(|(p, d)| (&p[..], d)).collect();
+    assert_eq!(frozen.iter().collect::<Vec<_>>(), original);
+
+    let mut unfrozen: KdTree<f64, usize, [f64; 3]> = frozen.unfreeze().unwrap();
+    assert_eq!(unfrozen.stats(), kdtree.stats());
+    assert!(unfrozen.iter().eq(kdtree.iter()));
+    assert_eq!(unfrozen.split_planes().len(), kdtree.split_planes().len());
+
+    unfrozen.add([0.5, 0.5, 0.5], 300).unwrap();
+    assert_eq!(unfrozen.size(), 301);
+
+    let frozen = kdtree.freeze();
+    assert!(frozen.unfreeze::<[f64; 2]>().is_err());
+
+    let empty: FrozenKdTree<f64, usize> = KdTree::<f64, usize, Vec<f64>>::new(2).freeze();
+    assert!(empty.nearest(&[0.0, 0.0], 1, &squared_euclidean).unwrap().is_empty());
+    assert_eq!(empty.unfreeze::<Vec<f64>>().unwrap().size(), 0);
+}
+
+#[test]
+fn it_unfreezes_with_configuration_and_node_ids() {
+    let mut kdtree: KdTree<f64, usize, [f64; 2]> = KdTree::with_capacity(2, 2);
+    kdtree.set_leaf_limit(Some(3));
+    kdtree.set_strict(true);
+    for i in 0..20 {
+        kdtree.add([(i * 7 % 11) as f64, (i * 3 % 13) as f64], i).unwrap();
+    }
+    for i in 0..3 {
+        kdtree.add([1.0, 1.0], 20 + i).unwrap();
+    }
+    let stats = kdtree.stats();
+
+    let mut unfrozen: KdTree<f64, usize, [f64; 2]> = kdtree.clone().freeze().unfreeze().unwrap();
+    assert_eq!(unfrozen.stats(), stats);
+    assert_eq!(unfrozen.generation(), unfrozen.split_planes().len() as u64);
+    let adjacency = unfrozen.leaf_adjacency();
+    assert_eq!(adjacency.len(), kdtree.leaf_adjacency().len());
+    assert!(adjacency.iter().all(|&(a, b)| a < b));
+    assert_eq!(unfrozen.add([1.0, 1.0], 23), Err(ErrorKind::LeafLimitExceeded(3)));
+}
+
+#[test]
+fn it_unfreezes_with_tight_bounds() {
+    let mut kdtree: KdTree<f64, usize, [f64; 1]> = KdTree::with_capacity(1, 2);
+    for i in 0..10 {
+        kdtree.add([i as f64], i).unwrap();
+    }
+    kdtree.remove(&[9.0], &9).unwrap();
+    let frozen = kdtree.clone().freeze();
+    // the cells derived from the loose bounds still find everything
+    assert_eq!(
+        frozen.nearest(&[20.0], 1, &squared_euclidean).unwrap(),
+        vec![(144.0, &8)]
+    );
+
+    let unfrozen: KdTree<f64, usize, [f64; 1]> = frozen.unfreeze().unwrap();
+    let mut tight = kdtree.clone();
+    tight.recompute_bounds();
+    let cells = |kdtree: &KdTree<f64, usize, [f64; 1]>| {
+        kdtree
+            .leaf_cells()
+            .into_iter()
+            .map(|(cell, _, _)| cell)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(cells(&unfrozen), cells(&tight));
+    assert_ne!(cells(&unfrozen), cells(&kdtree));
+}
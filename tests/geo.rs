@@ -0,0 +1,46 @@
+#![cfg(feature = "geo")]
+extern crate kdtree;
+
+use kdtree::geo::{GeoKdTree, Projection};
+use kdtree::ErrorKind;
+
+#[test]
+fn it_projects_to_ecef() {
+    let equator = Projection::Ecef.project(0.0, 0.0);
+    assert_eq!(equator, vec![6_378_137.0, 0.0, 0.0]);
+    let pole = Projection::Ecef.project(90.0f64, 0.0);
+    assert!(pole[0].abs() < 1e-6 && pole[1].abs() < 1e-6);
+    assert!((pole[2] - 6_356_752.314_245).abs() < 1e-3);
+}
+
+#[test]
+fn it_applies_the_same_projection_to_queries() {
+    let mut tree = GeoKdTree::new(Projection::Ecef);
+    tree.add(0.0f64, 179.9, "east of the antimeridian").unwrap();
+    tree.add(0.0, -179.9, "west of the antimeridian").unwrap();
+    tree.add(0.0, 170.0, "far").unwrap();
+
+    let found = tree.nearest(0.0, -179.95, 2).unwrap();
+    assert_eq!(found[0].1, &"west of the antimeridian");
+    assert_eq!(found[1].1, &"east of the antimeridian");
+    assert!((found[0].0 - 5566.0).abs() < 1.0);
+    assert_eq!(tree.within(0.0, -179.95, 10_000.0).unwrap().len(), 1);
+    assert_eq!(tree.within(0.0, -179.95, 20_000.0).unwrap().len(), 2);
+    // squaring would turn this into a radius of 20 km
+    assert_eq!(tree.within(0.0, -179.95, -20_000.0), Err(ErrorKind::InvalidRadius));
+    assert_eq!(tree.within(0.0, -179.95, f64::NAN), Err(ErrorKind::InvalidRadius));
+    assert_eq!(tree.projection(), &Projection::Ecef);
+}
+
+#[test]
+fn it_projects_equirectangular() {
+    let mut tree = GeoKdTree::with_capacity(Projection::Equirectangular { reference_lat: 60.0 }, 4);
+    tree.add(60.0f64, 10.0, 'a').unwrap();
+    tree.add(60.01, 10.0, 'b').unwrap();
+    tree.add(60.0, 10.02, 'c').unwrap();
+
+    // a degree of longitude is only half as long at 60° latitude
+    let found = tree.nearest(60.0, 10.0, 3).unwrap();
+    assert_eq!(found.iter().map(|(_, d)| **d).collect::<Vec<_>>(), vec!['a', 'b', 'c']);
+    assert!((found[1].0 - found[2].0).abs() < 1e-6);
+}
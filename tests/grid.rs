@@ -0,0 +1,94 @@
+extern crate kdtree;
+
+use kdtree::distance::squared_euclidean;
+use kdtree::{Grid, KdTree};
+
+fn sites() -> KdTree<f64, char, [f64; 2]> {
+    let mut kdtree = KdTree::with_capacity(2, 1);
+    kdtree.add([0.0, 0.0], 'a').unwrap();
+    kdtree.add([4.0, 0.0], 'b').unwrap();
+    kdtree.add([0.25, 3.75], 'c').unwrap();
+    kdtree
+}
+
+#[test]
+fn it_numbers_cells_first_dimension_fastest() {
+    let grid = Grid::new(vec![0.0, 0.0], vec![4.0, 2.0], vec![4, 2]).unwrap();
+    assert_eq!(grid.len(), 8);
+    assert_eq!(grid.cell_center(0), vec![0.5, 0.5]);
+    assert_eq!(grid.cell_center(3), vec![3.5, 0.5]);
+    assert_eq!(grid.cell_center(4), vec![0.5, 1.5]);
+
+    assert_eq!(
+        Grid::new(vec![0.0], vec![1.0, 1.0], vec![1, 1]),
+        Err(kdtree::ErrorKind::WrongDimension)
+    );
+    assert_eq!(
+        Grid::<f64>::new(vec![], vec![], vec![]),
+        Err(kdtree::ErrorKind::WrongDimension)
+    );
+}
+
+#[test]
+fn it_rasterizes_nearest_payloads() {
+    let kdtree = sites();
+    let grid = Grid::new(vec![0.0, 0.0], vec![4.0, 4.0], vec![4, 4]).unwrap();
+    let raster = kdtree.rasterize_nearest(&grid, &squared_euclidean, |&c| c).unwrap();
+    let expected: Vec<char> = "aabb\
+                               aabb\
+                               cccb\
+                               cccc"
+        .chars()
+        .collect();
+    assert_eq!(raster, expected);
+
+    let empty: KdTree<f64, char, [f64; 2]> = KdTree::new(2);
+    assert!(empty
+        .rasterize_nearest(&grid, &squared_euclidean, |&c| c)
+        .unwrap()
+        .is_empty());
+
+    let wrong = Grid::new(vec![0.0], vec![4.0], vec![4]).unwrap();
+    assert!(kdtree.rasterize_nearest(&wrong, &squared_euclidean, |&c| c).is_err());
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn it_rasterizes_rows_in_parallel() {
+    let kdtree = sites();
+    let grid = Grid::new(vec![-1.0, -1.0], vec![5.0, 5.0], vec![31, 17]).unwrap();
+    assert_eq!(
+        kdtree.par_rasterize_nearest(&grid, &squared_euclidean, |&c| c).unwrap(),
+        kdtree.rasterize_nearest(&grid, &squared_euclidean, |&c| c).unwrap()
+    );
+}
+
+#[test]
+fn it_computes_distance_fields() {
+    let kdtree = sites();
+    let grid = Grid::new(vec![-1.0, -1.0], vec![5.0, 5.0], vec![24, 13]).unwrap();
+    let field = kdtree.distance_field(&grid, &squared_euclidean).unwrap();
+    assert_eq!(field.len(), grid.len());
+    for (cell, d) in field.into_iter().enumerate() {
+        let center = grid.cell_center(cell);
+        let expected = kdtree.nearest(&center, 1, &squared_euclidean).unwrap()[0].0;
+        assert_eq!(d, expected);
+    }
+
+    let grid = Grid::new(vec![0.0, 0.0], vec![2.0, 1.0], vec![2, 1]).unwrap();
+    assert_eq!(
+        kdtree.distance_field(&grid, &squared_euclidean).unwrap(),
+        vec![0.5, 2.5]
+    );
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn it_computes_distance_fields_in_parallel() {
+    let kdtree = sites();
+    let grid = Grid::new(vec![-1.0, -1.0], vec![5.0, 5.0], vec![31, 17]).unwrap();
+    assert_eq!(
+        kdtree.par_distance_field(&grid, &squared_euclidean).unwrap(),
+        kdtree.distance_field(&grid, &squared_euclidean).unwrap()
+    );
+}
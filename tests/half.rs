@@ -0,0 +1,29 @@
+#![cfg(feature = "half")]
+
+extern crate kdtree;
+
+use kdtree::distance::{euclidean_f32, squared_euclidean, squared_euclidean_f32};
+use kdtree::{f16, KdTree};
+
+fn h(x: f32) -> f16 {
+    f16::from_f32(x)
+}
+
+#[test]
+fn it_stores_half_precision_and_computes_in_f32() {
+    let mut kdtree = KdTree::with_capacity(2, 2);
+    for i in 0..20 {
+        let x = i as f32 * 50.0;
+        kdtree.add([h(x), h(0.0)], i).unwrap();
+    }
+
+    // squared distances of 300² overflow in half precision, but not when widened
+    let query = [h(0.0), h(300.0)];
+    let found = kdtree.nearest(&query, 2, &euclidean_f32).unwrap();
+    assert_eq!(found, vec![(h(300.0), &0), (h(304.25), &1)]);
+    assert!(squared_euclidean(&query, &[h(0.0), h(0.0)]).is_infinite());
+    assert!(squared_euclidean_f32(&[h(0.0), h(0.0)], &[h(3.0), h(4.0)]) == h(25.0));
+
+    let within = kdtree.within(&query, h(320.0), &euclidean_f32).unwrap();
+    assert_eq!(within.len(), 3);
+}
@@ -0,0 +1,84 @@
+extern crate kdtree;
+
+use kdtree::distance::squared_euclidean;
+use kdtree::{ErrorKind, KdTree, Tolerance};
+
+fn tree() -> KdTree<f64, char, [f64; 2]> {
+    let mut kdtree = KdTree::with_capacity(2, 2);
+    kdtree.add([0.0, 0.0], 'a').unwrap();
+    kdtree.add([4.0, 2.0], 'b').unwrap();
+    kdtree.add([1.0, 3.0], 'c').unwrap();
+    kdtree
+}
+
+#[test]
+fn it_resolves_handles_of_the_current_revision() {
+    let kdtree = tree();
+    let nearest = kdtree.nearest_handles(&[3.0, 2.0], 2, &squared_euclidean).unwrap();
+    assert_eq!(nearest.len(), 2);
+    assert_eq!(nearest[0].0, 1.0);
+    assert_eq!(kdtree.get(nearest[0].1), Ok((&[4.0, 2.0], &'b')));
+    assert_eq!(kdtree.get(nearest[1].1).unwrap().1, &'c');
+
+    for (index, entry) in kdtree.iter().enumerate() {
+        let handle = kdtree.handle(index).unwrap();
+        assert_eq!(handle.index(), index);
+        assert_eq!(kdtree.get(handle), Ok(entry));
+    }
+    assert_eq!(kdtree.handle(3), None);
+    assert_eq!(
+        kdtree.nearest_handles(&[1.0], 1, &squared_euclidean),
+        Err(ErrorKind::WrongDimension)
+    );
+}
+
+#[test]
+fn it_rejects_handles_after_entries_move() {
+    let mut kdtree = tree();
+    let handle = kdtree.handle(0).unwrap();
+    kdtree.add([2.0, 2.0], 'd').unwrap();
+    assert_eq!(kdtree.get(handle), Err(ErrorKind::StaleHandle));
+
+    let handle = kdtree.handle(0).unwrap();
+    assert_eq!(kdtree.remove(&[9.0, 9.0], &'x'), Ok(0));
+    assert!(kdtree.get(handle).is_ok());
+    kdtree.remove(&[2.0, 2.0], &'d').unwrap();
+    assert_eq!(kdtree.get(handle), Err(ErrorKind::StaleHandle));
+
+    let handle = kdtree.handle(0).unwrap();
+    assert_eq!(kdtree.extract_if(|_, d| *d == 'x').count(), 0);
+    assert!(kdtree.get(handle).is_ok());
+    assert_eq!(kdtree.extract_if(|_, d| *d == 'c').count(), 1);
+    assert_eq!(kdtree.get(handle), Err(ErrorKind::StaleHandle));
+
+    let handle = kdtree.handle(0).unwrap();
+    kdtree
+        .remove_matching(&[4.0, 2.1], &'b', Tolerance::Absolute(0.5))
+        .unwrap();
+    assert_eq!(kdtree.get(handle), Err(ErrorKind::StaleHandle));
+}
+
+#[test]
+fn it_keeps_handles_across_changes_that_keep_the_order() {
+    let mut kdtree = tree();
+    kdtree.remove(&[0.0, 0.0], &'a').unwrap();
+    let handle = kdtree.handle(1).unwrap();
+    let revision = kdtree.revision();
+    kdtree.compact();
+    kdtree.recompute_bounds();
+    assert_eq!(kdtree.revision(), revision);
+    assert_eq!(kdtree.get(handle).ok(), kdtree.iter().nth(1));
+}
+
+#[test]
+fn it_rejects_handles_of_other_trees() {
+    let kdtree = tree();
+    let rebuilt = tree();
+    let handle = kdtree.handle(0).unwrap();
+    assert_eq!(rebuilt.get(handle), Err(ErrorKind::StaleHandle));
+
+    let mut clone = kdtree.clone();
+    assert!(clone.get(handle).is_ok());
+    clone.add([2.0, 2.0], 'd').unwrap();
+    assert_eq!(clone.get(handle), Err(ErrorKind::StaleHandle));
+}
@@ -0,0 +1,45 @@
+extern crate kdtree;
+extern crate rand;
+
+use kdtree::ErrorKind;
+use kdtree::KdTree;
+
+#[test]
+fn it_finds_the_hull_counter_clockwise() {
+    let mut points = vec![
+        ([0f64, 0f64], 'a'),
+        ([4f64, 0f64], 'b'),
+        ([4f64, 4f64], 'c'),
+        ([0f64, 4f64], 'd'),
+        ([2f64, 0f64], 'e'),
+        ([4f64, 4f64], 'f'),
+    ];
+    for i in 0..50 {
+        points.push(([1f64 + f64::from(i % 7) * 0.3, 1f64 + f64::from(i % 5) * 0.4], 'x'));
+    }
+    let kdtree = KdTree::bulk_load(2, 2, points).unwrap();
+    let hull: Vec<char> = kdtree.convex_hull_2d().unwrap().into_iter().map(|(_, d)| *d).collect();
+    assert_eq!(hull.len(), 4);
+    assert_eq!(hull[0], 'a');
+    assert_eq!(hull[1], 'b');
+    assert!(hull[2] == 'c' || hull[2] == 'f');
+    assert_eq!(hull[3], 'd');
+
+    let kdtree: KdTree<f64, char, [f64; 3]> = KdTree::new(3);
+    assert_eq!(kdtree.convex_hull_2d(), Err(ErrorKind::WrongDimension));
+}
+
+#[test]
+fn it_matches_brute_force() {
+    let points: Vec<([f64; 2], usize)> = (0..1000).map(|i| (rand::random(), i)).collect();
+    let kdtree = KdTree::bulk_load(2, 8, points.clone()).unwrap();
+    let hull: Vec<[f64; 2]> = kdtree.convex_hull_2d().unwrap().into_iter().map(|(p, _)| *p).collect();
+    assert!(hull.len() >= 3);
+    let cross =
+        |o: &[f64; 2], a: &[f64; 2], b: &[f64; 2]| (a[0] - o[0]) * (b[1] - o[1]) - (a[1] - o[1]) * (b[0] - o[0]);
+    for (p, _) in &points {
+        for i in 0..hull.len() {
+            assert!(cross(&hull[i], &hull[(i + 1) % hull.len()], p) >= 0.0);
+        }
+    }
+}
@@ -0,0 +1,75 @@
+#![cfg(feature = "hull3d")]
+extern crate kdtree;
+extern crate rand;
+
+use kdtree::ErrorKind;
+use kdtree::KdTree;
+
+fn orient(a: &[f64; 3], b: &[f64; 3], c: &[f64; 3], p: &[f64; 3]) -> f64 {
+    let (u, v, w) = (
+        [b[0] - a[0], b[1] - a[1], b[2] - a[2]],
+        [c[0] - a[0], c[1] - a[1], c[2] - a[2]],
+        [p[0] - a[0], p[1] - a[1], p[2] - a[2]],
+    );
+    (u[1] * v[2] - u[2] * v[1]) * w[0] + (u[2] * v[0] - u[0] * v[2]) * w[1] + (u[0] * v[1] - u[1] * v[0]) * w[2]
+}
+
+#[test]
+fn it_finds_the_corners_of_a_cube() {
+    let mut points = vec![];
+    for i in 0..8 {
+        let corner = [(i & 1) as f64, (i >> 1 & 1) as f64, (i >> 2 & 1) as f64];
+        points.push((corner, true));
+        // duplicates and points on faces are left out
+        points.push((corner, true));
+        points.push(([0.5, 0.5, corner[2]], false));
+    }
+    for i in 0..100 {
+        let x = 0.1 + f64::from(i % 7) * 0.1;
+        let y = 0.1 + f64::from(i % 5) * 0.2;
+        points.push(([x, y, 0.1 + f64::from(i % 3) * 0.4], false));
+    }
+    let kdtree = KdTree::bulk_load(3, 4, points).unwrap();
+    let hull = kdtree.convex_hull_3d().unwrap();
+    // two triangles per face
+    assert_eq!(hull.len(), 12);
+    assert!(hull.iter().flatten().all(|(_, &corner)| corner));
+    for triangle in &hull {
+        let [a, b, c] = [triangle[0].0, triangle[1].0, triangle[2].0];
+        assert!(orient(a, b, c, &[0.5, 0.5, 0.5]) < 0.0);
+    }
+
+    let kdtree: KdTree<f64, char, [f64; 2]> = KdTree::new(2);
+    assert_eq!(kdtree.convex_hull_3d(), Err(ErrorKind::WrongDimension));
+}
+
+#[test]
+fn it_is_empty_for_coplanar_points() {
+    let points: Vec<([f64; 3], usize)> = (0..50).map(|i| ([rand::random(), rand::random(), 1.0], i)).collect();
+    let kdtree = KdTree::bulk_load(3, 4, points).unwrap();
+    assert!(kdtree.convex_hull_3d().unwrap().is_empty());
+}
+
+#[test]
+fn it_matches_brute_force() {
+    let points: Vec<([f64; 3], usize)> = (0..1000).map(|i| (rand::random(), i)).collect();
+    let kdtree = KdTree::bulk_load(3, 8, points.clone()).unwrap();
+    let hull = kdtree.convex_hull_3d().unwrap();
+    assert!(hull.len() >= 4);
+    for triangle in &hull {
+        let [a, b, c] = [triangle[0].0, triangle[1].0, triangle[2].0];
+        for (p, _) in &points {
+            assert!(orient(a, b, c, p) <= 1e-12);
+        }
+    }
+    // a closed surface of triangles: every edge is shared by exactly two of them
+    let mut edges = vec![];
+    for triangle in &hull {
+        for k in 0..3 {
+            edges.push((*triangle[k].1, *triangle[(k + 1) % 3].1));
+        }
+    }
+    for &(from, to) in &edges {
+        assert_eq!(edges.iter().filter(|&&edge| edge == (to, from)).count(), 1);
+    }
+}
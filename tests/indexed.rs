@@ -0,0 +1,52 @@
+extern crate kdtree;
+extern crate rand;
+
+use kdtree::distance::squared_euclidean;
+use kdtree::KdTree;
+
+#[test]
+fn it_excludes_the_given_index() {
+    let points: Vec<([f64; 2], usize)> = (0..200)
+        .map(|i| ([rand::random::<f64>(), rand::random::<f64>()], i))
+        .collect();
+    let kdtree = KdTree::bulk_load(2, 4, points).unwrap();
+
+    for (index, (point, &data)) in kdtree.iter().enumerate() {
+        let found = kdtree
+            .nearest_excluding_index(point, 3, &squared_euclidean, index)
+            .unwrap();
+        let expected: Vec<(f64, &usize)> = kdtree
+            .nearest(point, 4, &squared_euclidean)
+            .unwrap()
+            .into_iter()
+            .filter(|&(_, &d)| d != data)
+            .take(3)
+            .collect();
+        assert_eq!(found, expected);
+    }
+}
+
+#[test]
+fn it_keeps_duplicates_at_other_indices() {
+    let mut kdtree = KdTree::new(1);
+    kdtree.add([0f64], 'a').unwrap();
+    kdtree.add([0f64], 'b').unwrap();
+    kdtree.add([3f64], 'c').unwrap();
+
+    assert_eq!(
+        kdtree
+            .nearest_excluding_index(&[0f64], 2, &squared_euclidean, 0)
+            .unwrap(),
+        vec![(0.0, &'b'), (9.0, &'c')]
+    );
+    assert_eq!(
+        kdtree
+            .nearest_excluding_index(&[0f64], 5, &squared_euclidean, 7)
+            .unwrap()
+            .len(),
+        3
+    );
+    assert!(kdtree
+        .nearest_excluding_index(&[0f64, 0f64], 1, &squared_euclidean, 0)
+        .is_err());
+}
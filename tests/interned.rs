@@ -0,0 +1,37 @@
+extern crate kdtree;
+
+use kdtree::distance::squared_euclidean;
+use kdtree::{ErrorKind, InternedKdTree};
+
+#[test]
+fn it_deduplicates_payloads() {
+    let mut kdtree = InternedKdTree::with_capacity(2, 2);
+    for i in 0..100 {
+        let label = if i % 2 == 0 { "even" } else { "odd" };
+        kdtree.add([f64::from(i), 0.0], label.to_string()).unwrap();
+    }
+
+    assert_eq!(kdtree.size(), 100);
+    assert_eq!(kdtree.distinct_payloads(), &["even".to_string(), "odd".to_string()]);
+    assert_eq!(
+        kdtree.nearest(&[3.25, 0.0], 2, &squared_euclidean).unwrap(),
+        vec![(0.0625, &"odd".to_string()), (0.5625, &"even".to_string())]
+    );
+    assert_eq!(kdtree.within(&[50.0, 0.0], 1.0, &squared_euclidean).unwrap().len(), 3);
+
+    assert_eq!(kdtree.remove(&[3.0, 0.0], &"odd".to_string()).unwrap(), 1);
+    assert_eq!(kdtree.remove(&[3.0, 0.0], &"unknown".to_string()).unwrap(), 0);
+    assert_eq!(kdtree.size(), 99);
+}
+
+#[test]
+fn it_validates_points_to_remove_of_unknown_payloads() {
+    let mut kdtree = InternedKdTree::new(2);
+    kdtree.add(vec![0.0, 0.0], 'a').unwrap();
+    assert_eq!(kdtree.remove(&vec![0.0], &'b'), Err(ErrorKind::WrongDimension));
+    assert_eq!(
+        kdtree.remove(&vec![f64::NAN, 0.0], &'b'),
+        Err(ErrorKind::NonFiniteCoordinate)
+    );
+    assert_eq!(kdtree.remove(&vec![0.0, 0.0], &'b'), Ok(0));
+}
@@ -0,0 +1,59 @@
+extern crate kdtree;
+
+use kdtree::distance::squared_euclidean;
+use kdtree::{ErrorKind, JitteredKdTree, KdTreeBuilder};
+
+fn pixels(seed: u64) -> JitteredKdTree<f64, usize, [f64; 2]> {
+    let mut kdtree = JitteredKdTree::with_capacity(2, 4, 1e-6, seed).unwrap();
+    for i in 0..200 {
+        kdtree.add([(i % 3) as f64, 0.0], i).unwrap();
+    }
+    kdtree
+}
+
+#[test]
+fn it_keeps_duplicates_splittable() {
+    let kdtree = pixels(7);
+    let stats = kdtree.tree().stats();
+    assert_eq!(stats.unsplittable_leaves, 0);
+    assert!(stats.max_leaf_size <= 4);
+
+    // same seed, same tree
+    let again = pixels(7);
+    assert!(kdtree.tree().iter().eq(again.tree().iter()));
+}
+
+#[test]
+fn it_reports_original_coordinates_and_distances() {
+    let mut kdtree = pixels(1);
+    assert!(kdtree.iter().all(|(point, &i)| point == &[(i % 3) as f64, 0.0]));
+
+    let nearest = kdtree.nearest(&[1.0, 1.0], 3, &squared_euclidean).unwrap();
+    assert_eq!(nearest.iter().map(|&(d, _)| d).collect::<Vec<_>>(), vec![1.0; 3]);
+    assert!(nearest.iter().all(|&(_, &i)| i % 3 == 1));
+
+    let within = kdtree.within(&[2.0, 0.0], 0.5, &squared_euclidean).unwrap();
+    assert_eq!(within.len(), 66);
+    assert!(within.iter().all(|&(d, &i)| d == 0.0 && i % 3 == 2));
+
+    assert_eq!(kdtree.remove(&[2.0, 0.0], &5).unwrap(), 1);
+    assert_eq!(kdtree.remove(&[2.0, 0.0], &6).unwrap(), 0);
+    assert_eq!(kdtree.size(), 199);
+}
+
+#[test]
+fn it_is_built_from_the_builder() {
+    let mut kdtree = KdTreeBuilder::new()
+        .dimensions::<1>()
+        .capacity(1)
+        .jittered(0.01, 3)
+        .unwrap();
+    kdtree.add([1.0], 'a').unwrap();
+    kdtree.add([1.0], 'b').unwrap();
+    assert_eq!(kdtree.tree().stats().unsplittable_leaves, 0);
+
+    assert_eq!(
+        JitteredKdTree::<f64, char, [f64; 1]>::new(1, -1.0, 0).unwrap_err(),
+        ErrorKind::InvalidJitter
+    );
+}
@@ -368,3 +368,493 @@ fn handles_remove_no_match() {
         vec![(16.0, &4), (36.0, &3)]
     );
 }
+
+#[test]
+fn handles_remove_with_matching_point_but_other_data() {
+    let mut kdtree = KdTree::with_capacity(1, 2);
+    kdtree.add([0f64], 1).unwrap();
+    kdtree.add([0f64], 2).unwrap();
+    kdtree.add([5f64], 3).unwrap();
+
+    assert_eq!(kdtree.remove(&[0f64], &3).unwrap(), 0);
+    assert_eq!(kdtree.remove(&[0f64], &2).unwrap(), 1);
+    assert_eq!(kdtree.size(), 2);
+    assert_eq!(
+        kdtree.nearest(&[0f64], 2, &squared_euclidean).unwrap(),
+        vec![(0.0, &1), (25.0, &3)]
+    );
+}
+
+#[test]
+fn unchecked_variants_agree_with_checked_ones() {
+    let mut checked = KdTree::with_capacity(2, 2);
+    let mut unchecked = KdTree::with_capacity(2, 2);
+    for item in &[POINT_A, POINT_B, POINT_C, POINT_D] {
+        checked.add(item.0, item.1).unwrap();
+        unchecked.add_unchecked(item.0, item.1);
+    }
+
+    assert_eq!(unchecked.size(), checked.size());
+    assert_eq!(
+        unchecked.nearest_unchecked(&POINT_B.0, 3, &squared_euclidean),
+        checked.nearest(&POINT_B.0, 3, &squared_euclidean).unwrap()
+    );
+}
+
+#[test]
+fn infers_dimensions_from_the_first_point() {
+    let mut kdtree = KdTree::new_infer();
+    assert_eq!(kdtree.dimensions(), 0);
+    assert_eq!(
+        kdtree.nearest(&[0f64, 0f64, 0f64], 1, &squared_euclidean).unwrap(),
+        vec![]
+    );
+
+    kdtree.add(vec![1f64, 2f64, 3f64], 0).unwrap();
+    assert_eq!(kdtree.dimensions(), 3);
+    assert_eq!(kdtree.add(vec![1f64, 2f64], 1), Err(ErrorKind::WrongDimension));
+    assert_eq!(
+        kdtree.nearest(&[0f64, 0f64], 1, &squared_euclidean),
+        Err(ErrorKind::WrongDimension)
+    );
+    assert_eq!(
+        kdtree.nearest(&[1f64, 2f64, 4f64], 1, &squared_euclidean).unwrap(),
+        vec![(1f64, &0)]
+    );
+}
+
+#[test]
+fn rejected_first_points_leave_the_dimension_size_open() {
+    let mut kdtree = KdTree::new_infer();
+    assert_eq!(kdtree.add(vec![f64::NAN, 0f64], 0), Err(ErrorKind::NonFiniteCoordinate));
+    assert_eq!(kdtree.dimensions(), 0);
+    kdtree.add(vec![1f64, 2f64, 3f64], 1).unwrap();
+    assert_eq!(kdtree.dimensions(), 3);
+    assert_eq!(kdtree.size(), 1);
+}
+
+#[test]
+fn bulk_load_validates_all_points_up_front() {
+    let points = vec![POINT_A, POINT_B, POINT_C, POINT_D];
+    let kdtree = KdTree::bulk_load(2, 2, points).unwrap();
+    assert_eq!(kdtree.size(), 4);
+    assert_eq!(
+        kdtree.nearest(&POINT_A.0, 2, &squared_euclidean).unwrap(),
+        vec![(0f64, &0), (2f64, &1)]
+    );
+
+    let points = vec![(vec![0f64, 0f64], 0), (vec![1f64, 1f64], 1), (vec![2f64], 2)];
+    assert_eq!(
+        KdTree::bulk_load(2, 2, points).unwrap_err(),
+        ErrorKind::WrongDimensionAt(2)
+    );
+    let points = vec![(vec![0f64, f64::NAN], 0), (vec![1f64], 1)];
+    assert_eq!(
+        KdTree::bulk_load(2, 2, points).unwrap_err(),
+        ErrorKind::NonFiniteCoordinateAt(0)
+    );
+    assert_eq!(
+        KdTree::<f64, i32, [f64; 2]>::bulk_load(2, 0, vec![]).unwrap_err(),
+        ErrorKind::ZeroCapacity
+    );
+}
+
+#[test]
+fn nearest_multi_k_slices_a_single_traversal() {
+    let kdtree = KdTree::bulk_load(2, 2, vec![POINT_A, POINT_B, POINT_C, POINT_D]).unwrap();
+    assert_eq!(
+        kdtree
+            .nearest_multi_k(&POINT_A.0, &[2, 0, 5], &squared_euclidean)
+            .unwrap(),
+        vec![
+            vec![(0f64, &0), (2f64, &1)],
+            vec![],
+            vec![(0f64, &0), (2f64, &1), (8f64, &2), (18f64, &3)],
+        ]
+    );
+}
+
+#[test]
+fn cloned_results_outlive_the_tree() {
+    let found = {
+        let mut kdtree = KdTree::new(2);
+        kdtree.add(POINT_A.0, POINT_A.1.to_string()).unwrap();
+        kdtree.add(POINT_B.0, POINT_B.1.to_string()).unwrap();
+        kdtree.add(POINT_C.0, POINT_C.1.to_string()).unwrap();
+        assert_eq!(
+            kdtree.within_cloned(&POINT_A.0, 2f64, &squared_euclidean).unwrap(),
+            vec![(0f64, "0".to_string()), (2f64, "1".to_string())]
+        );
+        kdtree.nearest_cloned(&POINT_C.0, 2, &squared_euclidean).unwrap()
+    };
+    assert_eq!(found, vec![(0f64, "2".to_string()), (2f64, "1".to_string())]);
+}
+
+#[test]
+fn into_points_moves_entries_out_in_iteration_order() {
+    let mut kdtree = KdTree::with_capacity(2, 1);
+    for &(point, data) in &[POINT_A, POINT_D, POINT_B, POINT_C] {
+        kdtree.add(point.to_vec(), data.to_string()).unwrap();
+    }
+    let expected: Vec<(Vec<f64>, String)> = kdtree.iter().map(|(p, d)| (p.clone(), d.clone())).collect();
+    let entries = kdtree.into_points();
+    assert_eq!(entries, expected);
+    assert_eq!(entries.len(), 4);
+}
+
+#[test]
+fn extract_if_moves_matching_entries_out() {
+    let mut kdtree = KdTree::with_capacity(2, 1);
+    for &(point, data) in &[POINT_A, POINT_D, POINT_B, POINT_C] {
+        kdtree.add(point, data).unwrap();
+    }
+    let extracted: Vec<([f64; 2], usize)> = kdtree.extract_if(|point, _| point[0] >= 2f64).collect();
+    assert_eq!(extracted, vec![POINT_C, POINT_D]);
+    assert_eq!(kdtree.size(), 2);
+    assert_eq!(
+        kdtree.nearest(&POINT_D.0, 4, &squared_euclidean).unwrap(),
+        vec![(8f64, &1), (18f64, &0)]
+    );
+
+    // dropping the iterator unconsumed still removes the entries
+    let _ = kdtree.extract_if(|_, data| {
+        *data += 10;
+        *data == 10
+    });
+    assert_eq!(kdtree.size(), 1);
+    assert_eq!(kdtree.iter().map(|(_, &data)| data).collect::<Vec<_>>(), vec![11]);
+}
+
+#[test]
+fn recompute_bounds_shrinks_bounds_after_removals() {
+    let mut kdtree = KdTree::with_capacity(2, 1);
+    for &(point, data) in &[POINT_A, POINT_B, POINT_C] {
+        kdtree.add(point, data).unwrap();
+    }
+    kdtree.add([1000f64, 1000f64], 99).unwrap();
+    kdtree.remove(&[1000f64, 1000f64], &99).unwrap();
+    let stale = kdtree.leaf_cells();
+    assert_eq!(stale.last().unwrap().0.max, vec![1000f64, 1000f64]);
+
+    kdtree.recompute_bounds();
+    let cells = kdtree.leaf_cells();
+    assert_eq!(cells[0].0.min, vec![0f64, 0f64]);
+    // only the first dimension was ever split, so all cells share the root's bounds in the second
+    assert!(cells.iter().all(|(cell, _, _)| cell.max[1] == 2f64));
+    assert_eq!(
+        kdtree.nearest(&POINT_C.0, 3, &squared_euclidean).unwrap(),
+        vec![(0f64, &2), (2f64, &1), (8f64, &0)]
+    );
+}
+
+#[test]
+fn compact_shrinks_bounds_after_removals() {
+    let mut kdtree = KdTree::with_capacity(2, 1);
+    for &(point, data) in &[POINT_A, POINT_B, POINT_C] {
+        kdtree.add(point, data).unwrap();
+    }
+    kdtree.add([1000f64, 1000f64], 99).unwrap();
+    kdtree.remove(&[1000f64, 1000f64], &99).unwrap();
+
+    assert_eq!(kdtree.compact(), 1);
+    // the root's bounds no longer reach out to the removed point
+    let cells = kdtree.leaf_cells();
+    assert!(cells.iter().all(|(cell, _, _)| cell.max.iter().all(|&x| x <= 2f64)));
+}
+
+#[test]
+fn nearest_at_least_grows_the_radius_until_enough_are_found() {
+    let mut kdtree = KdTree::with_capacity(2, 1);
+    for &(point, data) in &[POINT_A, POINT_B, POINT_C, POINT_D] {
+        kdtree.add(point, data).unwrap();
+    }
+    // squared radii 0.5 -> 1 -> 2 covers A and B, 2 -> 4 -> 8 adds C
+    assert_eq!(
+        kdtree
+            .nearest_at_least(&POINT_A.0, 1, 0.5, 2.0, &squared_euclidean)
+            .unwrap(),
+        vec![(0f64, &0)]
+    );
+    assert_eq!(
+        kdtree
+            .nearest_at_least(&POINT_A.0, 2, 0.5, 2.0, &squared_euclidean)
+            .unwrap(),
+        vec![(0f64, &0), (2f64, &1)]
+    );
+    assert_eq!(
+        kdtree
+            .nearest_at_least(&POINT_A.0, 3, 0.5, 2.0, &squared_euclidean)
+            .unwrap(),
+        vec![(0f64, &0), (2f64, &1), (8f64, &2)]
+    );
+    // more than asked for when the radius already covers them
+    assert_eq!(
+        kdtree
+            .nearest_at_least(&POINT_A.0, 1, 10.0, 2.0, &squared_euclidean)
+            .unwrap()
+            .len(),
+        3
+    );
+    assert_eq!(
+        kdtree
+            .nearest_at_least(&POINT_A.0, 10, 0.5, 2.0, &squared_euclidean)
+            .unwrap()
+            .len(),
+        4
+    );
+    assert_eq!(
+        kdtree.nearest_at_least(&POINT_A.0, 1, 0f64, 2.0, &squared_euclidean),
+        Err(ErrorKind::InvalidRadiusGrowth)
+    );
+    assert_eq!(
+        kdtree.nearest_at_least(&POINT_A.0, 1, 1.0, 1.0, &squared_euclidean),
+        Err(ErrorKind::InvalidRadiusGrowth)
+    );
+}
+
+#[test]
+fn build_splits_at_the_median() {
+    let points: Vec<([f64; 1], usize)> = (0..64).map(|i| ([f64::from(i as u32)], i)).collect();
+    let mut sequential = KdTree::with_capacity(1, 2);
+    for &(point, data) in &points {
+        sequential.add(point, data).unwrap();
+    }
+    let mut reversed = points.clone();
+    reversed.reverse();
+    let built = KdTree::build_with_capacity(2, reversed).unwrap();
+    assert_eq!(built.size(), 64);
+    assert_eq!(built.stats().depth, 5);
+    assert!(built.stats().depth < sequential.stats().depth);
+    assert_eq!(built.stats().max_leaf_size, 2);
+    assert_eq!(
+        built.iter().map(|(_, &data)| data).collect::<Vec<_>>(),
+        (0..64).collect::<Vec<_>>()
+    );
+    for &(point, data) in &points {
+        assert_eq!(
+            built.nearest(&point, 1, &squared_euclidean).unwrap(),
+            vec![(0f64, &data)]
+        );
+    }
+
+    // duplicates at the median still split, identical points stay in one leaf
+    let mut duplicates = vec![([0f64, 0f64], 0); 5];
+    duplicates.push(([1f64, 0f64], 1));
+    let mut built = KdTree::build_with_capacity(2, duplicates).unwrap();
+    assert_eq!(built.stats().unsplittable_leaves, 1);
+    built.add([0.5, 0.5], 2).unwrap();
+    assert_eq!(built.within(&[0f64, 0f64], 1f64, &squared_euclidean).unwrap().len(), 7);
+
+    assert_eq!(
+        KdTree::<f64, usize, [f64; 2]>::build(vec![([0.0, 0.0], 0), ([f64::NAN, 0.0], 1)]).err(),
+        Some(ErrorKind::NonFiniteCoordinateAt(1))
+    );
+    assert_eq!(KdTree::<f64, usize, [f64; 2]>::build(vec![]).unwrap().size(), 0);
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn par_build_builds_the_same_tree() {
+    let points: Vec<([f64; 3], usize)> = (0..20_000)
+        .map(|i| {
+            (
+                [(i * 7919 % 1000) as f64, (i * 104_729 % 997) as f64, (i % 13) as f64],
+                i,
+            )
+        })
+        .collect();
+    let sequential = KdTree::build_with_capacity(8, points.clone()).unwrap();
+    let parallel = KdTree::par_build_with_capacity(8, points).unwrap();
+    assert_eq!(parallel.stats(), sequential.stats());
+    assert_eq!(parallel.split_planes(), sequential.split_planes());
+    assert_eq!(parallel.changes_since(0), sequential.changes_since(0));
+    assert!(parallel.iter().eq(sequential.iter()));
+}
+
+#[test]
+fn it_finds_the_nearest_point_satisfying_a_predicate() {
+    let mut kdtree = KdTree::with_capacity(2, 3);
+    for i in 0..100 {
+        kdtree.add([(i % 10) as f64, (i / 10) as f64], i).unwrap();
+    }
+
+    let all = kdtree.nearest(&[4.2, 4.9], 100, &squared_euclidean).unwrap();
+    let lazy: Vec<_> = kdtree.iter_nearest(&[4.2, 4.9], &squared_euclidean).unwrap().collect();
+    assert_eq!(
+        lazy.iter().map(|&(d, _)| d).collect::<Vec<_>>(),
+        all.iter().map(|&(d, _)| d).collect::<Vec<_>>()
+    );
+
+    let found = kdtree
+        .iter_nearest(&[4.2, 4.9], &squared_euclidean)
+        .unwrap()
+        .find(|&(_, &i)| i >= 70);
+    let (distance, &i) = found.unwrap();
+    assert_eq!(i, 74);
+    assert!((distance - 4.45).abs() < 1e-9);
+}
+
+#[test]
+fn drain_empties_the_tree() {
+    let mut kdtree = KdTree::with_capacity(2, 2);
+    for i in 0..5 {
+        kdtree.add([i as f64, 0.0], i).unwrap();
+    }
+    let order: Vec<usize> = kdtree.iter().map(|(_, &i)| i).collect();
+
+    let drained = kdtree.drain();
+    kdtree.add([9.0, 9.0], 9).unwrap();
+    assert_eq!(drained.map(|(_, i)| i).collect::<Vec<_>>(), order);
+    assert_eq!(kdtree.size(), 1);
+}
+
+#[test]
+fn shared_trees_keep_a_snapshot_for_readers() {
+    use std::sync::Arc;
+
+    let mut tree = Arc::new(KdTree::with_capacity(1, 2));
+    Arc::make_mut(&mut tree).add([0f64], 0).unwrap();
+    let snapshot = Arc::clone(&tree);
+
+    Arc::make_mut(&mut tree).add([1f64], 1).unwrap();
+    assert_eq!(snapshot.iter().count(), 1);
+    assert_eq!(tree.iter().count(), 2);
+}
+
+#[test]
+fn it_finds_points_within_a_box() {
+    let mut kdtree = KdTree::with_capacity(2, 2);
+    for i in 0..100 {
+        kdtree.add([(i % 10) as f64, (i / 10) as f64], i).unwrap();
+    }
+
+    let found = kdtree.within_box(&[2.0, 3.5], &[4.0, 5.0]).unwrap();
+    let mut data: Vec<usize> = found.iter().map(|&(_, &i)| i).collect();
+    let order: Vec<usize> = kdtree.iter().map(|(_, &i)| i).filter(|i| data.contains(i)).collect();
+    assert_eq!(data, order);
+    data.sort_unstable();
+    assert_eq!(data, vec![42, 43, 44, 52, 53, 54]);
+    assert!(found.iter().all(|(point, _)| point[0] >= 2.0 && point[1] <= 5.0));
+
+    assert_eq!(kdtree.within_box(&[-1.0, -1.0], &[10.0, 10.0]).unwrap().len(), 100);
+    assert!(kdtree.within_box(&[4.0, 5.0], &[2.0, 3.5]).unwrap().is_empty());
+    assert_eq!(kdtree.within_box(&[0.0], &[1.0, 1.0]), Err(ErrorKind::WrongDimension));
+}
+
+#[test]
+fn approx_nearest_stays_within_epsilon() {
+    let mut kdtree = KdTree::with_capacity(3, 4);
+    let mut state = 17u64;
+    let mut next = || {
+        state = state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (state >> 11) as f64 / (1u64 << 53) as f64
+    };
+    for i in 0..500 {
+        kdtree.add([next(), next(), next()], i).unwrap();
+    }
+
+    for _ in 0..20 {
+        let query = [next(), next(), next()];
+        let exact = kdtree.nearest(&query, 5, &squared_euclidean).unwrap();
+        assert_eq!(
+            kdtree.approx_nearest(&query, 5, &squared_euclidean, 0.0).unwrap(),
+            exact
+        );
+        let approx = kdtree.approx_nearest(&query, 5, &squared_euclidean, 0.5).unwrap();
+        assert_eq!(approx.len(), 5);
+        for (a, e) in approx.iter().zip(&exact) {
+            assert!(a.0 <= e.0 * 1.5);
+        }
+    }
+
+    assert_eq!(
+        kdtree.approx_nearest(&[0.0, 0.0, 0.0], 1, &squared_euclidean, -0.1),
+        Err(ErrorKind::InvalidEpsilon)
+    );
+}
+
+#[test]
+fn nearest_with_budget_reports_whether_it_finished() {
+    let mut kdtree = KdTree::with_capacity(2, 4);
+    for i in 0..400 {
+        kdtree.add([(i % 20) as f64, (i / 20) as f64], i).unwrap();
+    }
+    let exact = kdtree.nearest(&[7.3, 11.6], 5, &squared_euclidean).unwrap();
+
+    let budgeted = kdtree
+        .nearest_with_budget(&[7.3, 11.6], 5, &squared_euclidean, 1000)
+        .unwrap();
+    assert!(budgeted.complete);
+    assert_eq!(budgeted.neighbours, exact);
+
+    let budgeted = kdtree
+        .nearest_with_budget(&[7.3, 11.6], 5, &squared_euclidean, 1)
+        .unwrap();
+    assert!(!budgeted.complete);
+    assert!(budgeted.neighbours.len() <= 4);
+    assert!(!budgeted.neighbours.is_empty());
+    assert!(budgeted.neighbours[0].0 >= exact[0].0);
+
+    let budgeted = kdtree
+        .nearest_with_budget(&[7.3, 11.6], 5, &squared_euclidean, 0)
+        .unwrap();
+    assert!(!budgeted.complete);
+    assert!(budgeted.neighbours.is_empty());
+}
+
+#[test]
+fn nearest_within_caps_both_count_and_distance() {
+    let mut kdtree = KdTree::with_capacity(2, 2);
+    for i in 0..50 {
+        kdtree.add([i as f64, 0.0], i).unwrap();
+    }
+    assert_eq!(
+        kdtree
+            .nearest_within(&[10.2, 0.0], 3, 100.0, &squared_euclidean)
+            .unwrap(),
+        kdtree.nearest(&[10.2, 0.0], 3, &squared_euclidean).unwrap()
+    );
+    let found = kdtree
+        .nearest_within(&[10.2, 0.0], 10, 2.0, &squared_euclidean)
+        .unwrap();
+    assert_eq!(found.iter().map(|&(_, &i)| i).collect::<Vec<_>>(), vec![10, 11, 9]);
+    assert!(kdtree
+        .nearest_within(&[100.0, 0.0], 5, 1.0, &squared_euclidean)
+        .unwrap()
+        .is_empty());
+    assert!(kdtree
+        .nearest_within(&[10.0, 0.0], 0, 1.0, &squared_euclidean)
+        .unwrap()
+        .is_empty());
+    assert_eq!(
+        kdtree.nearest_within(&[1.0], 1, 1.0, &squared_euclidean),
+        Err(ErrorKind::WrongDimension)
+    );
+}
+
+#[test]
+fn it_finds_nearest_neighbours_passing_a_filter() {
+    let mut kdtree = KdTree::with_capacity(1, 2);
+    for i in 0..100 {
+        kdtree.add([i as f64], i).unwrap();
+    }
+    // the 20 nearest points all fail the filter
+    let passing = |&i: &usize| i >= 20 && i % 10 == 3;
+    assert_eq!(
+        kdtree.nearest_filtered(&[0.0], 3, &squared_euclidean, passing).unwrap(),
+        vec![(529.0, &23), (1089.0, &33), (1849.0, &43)]
+    );
+    assert_eq!(
+        kdtree
+            .nearest_filtered(&[0.0], 20, &squared_euclidean, passing)
+            .unwrap()
+            .len(),
+        8
+    );
+    assert_eq!(
+        kdtree.nearest_filtered(&[f64::NAN], 1, &squared_euclidean, passing),
+        Err(ErrorKind::NonFiniteCoordinate)
+    );
+}
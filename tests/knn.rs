@@ -0,0 +1,80 @@
+extern crate kdtree;
+
+use kdtree::distance::squared_euclidean;
+use kdtree::KdTree;
+use kdtree::Weighting;
+
+fn labelled() -> KdTree<f64, (char, f64), [f64; 1]> {
+    let points = vec![
+        ([0f64], ('a', 1.0)),
+        ([1f64], ('a', 2.0)),
+        ([5f64], ('b', 10.0)),
+        ([6f64], ('b', 20.0)),
+        ([7f64], ('b', 30.0)),
+    ];
+    KdTree::bulk_load(1, 2, points).unwrap()
+}
+
+#[test]
+fn it_classifies_by_majority() {
+    let kdtree = labelled();
+    let label = |data: &(char, f64)| data.0;
+    assert_eq!(
+        kdtree
+            .knn_classify(&[2f64], 5, &squared_euclidean, Weighting::Uniform, label)
+            .unwrap(),
+        Some('b')
+    );
+    assert_eq!(
+        kdtree
+            .knn_classify(&[2f64], 5, &squared_euclidean, Weighting::InverseDistance, label)
+            .unwrap(),
+        Some('a')
+    );
+    // tie between one `a` and one `b`, the nearer one wins
+    assert_eq!(
+        kdtree
+            .knn_classify(&[3.5f64], 2, &squared_euclidean, Weighting::Uniform, label)
+            .unwrap(),
+        Some('b')
+    );
+    // exact matches outweigh everything else
+    assert_eq!(
+        kdtree
+            .knn_classify(&[1f64], 5, &squared_euclidean, Weighting::InverseDistance, label)
+            .unwrap(),
+        Some('a')
+    );
+
+    let empty: KdTree<f64, (char, f64), [f64; 1]> = KdTree::new(1);
+    assert_eq!(
+        empty
+            .knn_classify(&[0f64], 3, &squared_euclidean, Weighting::Uniform, label)
+            .unwrap(),
+        None
+    );
+}
+
+#[test]
+fn it_regresses_to_the_weighted_mean() {
+    let kdtree = labelled();
+    let value = |data: &(char, f64)| data.1;
+    assert_eq!(
+        kdtree
+            .knn_regress(&[6f64], 3, &squared_euclidean, Weighting::Uniform, value)
+            .unwrap(),
+        Some(20.0)
+    );
+    assert_eq!(
+        kdtree
+            .knn_regress(&[5.5f64], 2, &squared_euclidean, Weighting::InverseDistance, value)
+            .unwrap(),
+        Some(15.0)
+    );
+    assert_eq!(
+        kdtree
+            .knn_regress(&[6f64], 3, &squared_euclidean, Weighting::InverseDistance, value)
+            .unwrap(),
+        Some(20.0)
+    );
+}
@@ -0,0 +1,52 @@
+extern crate kdtree;
+extern crate rand;
+
+use kdtree::ErrorKind;
+use kdtree::KdTree;
+
+#[test]
+fn it_ranks_by_weighted_sum() {
+    let points = vec![
+        ([1f64, 5f64], 'a'),
+        ([2f64, 2f64], 'b'),
+        ([5f64, 1f64], 'c'),
+        ([3f64, 3f64], 'd'),
+    ];
+    let kdtree = KdTree::bulk_load(2, 1, points).unwrap();
+    assert_eq!(
+        kdtree.top_k_by_linear(&[1f64, 0f64], 2).unwrap(),
+        vec![(5f64, &'c'), (3f64, &'d')]
+    );
+    assert_eq!(
+        kdtree.top_k_by_linear(&[-1f64, 1f64], 10).unwrap(),
+        vec![(4f64, &'a'), (0f64, &'b'), (0f64, &'d'), (-4f64, &'c')]
+    );
+    assert_eq!(kdtree.top_k_by_linear(&[1f64], 1), Err(ErrorKind::WrongDimension));
+}
+
+#[test]
+fn it_matches_brute_force() {
+    let points: Vec<([f64; 3], usize)> = (0..500).map(|i| (rand::random(), i)).collect();
+    let kdtree = KdTree::bulk_load(3, 8, points.clone()).unwrap();
+    for _ in 0..20 {
+        let weights: [f64; 3] = [
+            rand::random::<f64>() - 0.5,
+            rand::random::<f64>() - 0.5,
+            rand::random::<f64>() - 0.5,
+        ];
+        let score = |p: &[f64; 3]| p.iter().zip(&weights).map(|(x, w)| x * w).sum::<f64>();
+        let mut expected: Vec<f64> = points.iter().map(|(p, _)| score(p)).collect();
+        expected.sort_by(|a, b| b.partial_cmp(a).unwrap());
+        expected.truncate(10);
+        let found: Vec<f64> = kdtree
+            .top_k_by_linear(&weights, 10)
+            .unwrap()
+            .into_iter()
+            .map(|(s, _)| s)
+            .collect();
+        for (f, e) in found.iter().zip(&expected) {
+            assert!((f - e).abs() < 1e-12);
+        }
+        assert_eq!(found.len(), 10);
+    }
+}
@@ -0,0 +1,45 @@
+#![cfg(feature = "metric_tests")]
+
+extern crate kdtree;
+
+use kdtree::distance::{chebyshev, manhattan, minkowski, squared_euclidean};
+use kdtree::metric_tests::{check_metric, MetricViolation};
+
+fn euclidean(a: &[f64], b: &[f64]) -> f64 {
+    squared_euclidean(a, b).sqrt()
+}
+
+#[test]
+fn it_accepts_proper_metrics() {
+    assert_eq!(check_metric(&euclidean, 3, 1000), vec![]);
+    assert_eq!(check_metric(&manhattan::<f64>, 5, 1000), vec![]);
+    assert_eq!(check_metric(&chebyshev::<f64>, 4, 1000), vec![]);
+    for &p in &[1.0, 1.5, 3.0, f64::INFINITY] {
+        assert_eq!(check_metric(&minkowski(p), 3, 1000), vec![]);
+    }
+}
+
+#[test]
+fn it_reports_squared_distances_breaking_the_triangle_inequality() {
+    let violations = check_metric(&squared_euclidean::<f64>, 2, 1000);
+    assert_eq!(violations.len(), 1);
+    match &violations[0] {
+        MetricViolation::TriangleInequality { a, b, c } => {
+            assert!(squared_euclidean(a, c) > squared_euclidean(a, b) + squared_euclidean(b, c));
+        }
+        other => panic!("unexpected violation {:?}", other),
+    }
+}
+
+#[test]
+fn it_reports_distances_breaking_pruning() {
+    // points far from the origin along the first axis seem farther away
+    let broken = |a: &[f64], b: &[f64]| euclidean(a, b) * (1.0 + b[0].abs());
+    let violations = check_metric(&broken, 2, 1000);
+    assert!(violations
+        .iter()
+        .any(|v| matches!(v, MetricViolation::NotSymmetric { .. })));
+    assert!(violations
+        .iter()
+        .any(|v| matches!(v, MetricViolation::BoxLowerBound { .. })));
+}
@@ -0,0 +1,39 @@
+extern crate kdtree;
+extern crate rand;
+
+use kdtree::distance::{chebyshev, manhattan, minkowski};
+use kdtree::KdTree;
+
+fn check<F: Fn(&[f64], &[f64]) -> f64>(distance: &F) {
+    let points: Vec<([f64; 3], usize)> = (0..400).map(|i| (rand::random(), i)).collect();
+    let kdtree = KdTree::bulk_load(3, 4, points.clone()).unwrap();
+    for query in &[[0.5, 0.5, 0.5], [0.0, 1.0, 0.3], [2.0, -1.0, 0.9]] {
+        let mut expected: Vec<f64> = points.iter().map(|(p, _)| distance(query, p)).collect();
+        expected.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let found = kdtree.nearest(query, 10, distance).unwrap();
+        assert_eq!(found.iter().map(|r| r.0).collect::<Vec<_>>(), expected[..10].to_vec());
+        assert_eq!(kdtree.within(query, expected[30], distance).unwrap().len(), 31);
+    }
+}
+
+#[test]
+fn it_finds_neighbours_like_brute_force() {
+    check(&manhattan);
+    check(&chebyshev);
+    check(&minkowski(1.5));
+    check(&minkowski(4.0));
+}
+
+#[test]
+fn it_matches_the_special_orders() {
+    let (a, b): ([f64; 3], [f64; 3]) = ([0.5, -2.0, 3.0], [1.0, 1.0, -1.0]);
+    assert!((minkowski(1.0)(&a, &b) - manhattan(&a, &b)).abs() < 1e-12);
+    assert!((minkowski(2.0)(&a, &b) - 25.25f64.sqrt()).abs() < 1e-12);
+    assert_eq!(minkowski(f64::INFINITY)(&a, &b), chebyshev(&a, &b));
+}
+
+#[test]
+#[should_panic(expected = "the order must be at least 1")]
+fn it_rejects_orders_below_one() {
+    let _ = minkowski(0.5);
+}
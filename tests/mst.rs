@@ -0,0 +1,94 @@
+extern crate kdtree;
+extern crate rand;
+
+use kdtree::KdTree;
+
+/// Prim's algorithm over the complete graph.
+fn brute_force_weight(points: &[[f64; 2]]) -> f64 {
+    let dist = |a: &[f64; 2], b: &[f64; 2]| ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2)).sqrt();
+    let mut in_tree = vec![false; points.len()];
+    let mut best = vec![f64::INFINITY; points.len()];
+    best[0] = 0.0;
+    let mut total = 0.0;
+    for _ in 0..points.len() {
+        let next = (0..points.len())
+            .filter(|&i| !in_tree[i])
+            .min_by(|&a, &b| best[a].partial_cmp(&best[b]).unwrap())
+            .unwrap();
+        in_tree[next] = true;
+        total += best[next];
+        for (b, p) in best.iter_mut().zip(points) {
+            *b = b.min(dist(&points[next], p));
+        }
+    }
+    total
+}
+
+#[test]
+fn it_connects_all_points_with_minimal_weight() {
+    let points: Vec<([f64; 2], usize)> = (0..400).map(|i| (rand::random(), i)).collect();
+    let kdtree = KdTree::bulk_load(2, 4, points).unwrap();
+    let ordered: Vec<[f64; 2]> = kdtree.iter().map(|(p, _)| *p).collect();
+
+    let edges = kdtree.euclidean_mst();
+    assert_eq!(edges.len(), ordered.len() - 1);
+    for window in edges.windows(2) {
+        assert!(window[0].2 <= window[1].2);
+    }
+    for &(i, j, _) in &edges {
+        assert!(i < j);
+    }
+    let total: f64 = edges.iter().map(|e| e.2).sum();
+    assert!((total - brute_force_weight(&ordered)).abs() < 1e-9);
+}
+
+#[test]
+fn it_handles_tiny_and_duplicate_inputs() {
+    let kdtree: KdTree<f64, (), [f64; 1]> = KdTree::new(1);
+    assert!(kdtree.euclidean_mst().is_empty());
+
+    let kdtree = KdTree::bulk_load(1, 2, vec![([0f64], ()), ([0f64], ()), ([3f64], ())]).unwrap();
+    assert_eq!(kdtree.euclidean_mst(), vec![(0, 1, 0f64), (0, 2, 3f64)]);
+}
+
+#[test]
+fn it_builds_the_mutual_reachability_mst() {
+    let points: Vec<([f64; 2], usize)> = (0..200).map(|i| (rand::random(), i)).collect();
+    let kdtree = KdTree::bulk_load(2, 4, points).unwrap();
+    let ordered: Vec<[f64; 2]> = kdtree.iter().map(|(p, _)| *p).collect();
+    let dist = |a: &[f64; 2], b: &[f64; 2]| ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2)).sqrt();
+
+    let core = kdtree.core_distances(5);
+    for (i, p) in ordered.iter().enumerate() {
+        let mut distances: Vec<f64> = ordered.iter().map(|q| dist(p, q)).collect();
+        distances.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(core[i], distances[4]);
+    }
+
+    // Prim's algorithm over the complete mutual reachability graph
+    let n = ordered.len();
+    let mreach = |i: usize, j: usize| dist(&ordered[i], &ordered[j]).max(core[i]).max(core[j]);
+    let mut in_tree = vec![false; n];
+    let mut best = vec![f64::INFINITY; n];
+    best[0] = 0.0;
+    let mut expected = 0.0;
+    for _ in 0..n {
+        let next = (0..n)
+            .filter(|&i| !in_tree[i])
+            .min_by(|&a, &b| best[a].partial_cmp(&best[b]).unwrap())
+            .unwrap();
+        in_tree[next] = true;
+        expected += best[next];
+        for (i, b) in best.iter_mut().enumerate() {
+            *b = b.min(mreach(next, i));
+        }
+    }
+
+    let edges = kdtree.mutual_reachability_mst(5);
+    assert_eq!(edges.len(), n - 1);
+    for &(i, j, w) in &edges {
+        assert_eq!(w, mreach(i, j));
+    }
+    let total: f64 = edges.iter().map(|e| e.2).sum();
+    assert!((total - expected).abs() < 1e-9);
+}
@@ -0,0 +1,74 @@
+extern crate kdtree;
+
+use kdtree::distance::squared_euclidean;
+use kdtree::ErrorKind;
+use kdtree::KdMultiMap;
+
+#[test]
+fn it_groups_payloads_by_coordinate() {
+    let mut map = KdMultiMap::with_capacity(2, 2);
+    map.add([0.0, 0.0], "a").unwrap();
+    map.add([1.0, 1.0], "b").unwrap();
+    map.add([0.0, 0.0], "c").unwrap();
+    map.add([2.0, 2.0], "d").unwrap();
+    map.add([0.0, 0.0], "a").unwrap();
+
+    assert_eq!(map.size(), 5);
+    assert_eq!(map.coordinates(), 3);
+    assert_eq!(map.get_all(&[0.0, 0.0]).unwrap(), &["a", "c", "a"]);
+    assert!(map.get_all(&[0.5, 0.5]).unwrap().is_empty());
+    assert_eq!(map.get_all(&[0.0]), Err(ErrorKind::WrongDimension));
+    assert_eq!(map.get_all(&[f64::NAN, 0.0]), Err(ErrorKind::NonFiniteCoordinate));
+    assert_eq!(
+        map.nearest(&[0.1, 0.1], 2, &squared_euclidean).unwrap(),
+        vec![(0.020000000000000004, &["a", "c", "a"][..]), (1.62, &["b"][..])]
+    );
+    assert_eq!(map.add([f64::NAN, 0.0], "e"), Err(ErrorKind::NonFiniteCoordinate));
+    assert_eq!(map.coordinates(), 3);
+}
+
+#[test]
+fn it_removes_one_or_all_payloads() {
+    let mut map = KdMultiMap::with_capacity(1, 1);
+    map.add([0.0], 1).unwrap();
+    map.add([0.0], 2).unwrap();
+    map.add([0.0], 1).unwrap();
+    map.add([5.0], 3).unwrap();
+
+    assert_eq!(map.remove_one(&[0.0], &1).unwrap(), Some(1));
+    assert_eq!(map.remove_one(&[0.0], &4).unwrap(), None);
+    assert_eq!(map.get_all(&[0.0]).unwrap(), &[2, 1]);
+    assert_eq!(map.remove_all(&[0.0]).unwrap(), vec![2, 1]);
+    assert_eq!(map.remove_all(&[0.0]).unwrap(), Vec::<i32>::new());
+    assert_eq!(map.size(), 1);
+    assert_eq!(map.coordinates(), 1);
+
+    map.add([7.0], 4).unwrap();
+    assert_eq!(
+        map.within(&[6.0], 1.0, &squared_euclidean).unwrap(),
+        vec![(1.0, &[3][..]), (1.0, &[4][..])]
+    );
+}
+
+#[test]
+fn it_finds_the_nearest_payloads_across_groups() {
+    let mut map = KdMultiMap::with_capacity(1, 2);
+    for i in 0..1000 {
+        map.add([0.0], i).unwrap();
+    }
+    map.add([2.0], 1000).unwrap();
+    map.add([3.0], 1001).unwrap();
+    assert_eq!(map.coordinates(), 3);
+
+    let near_two = map.nearest_payloads(&[2.4], 3, &squared_euclidean).unwrap();
+    let data: Vec<usize> = near_two.iter().map(|&(_, &i)| i).collect();
+    assert_eq!(data, vec![1000, 1001, 0]);
+
+    let near_zero = map.nearest_payloads(&[0.0], 5, &squared_euclidean).unwrap();
+    assert_eq!(near_zero, vec![(0.0, &0), (0.0, &1), (0.0, &2), (0.0, &3), (0.0, &4)]);
+    assert_eq!(
+        map.nearest_payloads(&[0.0], 2000, &squared_euclidean).unwrap().len(),
+        1002
+    );
+    assert!(map.nearest_payloads(&[0.0], 0, &squared_euclidean).unwrap().is_empty());
+}
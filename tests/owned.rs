@@ -0,0 +1,40 @@
+extern crate kdtree;
+
+use std::sync::Arc;
+use std::thread;
+
+use kdtree::distance::squared_euclidean;
+use kdtree::KdTree;
+
+#[test]
+fn owned_results_can_be_sent_to_other_threads() {
+    let mut kdtree = KdTree::with_capacity(2, 2);
+    for i in 0..20 {
+        kdtree.add([f64::from(i), 0.0], format!("point {}", i)).unwrap();
+    }
+    let kdtree = Arc::new(kdtree);
+
+    let found = kdtree.nearest_owned(&[3.25, 0.0], 2, &squared_euclidean).unwrap();
+    let handle = thread::spawn(move || {
+        found
+            .iter()
+            .map(|n| (n.distance(), n.point()[0], n.data().clone()))
+            .collect::<Vec<_>>()
+    });
+    assert_eq!(
+        handle.join().unwrap(),
+        vec![
+            (0.0625, 3.0, "point 3".to_string()),
+            (0.5625, 4.0, "point 4".to_string())
+        ]
+    );
+
+    let within = kdtree.within_owned(&[10.0, 0.0], 1.0, &squared_euclidean).unwrap();
+    assert_eq!(within.len(), 3);
+    for neighbor in &within {
+        assert_eq!(kdtree.iter().nth(neighbor.index()).unwrap().1, neighbor.data());
+    }
+    drop(kdtree);
+    assert_eq!(within[0].data(), "point 10");
+    assert!(Arc::strong_count(within[0].tree()) == 3);
+}
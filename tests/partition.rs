@@ -0,0 +1,252 @@
+extern crate kdtree;
+
+use kdtree::{Aabb, ErrorKind, KdTree, SplitPlane, StructureChange};
+
+fn tree() -> KdTree<f64, char, [f64; 2]> {
+    let mut kdtree = KdTree::with_capacity(2, 1);
+    kdtree.add([0.0, 0.0], 'a').unwrap();
+    kdtree.add([4.0, 2.0], 'b').unwrap();
+    kdtree.add([1.0, 3.0], 'c').unwrap();
+    kdtree
+}
+
+#[test]
+fn it_lists_the_split_planes() {
+    assert_eq!(
+        tree().split_planes(),
+        vec![
+            SplitPlane {
+                id: 0,
+                dimension: 0,
+                value: 2.0,
+                depth: 0,
+                bounds: Aabb {
+                    min: vec![0.0, 0.0],
+                    max: vec![4.0, 3.0],
+                },
+            },
+            SplitPlane {
+                id: 1,
+                dimension: 1,
+                value: 1.5,
+                depth: 1,
+                bounds: Aabb {
+                    min: vec![0.0, 0.0],
+                    max: vec![2.0, 3.0],
+                },
+            },
+        ]
+    );
+
+    let empty: KdTree<f64, char, [f64; 2]> = KdTree::new(2);
+    assert!(empty.split_planes().is_empty());
+}
+
+#[test]
+fn it_checks_points_against_boxes() {
+    let aabb = Aabb {
+        min: vec![0.0, 0.0],
+        max: vec![2.0, 3.0],
+    };
+    assert!(aabb.contains(&[2.0, 0.0]));
+    assert!(aabb.contains(&[1.0, 1.5]));
+    assert!(!aabb.contains(&[2.5, 1.0]));
+}
+
+#[test]
+fn it_lists_the_leaf_cells() {
+    let kdtree = tree();
+    let cells = kdtree.leaf_cells();
+    let data: Vec<&[char]> = cells.iter().map(|&(_, _, data)| data).collect();
+    assert_eq!(data, vec![&['a'][..], &['c'], &['b']]);
+    assert_eq!(
+        cells[1].0,
+        Aabb {
+            min: vec![0.0, 1.5],
+            max: vec![2.0, 3.0],
+        }
+    );
+    assert_eq!(cells[2].1, &[[4.0, 2.0]]);
+    for (cell, points, _) in &cells {
+        assert!(points.iter().all(|point| cell.contains(point)));
+    }
+
+    let empty: KdTree<f64, char, [f64; 2]> = KdTree::new(2);
+    assert!(empty.leaf_cells().is_empty());
+}
+
+#[test]
+fn it_locates_the_leaf_of_a_point() {
+    let kdtree = tree();
+    let leaf = kdtree.locate_leaf(&[0.5, 2.0]).unwrap();
+    assert_eq!(leaf.data, &['c']);
+    assert_eq!(leaf.points, &[[1.0, 3.0]]);
+    assert_eq!(
+        leaf.bounds,
+        Aabb {
+            min: vec![0.0, 1.5],
+            max: vec![2.0, 3.0],
+        }
+    );
+
+    // far away from every stored point, still in the cell it would be added to
+    let leaf = kdtree.locate_leaf(&[100.0, -100.0]).unwrap();
+    assert_eq!(leaf.data, &['b']);
+    assert!(!leaf.bounds.contains(&[100.0, -100.0]));
+
+    assert_eq!(kdtree.locate_leaf(&[1.0]), Err(ErrorKind::WrongDimension));
+    assert_eq!(
+        kdtree.locate_leaf(&[f64::NAN, 0.0]),
+        Err(ErrorKind::NonFiniteCoordinate)
+    );
+
+    let empty: KdTree<f64, char, [f64; 2]> = KdTree::new(2);
+    assert!(empty.locate_leaf(&[0.0, 0.0]).unwrap().data.is_empty());
+}
+
+#[test]
+fn it_keeps_node_ids_until_the_structure_changes() {
+    let mut kdtree = tree();
+    assert_eq!(kdtree.generation(), 2);
+    let leaf = kdtree.locate_leaf(&[0.5, 2.0]).unwrap().id;
+    let other = kdtree.locate_leaf(&[4.0, 0.0]).unwrap().id;
+    assert_eq!((leaf, other), (4, 2));
+
+    kdtree.remove(&[1.0, 3.0], &'c').unwrap();
+    kdtree.add([1.0, 3.0], 'd').unwrap();
+    assert_eq!(kdtree.generation(), 2);
+    assert_eq!(kdtree.locate_leaf(&[0.5, 2.0]).unwrap().id, leaf);
+
+    // the leaf overflows and becomes a stem, the rest of the tree keeps its IDs
+    kdtree.add([1.5, 2.5], 'e').unwrap();
+    assert_eq!(kdtree.generation(), 3);
+    assert!(kdtree.locate_leaf(&[0.5, 2.0]).unwrap().id > leaf);
+    assert_eq!(kdtree.locate_leaf(&[4.0, 0.0]).unwrap().id, other);
+    assert_eq!(
+        kdtree.split_planes().iter().map(|plane| plane.id).collect::<Vec<_>>(),
+        vec![0, 1, 4]
+    );
+}
+
+#[test]
+fn it_logs_structural_changes() {
+    let mut kdtree = tree();
+    assert_eq!(
+        kdtree.changes_since(0).unwrap(),
+        &[
+            StructureChange::Split {
+                id: 0,
+                left: 1,
+                right: 2
+            },
+            StructureChange::Split {
+                id: 1,
+                left: 3,
+                right: 4
+            },
+        ]
+    );
+    assert_eq!(kdtree.changes_since(2).unwrap(), &[]);
+    assert_eq!(kdtree.changes_since(3), None);
+
+    kdtree.clear_changes();
+    kdtree.add([1.5, 2.5], 'e').unwrap();
+    assert_eq!(kdtree.changes_since(1), None);
+    assert_eq!(
+        kdtree.changes_since(2).unwrap(),
+        &[StructureChange::Split {
+            id: 4,
+            left: 5,
+            right: 6
+        }]
+    );
+}
+
+#[test]
+fn it_collapses_empty_subtrees() {
+    let mut kdtree = tree();
+    assert_eq!(kdtree.compact(), 0);
+
+    kdtree.remove(&[0.0, 0.0], &'a').unwrap();
+    assert_eq!(kdtree.compact(), 1);
+    assert_eq!(kdtree.locate_leaf(&[0.5, 2.0]).unwrap().id, 4);
+    kdtree.remove(&[1.0, 3.0], &'c').unwrap();
+    assert_eq!(kdtree.compact(), 1);
+    assert_eq!(kdtree.stats().leaves, 1);
+    assert!(kdtree.split_planes().is_empty());
+    assert_eq!(kdtree.generation(), 4);
+    assert_eq!(
+        kdtree.changes_since(2).unwrap(),
+        &[
+            StructureChange::Collapse {
+                id: 1,
+                kept: 4,
+                removed: 3
+            },
+            StructureChange::Collapse {
+                id: 0,
+                kept: 2,
+                removed: 4
+            },
+        ]
+    );
+
+    let leaf = kdtree.locate_leaf(&[4.0, 2.0]).unwrap();
+    assert_eq!((leaf.id, leaf.data), (0, &['b'][..]));
+    kdtree.add([0.0, 0.0], 'a').unwrap();
+    kdtree.add([1.0, 3.0], 'c').unwrap();
+    assert_eq!(kdtree.size(), 3);
+    assert_eq!(kdtree.iter().count(), 3);
+}
+
+#[test]
+fn it_lists_adjacent_leaves() {
+    // cells: 'a' (id 3) below 'c' (id 4) on the left, 'b' (id 2) on the right of both
+    let kdtree = tree();
+    assert_eq!(kdtree.leaf_adjacency(), vec![(2, 3), (2, 4), (3, 4)]);
+
+    let empty: KdTree<f64, char, [f64; 2]> = KdTree::new(2);
+    assert!(empty.leaf_adjacency().is_empty());
+}
+
+#[test]
+fn it_leaves_out_cells_touching_at_a_corner() {
+    let mut kdtree: KdTree<f64, char, [f64; 2]> = KdTree::with_capacity(2, 1);
+    for &(point, data) in &[
+        ([0.0, 0.0], 'a'),
+        ([4.0, 4.0], 'b'),
+        ([0.0, 4.0], 'c'),
+        ([4.0, 0.0], 'd'),
+    ] {
+        kdtree.add(point, data).unwrap();
+    }
+    let cells = kdtree.leaf_cells();
+    let adjacency = kdtree.leaf_adjacency();
+    let ids: Vec<u64> = cells
+        .iter()
+        .map(|(cell, _, _)| {
+            kdtree
+                .locate_leaf(&[(cell.min[0] + cell.max[0]) / 2.0, (cell.min[1] + cell.max[1]) / 2.0])
+                .unwrap()
+                .id
+        })
+        .collect();
+
+    // brute force over the cells
+    let mut expected = vec![];
+    for i in 0..cells.len() {
+        for j in i + 1..cells.len() {
+            let (a, b) = (&cells[i].0, &cells[j].0);
+            let touching = (0..2).filter(|&d| a.max[d] == b.min[d] || b.max[d] == a.min[d]).count();
+            let overlapping = (0..2)
+                .filter(|&d| a.min[d].max(b.min[d]) < a.max[d].min(b.max[d]))
+                .count();
+            if touching == 1 && overlapping == 1 {
+                expected.push((ids[i].min(ids[j]), ids[i].max(ids[j])));
+            }
+        }
+    }
+    expected.sort_unstable();
+    assert_eq!(adjacency, expected);
+    assert_eq!(adjacency.len(), 4);
+}
@@ -0,0 +1,68 @@
+extern crate kdtree;
+extern crate rand;
+
+use kdtree::{ErrorKind, PeriodicKdTree};
+
+const LENGTHS: [f64; 3] = [10.0, 4.0, 7.5];
+
+fn random_tree() -> (PeriodicKdTree<f64, usize>, Vec<[f64; 3]>) {
+    let mut kdtree = PeriodicKdTree::with_capacity(LENGTHS.to_vec(), 4).unwrap();
+    let points: Vec<[f64; 3]> = (0..500)
+        .map(|_| {
+            let r: [f64; 3] = rand::random();
+            [r[0] * 10.0, r[1] * 4.0, r[2] * 7.5]
+        })
+        .collect();
+    for (i, point) in points.iter().enumerate() {
+        kdtree.add(point, i).unwrap();
+    }
+    (kdtree, points)
+}
+
+#[test]
+fn it_matches_brute_force_near_the_edges() {
+    let (kdtree, points) = random_tree();
+    for query in &[[0.05, 3.95, 7.4], [9.9, 0.1, 0.0], [5.0, 2.0, 3.7], [-0.2, 4.1, 15.1]] {
+        let mut expected: Vec<f64> = points.iter().map(|p| kdtree.squared_distance(query, p)).collect();
+        expected.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        let found = kdtree.nearest(query, 10).unwrap();
+        // queries outside of the box are wrapped first, which may round differently
+        assert_eq!(found.len(), 10);
+        for (&(d, _), &e) in found.iter().zip(&expected) {
+            assert!((d - e).abs() < 1e-9);
+        }
+
+        // the radius is a squared distance, like the distances returned
+        let within = kdtree.within(query, 2.25).unwrap();
+        let count = |radius: f64| expected.iter().filter(|&&d| d <= radius).count();
+        assert!(count(2.25 - 1e-9) <= within.len() && within.len() <= count(2.25 + 1e-9));
+        assert!(within.windows(2).all(|w| w[0].0 <= w[1].0));
+    }
+}
+
+#[test]
+fn it_wraps_points_into_the_box() {
+    let mut kdtree = PeriodicKdTree::new(vec![10.0, 10.0]).unwrap();
+    kdtree.add(&[-1.0, 25.0], 'a').unwrap();
+    kdtree.add(&[5.0, 5.0], 'b').unwrap();
+    assert_eq!(kdtree.iter().find(|entry| *entry.1 == 'a').unwrap().0, &[9.0, 5.0]);
+    assert_eq!(kdtree.nearest(&[0.5, 5.0], 1).unwrap(), vec![(2.25, &'a')]);
+    assert_eq!(kdtree.squared_distance(&[0.5, 0.5], &[9.5, 9.5]), 2.0);
+}
+
+#[test]
+fn it_rejects_invalid_boxes_and_points() {
+    assert_eq!(
+        PeriodicKdTree::<f64, char>::new(vec![1.0, 0.0]).map(|tree| tree.size()),
+        Err(ErrorKind::InvalidPeriod)
+    );
+    assert_eq!(
+        PeriodicKdTree::<f64, char>::new(vec![]).map(|tree| tree.size()),
+        Err(ErrorKind::InvalidPeriod)
+    );
+    let mut kdtree = PeriodicKdTree::new(vec![1.0, 1.0]).unwrap();
+    assert_eq!(kdtree.add(&[0.5], 'a'), Err(ErrorKind::WrongDimension));
+    assert_eq!(kdtree.add(&[f64::NAN, 0.5], 'a'), Err(ErrorKind::NonFiniteCoordinate));
+    assert_eq!(kdtree.nearest(&[0.5], 1), Err(ErrorKind::WrongDimension));
+}
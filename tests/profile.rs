@@ -0,0 +1,70 @@
+extern crate kdtree;
+
+use std::cell::Cell;
+
+use kdtree::distance::squared_euclidean;
+use kdtree::{ErrorKind, KdTree};
+
+#[test]
+fn it_profiles_queries() {
+    let points: Vec<([f64; 2], usize)> = (0..100)
+        .map(|i| ([f64::from(i as u32 % 10), f64::from(i as u32 / 10)], i))
+        .collect();
+    let kdtree = KdTree::bulk_load(2, 4, points).unwrap();
+    let queries: Vec<[f64; 2]> = (0..10).map(|i| [f64::from(i), 4.5]).collect();
+
+    // a fake clock where the `i`-th query takes `i + 1` ticks
+    let now = Cell::new(0.0);
+    let calls = Cell::new(0);
+    let timer = || {
+        calls.set(calls.get() + 1);
+        if calls.get() % 2 == 0 {
+            now.set(now.get() + f64::from(calls.get() / 2));
+        }
+        now.get()
+    };
+    let report = kdtree.profile_queries(&queries, 3, &squared_euclidean, timer).unwrap();
+
+    assert_eq!(report.queries, 10);
+    assert_eq!(report.k, 3);
+    assert!(report.leaves_scanned >= 10);
+    assert!(report.nodes_visited >= report.leaves_scanned);
+    assert!(report.points_evaluated >= 30);
+    assert!(report.points_evaluated < 1000);
+    assert_eq!(report.latency_p50, 5.0);
+    assert_eq!(report.latency_p90, 9.0);
+    assert_eq!(report.latency_p99, 10.0);
+    assert_eq!(report.latency_max, 10.0);
+
+    let json = report.to_json_string();
+    assert!(json.starts_with("{\"queries\":10,\"k\":3,"));
+    assert!(json.ends_with("\"latency_p50\":5,\"latency_p90\":9,\"latency_p99\":10,\"latency_max\":10}"));
+    assert!(report.to_string().starts_with("queries: 10 (k = 3)\n"));
+}
+
+#[test]
+fn it_rejects_invalid_queries_up_front() {
+    let kdtree: KdTree<f64, usize, [f64; 2]> = KdTree::new(2);
+    let queries = vec![vec![0.0, 0.0], vec![0.0]];
+    let calls = Cell::new(0);
+    let result = kdtree.profile_queries(&queries, 1, &squared_euclidean, || {
+        calls.set(calls.get() + 1);
+        0.0
+    });
+    assert_eq!(result, Err(ErrorKind::WrongDimensionAt(1)));
+    assert_eq!(calls.get(), 0);
+}
+
+#[test]
+fn it_counts_the_work_of_nearest() {
+    let points: Vec<([f64; 2], usize)> = (0..50).map(|i| ([f64::from(i as u32), 0.0], i)).collect();
+    let kdtree = KdTree::bulk_load(2, 4, points).unwrap();
+    let queries = vec![[10.0, 0.0], [40.0, 1.0]];
+
+    // asking for every point scans every leaf, once
+    let report = kdtree
+        .profile_queries(&queries, 50, &squared_euclidean, || 0.0)
+        .unwrap();
+    assert_eq!(report.points_evaluated, 100);
+    assert_eq!(report.leaves_scanned, 2 * kdtree.stats().leaves);
+}
@@ -0,0 +1,63 @@
+extern crate kdtree;
+extern crate rand;
+
+use std::cell::Cell;
+
+use kdtree::distance::squared_euclidean;
+use kdtree::{KdTree, QuantizedKdTree};
+
+#[test]
+fn it_refines_to_exact_neighbours() {
+    let points: Vec<[f64; 3]> = (0..500)
+        .map(|_| {
+            [
+                rand::random::<f64>(),
+                rand::random::<f64>() * 1000.0,
+                rand::random::<f64>(),
+            ]
+        })
+        .collect();
+    let kdtree = KdTree::bulk_load(3, 8, points.iter().cloned().zip(0..).collect()).unwrap();
+    let quantized = QuantizedKdTree::from_tree(kdtree.clone());
+    assert_eq!(quantized.size(), 500);
+
+    let refined = Cell::new(0);
+    for _ in 0..20 {
+        let query = [
+            rand::random::<f64>(),
+            rand::random::<f64>() * 1000.0,
+            rand::random::<f64>(),
+        ];
+        let expected = kdtree.nearest(&query, 5, &squared_euclidean).unwrap();
+        let found = quantized
+            .nearest_refined(&query, 5, &squared_euclidean, |&i: &usize| {
+                refined.set(refined.get() + 1);
+                points[i]
+            })
+            .unwrap();
+        assert_eq!(found, expected);
+
+        let approximate = quantized.nearest(&query, 5, &squared_euclidean).unwrap();
+        assert_eq!(approximate.len(), 5);
+        for ((approx, _), (exact, _)) in approximate.iter().zip(&expected) {
+            assert!((approx.sqrt() - exact.sqrt()).abs() < 0.01);
+        }
+    }
+    assert!(refined.get() < 20 * 50);
+}
+
+#[test]
+fn it_handles_degenerate_leaves() {
+    let mut kdtree = KdTree::new(2);
+    kdtree.add([1.0, 1.0], 'a').unwrap();
+    kdtree.add([1.0, 1.0], 'b').unwrap();
+    kdtree.add([1.0, 2.0], 'c').unwrap();
+    let quantized = QuantizedKdTree::from_tree(kdtree);
+
+    let found = quantized.nearest(&[1.0, 1.9], 1, &squared_euclidean).unwrap();
+    assert_eq!(found[0].1, &'c');
+    assert!(quantized.nearest(&[1.0], 1, &squared_euclidean).is_err());
+
+    let empty: QuantizedKdTree<f64, char> = QuantizedKdTree::from_tree(KdTree::<f64, char, [f64; 2]>::new(2));
+    assert!(empty.nearest(&[0.0, 0.0], 3, &squared_euclidean).unwrap().is_empty());
+}
@@ -0,0 +1,279 @@
+extern crate kdtree;
+
+use kdtree::{KdTree, PointStyle, RenderError, RenderOverlay, SvgOptions, TikzOptions};
+
+fn tree() -> KdTree<f64, char, [f64; 2]> {
+    let mut kdtree = KdTree::with_capacity(2, 1);
+    kdtree.add([0.0, 0.0], 'a').unwrap();
+    kdtree.add([4.0, 2.0], 'b').unwrap();
+    kdtree.add([1.0, 3.0], 'c').unwrap();
+    kdtree
+}
+
+#[test]
+fn it_renders_tikz() {
+    let kdtree = tree();
+    let tikz = kdtree.to_tikz_string(&TikzOptions::default()).unwrap();
+    assert_eq!(
+        tikz,
+        "\\begin{tikzpicture}[scale=1]
+  \\draw[gray] (0,0) rectangle (4,3);
+  \\draw (2,0) -- (2,3);
+  \\draw (0,1.5) -- (2,1.5);
+  \\fill (0,0) circle (0.05);
+  \\fill (1,3) circle (0.05);
+  \\fill (4,2) circle (0.05);
+\\end{tikzpicture}
+"
+    );
+    assert_eq!(kdtree.display_tikz(TikzOptions::default()).to_string(), tikz);
+}
+
+#[test]
+fn it_renders_svg() {
+    let options = SvgOptions {
+        width: 100.0,
+        height: 100.0,
+        margin: 10.0,
+        point_radius: 2.0,
+        ..SvgOptions::default()
+    };
+    let svg = tree().to_svg_string(&options).unwrap();
+    assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"100\" height=\"100\""));
+    assert!(svg.contains("<rect x=\"10\" y=\"30\" width=\"80\" height=\"60\" fill=\"none\" stroke=\"gray\"/>"));
+    assert!(svg.contains("<line x1=\"50\" y1=\"90\" x2=\"50\" y2=\"30\" stroke=\"black\"/>"));
+    assert!(svg.contains("<circle cx=\"90\" cy=\"50\" r=\"2\" fill=\"black\"/>"));
+    assert_eq!(svg.matches("<circle").count(), 3);
+    assert!(svg.ends_with("</svg>\n"));
+    assert_eq!(tree().display_svg(options).to_string(), svg);
+}
+
+#[test]
+fn it_reports_what_cannot_be_rendered() {
+    let mut kdtree = KdTree::new(3);
+    kdtree.add([0.0, 0.0, 0.0], 'a').unwrap();
+    assert_eq!(
+        kdtree.to_tikz_string(&TikzOptions::default()),
+        Err(RenderError::WrongDimension(3))
+    );
+    assert_eq!(
+        kdtree.to_svg_string(&SvgOptions::default()),
+        Err(RenderError::WrongDimension(3))
+    );
+
+    let empty: KdTree<f64, char, [f64; 2]> = KdTree::new(2);
+    assert_eq!(
+        empty.to_tikz_string(&TikzOptions::default()).unwrap(),
+        "\\begin{tikzpicture}[scale=1]\n\\end{tikzpicture}\n"
+    );
+}
+
+#[test]
+fn it_styles_points_by_their_data() {
+    let style = |&data: &char| PointStyle {
+        color: if data == 'b' { "red" } else { "black" }.to_string(),
+        label: if data == 'c' { Some("c & <d>".to_string()) } else { None },
+    };
+    let kdtree = tree();
+    let tikz = kdtree.to_tikz_string_with(&TikzOptions::default(), style).unwrap();
+    assert!(tikz.contains("  \\fill (0,0) circle (0.05);\n"));
+    assert!(tikz.contains("  \\fill[red] (4,2) circle (0.05);\n"));
+    assert!(tikz.contains("  \\node[above right] at (1,3) {c & <d>};\n"));
+
+    let svg = kdtree.to_svg_string_with(&SvgOptions::default(), style).unwrap();
+    assert_eq!(svg.matches("fill=\"red\"").count(), 1);
+    assert!(svg.contains(">c &amp; &lt;d&gt;</text>"));
+}
+
+#[test]
+fn it_draws_overlays_on_top_of_the_tree() {
+    let overlays = vec![
+        RenderOverlay::circle([1.0, 1.0], 2.0),
+        RenderOverlay::rect([0.0, 0.0], [2.0, 1.0]),
+        RenderOverlay::point([1.0, 1.0]),
+    ];
+    let tikz = tree()
+        .to_tikz_string(&TikzOptions {
+            overlays: overlays.clone(),
+            ..TikzOptions::default()
+        })
+        .unwrap();
+    assert!(tikz.contains("  \\draw[blue] (1,1) circle (2);\n"));
+    assert!(tikz.contains("  \\draw[blue] (0,0) rectangle (2,1);\n"));
+    assert!(tikz.contains("  \\fill[blue] (1,1) circle (0.05);\n\\end{tikzpicture}\n"));
+
+    // the circle reaches beyond the tree, the view grows to [-1, 5] x [-1, 3]
+    let svg = tree()
+        .to_svg_string(&SvgOptions {
+            width: 60.0,
+            height: 40.0,
+            margin: 0.0,
+            overlays,
+            ..SvgOptions::default()
+        })
+        .unwrap();
+    assert!(svg.contains("<circle cx=\"20\" cy=\"20\" r=\"20\" fill=\"none\" stroke=\"blue\"/>"));
+    assert!(svg.contains("<rect x=\"10\" y=\"20\" width=\"20\" height=\"10\" fill=\"none\" stroke=\"blue\"/>"));
+    assert!(svg.contains("<circle cx=\"20\" cy=\"20\" r=\"3\" fill=\"blue\"/>"));
+
+    let empty: KdTree<f64, char, [f64; 2]> = KdTree::new(2);
+    let svg = empty
+        .to_svg_string(&SvgOptions {
+            overlays: vec![RenderOverlay::point([0.0, 0.0])],
+            ..SvgOptions::default()
+        })
+        .unwrap();
+    assert_eq!(svg.matches("fill=\"blue\"").count(), 1);
+
+    let options = TikzOptions {
+        overlays: vec![RenderOverlay::circle([0.0, 0.0], f64::NAN)],
+        ..TikzOptions::default()
+    };
+    assert_eq!(tree().to_tikz_string(&options), Err(RenderError::NonFiniteCoordinate));
+}
+
+#[test]
+fn it_limits_the_depth_and_region_drawn() {
+    let kdtree = tree();
+    let shallow = kdtree
+        .to_tikz_string(&TikzOptions {
+            max_depth: Some(0),
+            ..TikzOptions::default()
+        })
+        .unwrap();
+    assert!(shallow.contains("  \\draw (2,0) -- (2,3);\n"));
+    assert!(!shallow.contains("1.5"));
+    assert_eq!(shallow.matches("\\fill").count(), 3);
+
+    let clipped = kdtree
+        .to_tikz_string(&TikzOptions {
+            clip: Some(([1.0, 1.0], [5.0, 5.0])),
+            ..TikzOptions::default()
+        })
+        .unwrap();
+    assert_eq!(
+        clipped,
+        "\\begin{tikzpicture}[scale=1]
+  \\draw[gray] (1,1) rectangle (4,3);
+  \\draw (2,1) -- (2,3);
+  \\draw (1,1.5) -- (2,1.5);
+  \\fill (1,3) circle (0.05);
+  \\fill (4,2) circle (0.05);
+\\end{tikzpicture}
+"
+    );
+
+    let outside = kdtree
+        .to_svg_string(&SvgOptions {
+            clip: Some(([10.0, 10.0], [20.0, 20.0])),
+            ..SvgOptions::default()
+        })
+        .unwrap();
+    assert!(!outside.contains("<rect"));
+    assert!(!outside.contains("<circle"));
+
+    let options = SvgOptions {
+        clip: Some(([0.0, 0.0], [f64::INFINITY, 1.0])),
+        ..SvgOptions::default()
+    };
+    assert_eq!(kdtree.to_svg_string(&options), Err(RenderError::NonFiniteCoordinate));
+}
+
+#[test]
+fn it_rounds_numbers_to_the_given_precision() {
+    let mut kdtree = KdTree::with_capacity(2, 1);
+    kdtree.add([1.0 / 3.0, -1e-9], 'a').unwrap();
+    kdtree.add([2.0, 1e20], 'b').unwrap();
+    let options = TikzOptions {
+        precision: Some(2),
+        ..TikzOptions::default()
+    };
+    assert_eq!(
+        kdtree.to_tikz_string(&options).unwrap(),
+        "\\begin{tikzpicture}[scale=1]
+  \\draw[gray] (0.33,0) rectangle (2,100000000000000000000);
+  \\draw (0.33,50000000000000000000) -- (2,50000000000000000000);
+  \\fill (0.33,0) circle (0.05);
+  \\fill (2,100000000000000000000) circle (0.05);
+\\end{tikzpicture}
+"
+    );
+
+    let full = kdtree.to_tikz_string(&TikzOptions::default()).unwrap();
+    assert!(full.contains("(0.3333333333333333,-0.000000001)"));
+    assert!(!full.contains("e-") && !full.contains("e20"));
+
+    let svg = tree()
+        .to_svg_string(&SvgOptions {
+            width: 200.0,
+            height: 100.0,
+            margin: 0.0,
+            precision: Some(1),
+            ..SvgOptions::default()
+        })
+        .unwrap();
+    assert!(svg.contains("<line x1=\"66.7\" y1=\"100\" x2=\"66.7\" y2=\"0\" stroke=\"black\"/>"));
+    assert!(svg.contains("<circle cx=\"133.3\" cy=\"33.3\" r=\"3\" fill=\"black\"/>"));
+}
+
+#[test]
+fn it_draws_ticks_grid_and_scale_bar() {
+    let options = TikzOptions {
+        ticks: true,
+        grid: true,
+        scale_bar: true,
+        ..TikzOptions::default()
+    };
+    let tikz = tree().to_tikz_string(&options).unwrap();
+    assert_eq!(tikz.matches("gray!30").count(), 5 + 4);
+    assert!(tikz.starts_with("\\begin{tikzpicture}[scale=1]\n  \\draw[very thin, gray!30] (0,0) -- (0,3);\n"));
+    assert!(tikz.contains("  \\draw (4,0) -- ++(0,-2pt) node[below] {4};\n"));
+    assert!(tikz.contains("  \\draw (0,3) -- ++(-2pt,0) node[left] {3};\n"));
+    assert!(
+        tikz.ends_with("  \\draw[|-|] ([yshift=-8mm]0,0) -- ++(1,0) node[midway, below] {1};\n\\end{tikzpicture}\n")
+    );
+
+    // spacing follows the extent, labels only get the decimals they need
+    let mut small = KdTree::new(2);
+    small.add([0.05, 0.0], 'a').unwrap();
+    small.add([0.5, 0.12], 'b').unwrap();
+    let tikz = small.to_tikz_string(&options).unwrap();
+    assert!(tikz.contains("  \\draw (0.1,0) -- ++(0,-2pt) node[below] {0.1};\n"));
+    assert!(tikz.contains("  \\draw (0.3,0) -- ++(0,-2pt) node[below] {0.3};\n"));
+    assert_eq!(tikz.matches("node[below]").count(), 5);
+    assert!(tikz.contains("++(0.1,0) node[midway, below] {0.1};"));
+}
+
+#[test]
+fn it_writes_the_structure_as_dot() {
+    let mut kdtree = tree();
+    kdtree.add([2.5, 1.0], '"').unwrap();
+    assert_eq!(
+        kdtree.display_dot().to_string(),
+        r#"digraph kdtree {
+  node [shape=box, fontname=monospace];
+  n0 [label="stem 0, size 4\lsplit dimension 0 at 2\l"];
+  n0 -> n1 [label=left];
+  n0 -> n2 [label=right];
+  n1 [label="stem 1, size 2\lsplit dimension 1 at 1.5\l"];
+  n1 -> n3 [label=left];
+  n1 -> n4 [label=right];
+  n3 [label="leaf 3, size 1\l(0, 0) 'a'\l"];
+  n4 [label="leaf 4, size 1\l(1, 3) 'c'\l"];
+  n2 [label="stem 2, size 2\lsplit dimension 0 at 3.25\l"];
+  n2 -> n5 [label=left];
+  n2 -> n6 [label=right];
+  n5 [label="leaf 5, size 1\l(2.5, 1) '\"'\l"];
+  n6 [label="leaf 6, size 1\l(4, 2) 'b'\l"];
+}
+"#
+    );
+
+    // any dimension size works
+    let mut kdtree = KdTree::new(3);
+    kdtree.add([1.0, 2.0, 3.0], 7).unwrap();
+    assert!(kdtree
+        .display_dot()
+        .to_string()
+        .contains("n0 [label=\"leaf 0, size 1\\l(1, 2, 3) 7\\l\"];"));
+}
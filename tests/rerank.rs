@@ -0,0 +1,51 @@
+extern crate kdtree;
+extern crate rand;
+
+use kdtree::distance::{manhattan, squared_euclidean};
+use kdtree::{ErrorKind, KdTree};
+
+#[test]
+fn it_ranks_candidates_by_the_second_distance() {
+    let points: Vec<([f64; 2], usize)> = (0..500).map(|i| (rand::random(), i)).collect();
+    let kdtree = KdTree::bulk_load(2, 4, points.clone()).unwrap();
+    let query = [0.3, 0.7];
+
+    // all points as candidates gives the exact neighbours by the second distance
+    let found = kdtree
+        .nearest_reranked(&query, 5, 500, &squared_euclidean, &manhattan)
+        .unwrap();
+    let mut expected: Vec<f64> = points.iter().map(|(p, _)| manhattan(&query, p)).collect();
+    expected.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    assert_eq!(found.iter().map(|r| r.0).collect::<Vec<_>>(), expected[..5].to_vec());
+
+    // the same order as the first distance keeps its neighbours
+    let euclidean = |a: &[f64], b: &[f64]| squared_euclidean(a, b).sqrt();
+    let found = kdtree
+        .nearest_reranked(&query, 4, 0, &squared_euclidean, &euclidean)
+        .unwrap();
+    let nearest = kdtree.nearest(&query, 4, &squared_euclidean).unwrap();
+    assert_eq!(
+        found.iter().map(|r| r.1).collect::<Vec<_>>(),
+        nearest.iter().map(|r| r.1).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn it_only_ranks_the_candidates() {
+    let mut kdtree = KdTree::new(1);
+    for i in 0..10 {
+        kdtree.add([i as f64], i).unwrap();
+    }
+    // prefers points far from the query, but only sees the three nearest
+    let farther_first = |a: &[f64], b: &[f64]| -(a[0] - b[0]).abs();
+    assert_eq!(
+        kdtree
+            .nearest_reranked(&[0.0], 2, 3, &squared_euclidean, &farther_first)
+            .unwrap(),
+        vec![(-2.0, &2), (-1.0, &1)]
+    );
+    assert_eq!(
+        kdtree.nearest_reranked(&[0.0, 1.0], 2, 3, &squared_euclidean, &farther_first),
+        Err(ErrorKind::WrongDimension)
+    );
+}
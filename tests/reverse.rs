@@ -0,0 +1,55 @@
+extern crate kdtree;
+extern crate rand;
+
+use kdtree::distance::squared_euclidean;
+use kdtree::KdTree;
+
+#[test]
+fn it_matches_brute_force() {
+    let points: Vec<([f64; 2], usize)> = (0..300).map(|i| (rand::random(), i)).collect();
+    let kdtree = KdTree::bulk_load(2, 4, points.clone()).unwrap();
+
+    for _ in 0..20 {
+        let query: [f64; 2] = rand::random();
+        for &k in &[1, 3, 8] {
+            let mut expected: Vec<usize> = points
+                .iter()
+                .filter(|(p, i)| {
+                    let radius = squared_euclidean(p, &query);
+                    let closer = points
+                        .iter()
+                        .filter(|(q, j)| j != i && squared_euclidean(p, q) < radius)
+                        .count();
+                    closer < k
+                })
+                .map(|(_, i)| *i)
+                .collect();
+            let mut found: Vec<usize> = kdtree
+                .reverse_nearest(&query, k, &squared_euclidean)
+                .unwrap()
+                .into_iter()
+                .map(|(_, i)| *i)
+                .collect();
+            expected.sort_unstable();
+            found.sort_unstable();
+            assert_eq!(found, expected);
+        }
+    }
+}
+
+#[test]
+fn it_orders_by_distance_to_the_query() {
+    let kdtree = KdTree::bulk_load(1, 2, vec![([0f64], 'a'), ([10f64], 'b'), ([11f64], 'c')]).unwrap();
+    assert_eq!(
+        kdtree.reverse_nearest(&[4f64], 1, &squared_euclidean).unwrap(),
+        vec![(16f64, &'a')]
+    );
+    assert_eq!(
+        kdtree.reverse_nearest(&[9f64], 1, &squared_euclidean).unwrap(),
+        vec![(1f64, &'b'), (81f64, &'a')]
+    );
+    assert_eq!(
+        kdtree.reverse_nearest(&[9f64], 2, &squared_euclidean).unwrap(),
+        vec![(1f64, &'b'), (4f64, &'c'), (81f64, &'a')]
+    );
+}
@@ -0,0 +1,51 @@
+#![cfg(feature = "serialize")]
+
+extern crate kdtree;
+extern crate serde_json;
+
+use kdtree::distance::squared_euclidean;
+use kdtree::{ErrorKind, KdTree};
+
+#[test]
+fn it_restores_the_node_structure() {
+    let mut kdtree = KdTree::with_capacity(2, 3);
+    for i in 0..50 {
+        kdtree.add([(i * 7 % 11) as f64, (i * 3 % 13) as f64], i).unwrap();
+    }
+    kdtree.remove(&[0.0, 0.0], &0).unwrap();
+
+    let json = serde_json::to_string(&kdtree).unwrap();
+    let mut restored: KdTree<f64, usize, [f64; 2]> = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(restored.stats(), kdtree.stats());
+    assert_eq!(restored.split_planes(), kdtree.split_planes());
+    assert_eq!(restored.generation(), kdtree.generation());
+    assert!(restored.iter().eq(kdtree.iter()));
+    assert_eq!(restored.coordinate_stats(), kdtree.coordinate_stats());
+    assert_eq!(
+        restored.nearest(&[5.0, 5.0], 4, &squared_euclidean).unwrap(),
+        kdtree.nearest(&[5.0, 5.0], 4, &squared_euclidean).unwrap()
+    );
+
+    // the restored tree keeps growing like the original
+    for i in 50..80 {
+        kdtree.add([i as f64, 0.5], i).unwrap();
+        restored.add([i as f64, 0.5], i).unwrap();
+    }
+    assert_eq!(restored.split_planes(), kdtree.split_planes());
+}
+
+#[test]
+fn it_restores_the_leaf_limit() {
+    let mut kdtree: KdTree<f64, usize, [f64; 2]> = KdTree::with_capacity(2, 3);
+    kdtree.set_leaf_limit(Some(4));
+    kdtree.set_strict(true);
+    for i in 0..4 {
+        kdtree.add([1.0, 1.0], i).unwrap();
+    }
+
+    let json = serde_json::to_string(&kdtree).unwrap();
+    let mut restored: KdTree<f64, usize, [f64; 2]> = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored.stats(), kdtree.stats());
+    assert_eq!(restored.add([1.0, 1.0], 4), Err(ErrorKind::LeafLimitExceeded(4)));
+}
@@ -0,0 +1,98 @@
+#![cfg(feature = "async")]
+
+extern crate kdtree;
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake};
+use std::thread::{self, Thread};
+
+use kdtree::distance::squared_euclidean;
+use kdtree::service::KdTreeService;
+use kdtree::{ErrorKind, KdTree};
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let waker = Arc::new(ThreadWaker(thread::current())).into();
+    let mut cx = Context::from_waker(&waker);
+    let mut future: Pin<Box<F>> = Box::pin(future);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+fn line(len: u32, label: &'static str) -> KdTree<f64, &'static str, [f64; 1]> {
+    let mut kdtree = KdTree::with_capacity(1, 4);
+    for i in 0..len {
+        kdtree.add([f64::from(i)], label).unwrap();
+    }
+    kdtree
+}
+
+#[test]
+fn it_answers_concurrent_queries() {
+    let service = Arc::new(KdTreeService::new(line(100, "old"), squared_euclidean, 3, 8));
+    let clients: Vec<_> = (0..8)
+        .map(|c| {
+            let service = Arc::clone(&service);
+            thread::spawn(move || {
+                for i in 0..25 {
+                    let x = f64::from(c * 10 + i % 10) + 0.25;
+                    let found = block_on(service.nearest(vec![x], 2)).unwrap();
+                    assert_eq!(found.len(), 2);
+                    assert_eq!(found[0].distance(), 0.0625);
+                    assert_eq!(found[0].point()[0], x - 0.25);
+                }
+            })
+        })
+        .collect();
+    for client in clients {
+        client.join().unwrap();
+    }
+    assert!(block_on(service.nearest(vec![0.0, 0.0], 1)).is_err());
+}
+
+#[test]
+fn it_swaps_snapshots_under_running_queries() {
+    let service = KdTreeService::new(line(10, "old"), squared_euclidean, 2, 4);
+    let before = block_on(service.within(vec![0.0], 1.0)).unwrap();
+    let previous = service.swap(line(10, "new"));
+
+    assert_eq!(*before[0].data(), "old");
+    assert!(Arc::ptr_eq(before[0].tree(), &previous));
+    let after = block_on(service.within(vec![0.0], 1.0)).unwrap();
+    assert_eq!(after.len(), 2);
+    assert_eq!(*after[0].data(), "new");
+    assert!(Arc::ptr_eq(after[0].tree(), &service.tree()));
+}
+
+#[test]
+fn it_survives_panicking_queries() {
+    let distance = |a: &[f64], b: &[f64]| {
+        assert!(a[0] >= 0.0, "negative query");
+        squared_euclidean(a, b)
+    };
+    // a single worker answering all queries of a batch
+    let service = KdTreeService::new(line(10, "line"), distance, 1, 8);
+    let queries = vec![
+        service.nearest(vec![1.0], 1),
+        service.nearest(vec![-1.0], 1),
+        service.within(vec![2.0], 1.0),
+    ];
+    let answers: Vec<_> = queries.into_iter().map(block_on).collect();
+    assert_eq!(answers[0].as_ref().unwrap().len(), 1);
+    assert_eq!(answers[1].as_ref().err(), Some(&ErrorKind::QueryPanicked));
+    assert_eq!(answers[2].as_ref().unwrap().len(), 3);
+    assert_eq!(block_on(service.nearest(vec![5.0], 2)).unwrap().len(), 2);
+}
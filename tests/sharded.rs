@@ -0,0 +1,68 @@
+extern crate kdtree;
+
+use kdtree::distance::squared_euclidean;
+use kdtree::ErrorKind;
+use kdtree::ShardedKdTree;
+
+fn layers() -> ShardedKdTree<&'static str, f64, usize, [f64; 2]> {
+    let mut sharded = ShardedKdTree::with_capacity(2, 2);
+    sharded.add("roads", [0.0, 0.0], 0).unwrap();
+    sharded.add("roads", [3.0, 3.0], 1).unwrap();
+    sharded.add("shops", [1.0, 1.0], 2).unwrap();
+    sharded.add("shops", [5.0, 5.0], 3).unwrap();
+    sharded.add("parks", [2.0, 2.0], 4).unwrap();
+    sharded
+}
+
+#[test]
+fn it_queries_one_shard_or_all() {
+    let sharded = layers();
+    assert_eq!(sharded.size(), 5);
+    let mut keys: Vec<_> = sharded.keys().cloned().collect();
+    keys.sort();
+    assert_eq!(keys, vec!["parks", "roads", "shops"]);
+
+    assert_eq!(
+        sharded
+            .nearest_in(&"shops", &[0.0, 0.0], 1, &squared_euclidean)
+            .unwrap(),
+        vec![(2.0, &2)]
+    );
+    assert!(sharded
+        .nearest_in(&"rivers", &[0.0, 0.0], 1, &squared_euclidean)
+        .unwrap()
+        .is_empty());
+    assert_eq!(
+        sharded.nearest_any(&[0.0, 0.0], 3, &squared_euclidean).unwrap(),
+        vec![(0.0, &"roads", &0), (2.0, &"shops", &2), (8.0, &"parks", &4)]
+    );
+    assert_eq!(
+        sharded
+            .within_in(&"roads", &[0.0, 0.0], 18.0, &squared_euclidean)
+            .unwrap(),
+        vec![(0.0, &0), (18.0, &1)]
+    );
+    assert_eq!(
+        sharded.within_any(&[4.0, 4.0], 2.0, &squared_euclidean).unwrap().len(),
+        2
+    );
+}
+
+#[test]
+fn it_validates_points_and_drops_empty_shards() {
+    let mut sharded = layers();
+    assert_eq!(
+        sharded.add("rivers", [f64::NAN, 0.0], 5),
+        Err(ErrorKind::NonFiniteCoordinate)
+    );
+    assert!(sharded.shard(&"rivers").is_none());
+    assert_eq!(
+        sharded.nearest_in(&"rivers", &[0.0], 1, &squared_euclidean),
+        Err(ErrorKind::WrongDimension)
+    );
+
+    assert_eq!(sharded.remove(&"parks", &[2.0, 2.0], &4).unwrap(), 1);
+    assert!(sharded.shard(&"parks").is_none());
+    assert_eq!(sharded.remove(&"parks", &[2.0, 2.0], &4).unwrap(), 0);
+    assert_eq!(sharded.size(), 4);
+}
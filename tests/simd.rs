@@ -0,0 +1,55 @@
+extern crate kdtree;
+extern crate rand;
+
+use kdtree::distance::{squared_euclidean, squared_euclidean_simd};
+use kdtree::KdTree;
+use rand::Rng;
+
+#[test]
+fn it_matches_the_scalar_distance() {
+    let mut rng = rand::thread_rng();
+    for dims in 0..40 {
+        // small integers sum up exactly in any order
+        let a: Vec<f64> = (0..dims).map(|_| rng.gen_range(-50..50) as f64).collect();
+        let b: Vec<f64> = (0..dims).map(|_| rng.gen_range(-50..50) as f64).collect();
+        assert_eq!(squared_euclidean_simd(&a, &b), squared_euclidean(&a, &b));
+
+        let a: Vec<f32> = a.iter().map(|&x| x as f32).collect();
+        let b: Vec<f32> = b.iter().map(|&x| x as f32).collect();
+        assert_eq!(squared_euclidean_simd(&a, &b), squared_euclidean(&a, &b));
+    }
+}
+
+#[test]
+fn it_finds_the_same_neighbours() {
+    let mut rng = rand::thread_rng();
+    let mut kdtree = KdTree::with_capacity(20, 32);
+    for i in 0..500 {
+        let point: Vec<f64> = (0..20).map(|_| rng.gen_range(0..100) as f64).collect();
+        kdtree.add(point, i).unwrap();
+    }
+    let query: Vec<f64> = (0..20).map(|_| rng.gen_range(0..100) as f64).collect();
+    let scalar = kdtree.nearest(&query, 10, &squared_euclidean).unwrap();
+    let simd = kdtree.nearest(&query, 10, &squared_euclidean_simd).unwrap();
+    assert_eq!(
+        simd.iter().map(|r| r.0).collect::<Vec<_>>(),
+        scalar.iter().map(|r| r.0).collect::<Vec<_>>()
+    );
+}
+
+#[cfg(feature = "simd")]
+#[test]
+fn it_sums_in_lanes_with_the_feature() {
+    let mut rng = rand::thread_rng();
+    for dims in 0..40 {
+        let a: Vec<f32> = (0..dims).map(|_| rng.gen()).collect();
+        let b: Vec<f32> = (0..dims).map(|_| rng.gen()).collect();
+        let plain = a.iter().zip(&b).fold(0.0, |sum, (x, y)| sum + (x - y) * (x - y));
+        let expected = if dims >= 8 {
+            squared_euclidean_simd(&a, &b)
+        } else {
+            plain
+        };
+        assert_eq!(squared_euclidean(&a, &b), expected);
+    }
+}
@@ -0,0 +1,37 @@
+extern crate kdtree;
+extern crate rand;
+
+use kdtree::KdTree;
+
+#[test]
+fn it_finds_the_pareto_front() {
+    let points = vec![
+        ([1f64, 5f64], 'a'),
+        ([2f64, 2f64], 'b'),
+        ([5f64, 1f64], 'c'),
+        ([3f64, 3f64], 'd'),
+        ([2f64, 6f64], 'e'),
+        ([2f64, 2f64], 'f'),
+        ([1f64, 7f64], 'g'),
+    ];
+    let kdtree = KdTree::bulk_load(2, 2, points).unwrap();
+    let mut skyline: Vec<char> = kdtree.skyline().into_iter().map(|(_, d)| *d).collect();
+    skyline.sort_unstable();
+    assert_eq!(skyline, vec!['a', 'b', 'c', 'f']);
+}
+
+#[test]
+fn it_matches_brute_force() {
+    let points: Vec<([f64; 3], usize)> = (0..500).map(|i| (rand::random(), i)).collect();
+    let kdtree = KdTree::bulk_load(3, 8, points.clone()).unwrap();
+    let dominates = |a: &[f64; 3], b: &[f64; 3]| a.iter().zip(b).all(|(x, y)| x <= y) && a != b;
+    let mut expected: Vec<usize> = points
+        .iter()
+        .filter(|(p, _)| !points.iter().any(|(q, _)| dominates(q, p)))
+        .map(|(_, i)| *i)
+        .collect();
+    let mut found: Vec<usize> = kdtree.skyline().into_iter().map(|(_, i)| *i).collect();
+    expected.sort_unstable();
+    found.sort_unstable();
+    assert_eq!(found, expected);
+}
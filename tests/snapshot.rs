@@ -0,0 +1,80 @@
+extern crate kdtree;
+
+use kdtree::distance::squared_euclidean;
+use kdtree::KdTree;
+
+static ONE_DIMENSION: &str = include_str!("snapshots/one_dimension.txt");
+
+fn one_dimension() -> KdTree<f64, i32, [f64; 1]> {
+    let mut kdtree = KdTree::with_capacity(1, 2);
+    kdtree.add([0f64], 1).unwrap();
+    kdtree.add([100f64], 2).unwrap();
+    kdtree.add([45f64], 3).unwrap();
+    kdtree.add([55f64], 4).unwrap();
+    kdtree
+}
+
+#[test]
+fn it_matches_the_golden_file() {
+    assert_eq!(one_dimension().to_snapshot_string(), ONE_DIMENSION);
+}
+
+#[test]
+fn it_round_trips() {
+    let restored: KdTree<f64, i32, [f64; 1]> = KdTree::from_snapshot_str(ONE_DIMENSION).unwrap();
+    assert_eq!(restored.to_snapshot_string(), ONE_DIMENSION);
+    assert_eq!(
+        restored.nearest(&[51f64], 4, &squared_euclidean).unwrap(),
+        one_dimension().nearest(&[51f64], 4, &squared_euclidean).unwrap()
+    );
+
+    let empty: KdTree<f64, String, Vec<f64>> = KdTree::new(3);
+    let restored: KdTree<f64, String, Vec<f64>> = KdTree::from_snapshot_str(&empty.to_snapshot_string()).unwrap();
+    assert_eq!(restored.size(), 0);
+    assert_eq!(restored.to_snapshot_string(), empty.to_snapshot_string());
+}
+
+#[test]
+fn it_reports_the_offending_line() {
+    let broken = ONE_DIMENSION.replace("- 45 => 3", "- 45,1 => 3");
+    let err = KdTree::<f64, i32, [f64; 1]>::from_snapshot_str(&broken).unwrap_err();
+    assert_eq!(err.line, 5);
+
+    let err = KdTree::<f64, i32, [f64; 1]>::from_snapshot_str("kdtree snapshot v2").unwrap_err();
+    assert_eq!(err.line, 1);
+}
+
+#[test]
+fn it_keeps_the_leaf_limit() {
+    let mut kdtree = one_dimension();
+    kdtree.set_leaf_limit(Some(3));
+    kdtree.set_strict(true);
+    let snapshot = kdtree.to_snapshot_string();
+    assert!(snapshot.starts_with("kdtree snapshot v1 dimensions=1 capacity=2 leaf_limit=3 strict=true\n"));
+
+    let mut restored: KdTree<f64, i32, [f64; 1]> = KdTree::from_snapshot_str(&snapshot).unwrap();
+    assert_eq!(restored.stats().leaf_limit, Some(3));
+    assert_eq!(restored.to_snapshot_string(), snapshot);
+    for i in 0..3 {
+        restored.add([7f64], i).unwrap();
+    }
+    assert!(restored.add([7f64], 3).is_err());
+}
+
+#[test]
+fn it_numbers_restored_nodes() {
+    let mut kdtree = KdTree::with_capacity(2, 2);
+    for i in 0..20 {
+        kdtree.add([(i * 7 % 11) as f64, (i * 3 % 13) as f64], i).unwrap();
+    }
+    let restored: KdTree<f64, i32, [f64; 2]> = KdTree::from_snapshot_str(&kdtree.to_snapshot_string()).unwrap();
+
+    assert_eq!(restored.generation(), restored.split_planes().len() as u64);
+    let mut ids: Vec<u64> = restored.split_planes().iter().map(|plane| plane.id).collect();
+    ids.sort_unstable();
+    ids.dedup();
+    assert_eq!(ids.len(), restored.split_planes().len());
+    let adjacency = restored.leaf_adjacency();
+    assert_eq!(adjacency.len(), kdtree.leaf_adjacency().len());
+    assert!(adjacency.iter().all(|&(a, b)| a < b));
+}
@@ -0,0 +1,68 @@
+extern crate kdtree;
+
+use kdtree::{ErrorKind, SphereKdTree};
+
+fn degrees<'a>(found: Vec<(f64, &&'a str)>) -> Vec<(f64, &'a str)> {
+    found
+        .into_iter()
+        .map(|(angle, data)| ((angle.to_degrees() * 1e6).round() / 1e6, *data))
+        .collect()
+}
+
+#[test]
+fn it_wraps_around_the_antimeridian() {
+    let mut tree = SphereKdTree::with_capacity(2);
+    tree.add(0.0, 179.5, "east").unwrap();
+    tree.add(0.0, -179.5, "west").unwrap();
+    tree.add(0.0, 170.0, "far east").unwrap();
+    tree.add(0.0, 0.0, "origin").unwrap();
+
+    assert_eq!(
+        degrees(tree.nearest_angular(0.0, 180.0, 3).unwrap()),
+        vec![(0.5, "east"), (0.5, "west"), (10.0, "far east")]
+    );
+    assert_eq!(
+        degrees(tree.within_angle(0.0, -179.0, 1.0f64.to_radians()).unwrap()),
+        vec![(0.5, "west")]
+    );
+}
+
+#[test]
+fn it_is_correct_near_the_poles() {
+    let mut tree = SphereKdTree::new();
+    tree.add(89.0, 0.0, "a").unwrap();
+    tree.add(89.0, 180.0, "b").unwrap();
+    tree.add(80.0, 0.0, "c").unwrap();
+
+    assert_eq!(
+        degrees(tree.nearest_angular(89.0, 0.0, 3).unwrap()),
+        vec![(0.0, "a"), (2.0, "b"), (9.0, "c")]
+    );
+}
+
+#[test]
+fn it_agrees_with_haversine() {
+    use kdtree::distance::haversine;
+
+    let places: [(f64, f64); 5] = [(52.5, 13.4), (-33.9, 151.2), (89.9, -45.0), (0.0, 179.9), (40.7, -74.0)];
+    let mut tree = SphereKdTree::new();
+    for (i, &(lat, lon)) in places.iter().enumerate() {
+        tree.add(lat, lon, i).unwrap();
+    }
+    for &(lat, lon) in &[(48.9f64, 2.4f64), (-0.5, -179.8), (85.0, 100.0)] {
+        for (angle, &i) in tree.nearest_angular(lat, lon, places.len()).unwrap() {
+            let (other_lat, other_lon) = places[i];
+            assert!((angle - haversine(&[lat, lon], &[other_lat, other_lon])).abs() < 1e-9);
+        }
+    }
+}
+
+#[test]
+fn it_rejects_invalid_angles() {
+    let mut tree = SphereKdTree::new();
+    tree.add(0.0, 0.0, "origin").unwrap();
+    assert_eq!(tree.within_angle(0.0, 0.0, -0.1), Err(ErrorKind::InvalidAngle));
+    assert_eq!(tree.within_angle(0.0, 0.0, f64::NAN), Err(ErrorKind::InvalidAngle));
+    assert_eq!(tree.within_angle(0.0, 0.0, f64::INFINITY), Err(ErrorKind::InvalidAngle));
+    assert_eq!(tree.within_angle(0.0, 0.0, 0.0).unwrap().len(), 1);
+}
@@ -0,0 +1,118 @@
+extern crate kdtree;
+
+use kdtree::TreeStats;
+use kdtree::{ErrorKind, KdTree};
+
+#[test]
+fn it_reports_the_shape_of_the_tree() {
+    let mut kdtree = KdTree::with_capacity(1, 2);
+    assert_eq!(
+        kdtree.stats(),
+        TreeStats {
+            size: 0,
+            dimensions: 1,
+            capacity: 2,
+            depth: 0,
+            stems: 0,
+            leaves: 1,
+            empty_leaves: 1,
+            max_leaf_size: 0,
+            unsplittable_leaves: 0,
+            leaf_limit: None,
+            oversized_leaves: 0,
+        }
+    );
+
+    for (i, x) in [0f64, 100f64, 45f64, 55f64].iter().enumerate() {
+        kdtree.add([*x], i).unwrap();
+    }
+    let stats = kdtree.stats();
+    assert_eq!(stats.size, 4);
+    assert_eq!(stats.leaves, stats.stems + 1);
+    assert!(stats.depth >= 1);
+    assert!(stats.max_leaf_size <= 2);
+}
+
+#[test]
+fn it_counts_unsplittable_leaves() {
+    let mut kdtree = KdTree::with_capacity(2, 2);
+    for i in 0..4 {
+        kdtree.add([1f64, 1f64], i).unwrap();
+    }
+    // the leaf failed to split twice, but it is one leaf
+    let stats = kdtree.stats();
+    assert_eq!(stats.unsplittable_leaves, 1);
+    assert_eq!(stats.max_leaf_size, 4);
+
+    // the identical points move to a new leaf, which cannot be split either
+    kdtree.add([2f64, 1f64], 4).unwrap();
+    assert_eq!(kdtree.stats().unsplittable_leaves, 1);
+    assert_eq!(kdtree.stats().stems, 1);
+
+    kdtree.add([5f64, 5f64], 5).unwrap();
+    kdtree.add([5f64, 5f64], 6).unwrap();
+    kdtree.add([5f64, 5f64], 7).unwrap();
+    assert_eq!(kdtree.stats().unsplittable_leaves, 2);
+}
+
+#[test]
+fn it_summarizes_coordinates() {
+    let mut kdtree = KdTree::with_capacity(2, 2);
+    assert!(kdtree.coordinate_stats().is_empty());
+    for i in 0..20 {
+        let x = f64::from(i);
+        kdtree.add([1e6 + x, -x * x], i).unwrap();
+    }
+    kdtree.remove(&[1e6 + 19.0, -361.0], &19).unwrap();
+    let _ = kdtree.extract_if(|_, &mut data| data == 0);
+
+    let points: Vec<[f64; 2]> = kdtree.iter().map(|(p, _)| *p).collect();
+    let n = points.len() as f64;
+    let stats = kdtree.coordinate_stats();
+    assert_eq!(stats.len(), 2);
+    for (dim, stats) in stats.iter().enumerate() {
+        let values: Vec<f64> = points.iter().map(|p| p[dim]).collect();
+        let mean = values.iter().sum::<f64>() / n;
+        let variance = values.iter().map(|v| (v - mean) * (v - mean)).sum::<f64>() / n;
+        assert_eq!(stats.min, values.iter().cloned().fold(f64::INFINITY, f64::min));
+        assert_eq!(stats.max, values.iter().cloned().fold(f64::NEG_INFINITY, f64::max));
+        assert!((stats.mean - mean).abs() < 1e-6);
+        assert!((stats.variance - variance).abs() < 1e-6 * variance);
+    }
+    assert_eq!(stats[0].min, 1e6 + 1.0);
+    assert_eq!(stats[0].max, 1e6 + 18.0);
+}
+
+#[test]
+fn it_reports_leaves_beyond_the_leaf_limit() {
+    let mut kdtree = KdTree::with_capacity(2, 2);
+    kdtree.set_leaf_limit(Some(4));
+    for i in 0..6 {
+        kdtree.add([1.0, 1.0], i).unwrap();
+    }
+    kdtree.add([5.0, 5.0], 6).unwrap();
+    let stats = kdtree.stats();
+    assert_eq!(stats.leaf_limit, Some(4));
+    assert_eq!(stats.oversized_leaves, 1);
+
+    kdtree.set_leaf_limit(None);
+    assert_eq!(kdtree.stats().oversized_leaves, 0);
+}
+
+#[test]
+fn strict_trees_reject_points_beyond_the_leaf_limit() {
+    let mut kdtree = KdTree::with_capacity(2, 2);
+    kdtree.set_leaf_limit(Some(4));
+    kdtree.set_strict(true);
+    for i in 0..4 {
+        kdtree.add([1.0, 1.0], i).unwrap();
+    }
+    assert_eq!(kdtree.add([1.0, 1.0], 4), Err(ErrorKind::LeafLimitExceeded(4)));
+    assert_eq!(kdtree.size(), 4);
+
+    // other points still split off the full leaf
+    kdtree.add([1.0, 2.0], 5).unwrap();
+    kdtree.add([3.0, 1.0], 6).unwrap();
+    assert_eq!(kdtree.size(), 6);
+    assert_eq!(kdtree.stats().oversized_leaves, 0);
+}
@@ -0,0 +1,63 @@
+extern crate kdtree;
+
+use kdtree::{KdTree, Tolerance};
+
+fn tree() -> KdTree<f64, char, [f64; 2]> {
+    let mut kdtree = KdTree::with_capacity(2, 1);
+    kdtree.add([0.1 + 0.2, 1.0], 'a').unwrap();
+    kdtree.add([1000.0, 1.0], 'b').unwrap();
+    kdtree.add([1000.0, 1.0], 'c').unwrap();
+    kdtree
+}
+
+#[test]
+fn it_compares_coordinates_up_to_a_tolerance() {
+    assert!(Tolerance::Exact.matches(1.0, 1.0));
+    assert!(!Tolerance::Exact.matches(0.1 + 0.2, 0.3));
+    assert!(Tolerance::Absolute(1e-9).matches(0.1 + 0.2, 0.3));
+    assert!(!Tolerance::Absolute(1e-9).matches(1000.0, 1000.001));
+    assert!(Tolerance::Relative(1e-5).matches(1000.0, 1000.001));
+    assert!(!Tolerance::Relative(1e-5).matches(0.0, 1e-9));
+}
+
+#[test]
+fn it_finds_points_up_to_a_tolerance() {
+    let kdtree = tree();
+    assert!(!kdtree.contains(&[0.3, 1.0], Tolerance::Exact).unwrap());
+    assert!(kdtree.contains(&[0.3, 1.0], Tolerance::Absolute(1e-9)).unwrap());
+    assert!(!kdtree.contains(&[0.3, 1.1], Tolerance::Absolute(1e-9)).unwrap());
+    assert_eq!(
+        kdtree
+            .get_matching(&[1000.001, 1.0], Tolerance::Relative(1e-5))
+            .unwrap()
+            .len(),
+        2
+    );
+    assert!(kdtree.contains(&[0.3], Tolerance::Exact).is_err());
+}
+
+#[test]
+fn it_removes_points_up_to_a_tolerance() {
+    let mut kdtree = tree();
+    assert_eq!(kdtree.remove_matching(&[0.3, 1.0], &'a', Tolerance::Exact).unwrap(), 0);
+    assert_eq!(
+        kdtree
+            .remove_matching(&[0.3, 1.0], &'b', Tolerance::Absolute(1e-9))
+            .unwrap(),
+        0
+    );
+    assert_eq!(
+        kdtree
+            .remove_matching(&[0.3, 1.0], &'a', Tolerance::Absolute(1e-9))
+            .unwrap(),
+        1
+    );
+    assert_eq!(
+        kdtree
+            .remove_matching(&[999.999, 1.0], &'c', Tolerance::Relative(1e-5))
+            .unwrap(),
+        1
+    );
+    assert_eq!(kdtree.size(), 1);
+    assert_eq!(kdtree.iter().map(|(_, &d)| d).collect::<Vec<_>>(), vec!['b']);
+}
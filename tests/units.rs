@@ -0,0 +1,45 @@
+#![cfg(feature = "uom")]
+extern crate kdtree;
+extern crate uom;
+
+use kdtree::units::LengthKdTree;
+use kdtree::ErrorKind;
+use uom::si::area::square_meter;
+use uom::si::f64::Length;
+use uom::si::length::{kilometer, meter};
+
+#[test]
+fn it_converts_between_units() {
+    let mut tree = LengthKdTree::with_capacity(2, 2);
+    tree.add(&[Length::new::<kilometer>(1.0), Length::new::<meter>(0.0)], "a")
+        .unwrap();
+    tree.add(&[Length::new::<meter>(10.0), Length::new::<meter>(0.0)], "b")
+        .unwrap();
+    tree.add(&[Length::new::<meter>(0.0), Length::new::<kilometer>(2.0)], "c")
+        .unwrap();
+
+    let origin = [Length::new::<meter>(0.0), Length::new::<meter>(0.0)];
+    let found = tree.nearest(&origin, 2).unwrap();
+    assert_eq!(found[0].1, &"b");
+    assert_eq!(found[0].0.get::<square_meter>(), 100.0);
+    assert_eq!(found[1].1, &"a");
+    assert_eq!(found[1].0.get::<square_meter>(), 1_000_000.0);
+
+    assert_eq!(tree.within(&origin, Length::new::<kilometer>(1.5)).unwrap().len(), 2);
+}
+
+#[test]
+fn it_removes_points_and_rejects_negative_radii() {
+    let mut tree = LengthKdTree::new(1);
+    tree.add(&[Length::new::<meter>(5.0)], 'a').unwrap();
+    tree.add(&[Length::new::<meter>(7.0)], 'b').unwrap();
+
+    let origin = [Length::new::<meter>(0.0)];
+    assert_eq!(
+        tree.within(&origin, Length::new::<meter>(-10.0)),
+        Err(ErrorKind::InvalidRadius)
+    );
+    assert_eq!(tree.remove(&[Length::new::<kilometer>(0.005)], &'a'), Ok(1));
+    assert_eq!(tree.size(), 1);
+    assert_eq!(tree.nearest(&origin, 1).unwrap()[0].1, &'b');
+}
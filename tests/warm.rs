@@ -0,0 +1,19 @@
+extern crate kdtree;
+
+use kdtree::KdTree;
+
+#[test]
+fn it_reads_every_coordinate() {
+    let empty: KdTree<f64, usize, [f64; 2]> = KdTree::new(2);
+    assert_eq!(empty.warm_up(), 0);
+
+    let mut kdtree = KdTree::with_capacity(2, 2);
+    for i in 0..10 {
+        kdtree.add([f64::from(i), 0.0], i).unwrap();
+    }
+    let stats = kdtree.stats();
+    let bounds = 2 * 2 * (stats.stems + stats.leaves);
+    assert_eq!(kdtree.warm_up(), 10 * 2 + bounds);
+    #[cfg(feature = "rayon")]
+    assert_eq!(kdtree.par_warm_up(), kdtree.warm_up());
+}
@@ -0,0 +1,29 @@
+extern crate kdtree;
+extern crate rand;
+
+use kdtree::distance::weighted_squared_euclidean;
+use kdtree::KdTree;
+
+#[test]
+fn it_matches_brute_force() {
+    let distance = weighted_squared_euclidean(vec![1.0, 250.0, 0.0]);
+    let points: Vec<([f64; 3], usize)> = (0..500).map(|i| (rand::random(), i)).collect();
+    let kdtree = KdTree::bulk_load(3, 4, points.clone()).unwrap();
+
+    for query in &[[0.5, 0.5, 0.5], [0.0, 1.0, 0.3], [2.0, -1.0, 9.0]] {
+        let mut expected: Vec<f64> = points.iter().map(|(p, _)| distance(query, p)).collect();
+        expected.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        let found = kdtree.nearest(query, 8, &distance).unwrap();
+        assert_eq!(found.iter().map(|r| r.0).collect::<Vec<_>>(), expected[..8].to_vec());
+
+        let radius = expected[20];
+        assert_eq!(kdtree.within(query, radius, &distance).unwrap().len(), 21);
+    }
+}
+
+#[test]
+#[should_panic(expected = "weights must be finite and zero or positive")]
+fn it_rejects_negative_weights() {
+    let _ = weighted_squared_euclidean(vec![1.0, -1.0]);
+}